@@ -48,6 +48,13 @@ pub enum AccountCmd {
         #[clap(subcommand)]
         template: AccountTemplate,
     },
+    /// Recover the next account derived from the client's master seed. Accounts must be recovered
+    /// with the same templates and in the same order in which they were created
+    #[clap(short_flag = 'r')]
+    Recover {
+        #[clap(subcommand)]
+        template: AccountTemplate,
+    },
     /// Import accounts from binary files (with .mac extension)
     #[clap(short_flag = 'i')]
     Import {
@@ -109,8 +116,46 @@ impl From<&AccountStorageMode> for accounts::AccountStorageMode {
     }
 }
 
+impl TryFrom<&AccountTemplate> for accounts::AccountTemplate {
+    type Error = String;
+
+    fn try_from(template: &AccountTemplate) -> Result<Self, Self::Error> {
+        let client_template = match template {
+            AccountTemplate::BasicImmutable { storage_type: storage_mode } => {
+                accounts::AccountTemplate::BasicWallet {
+                    mutable_code: false,
+                    storage_mode: storage_mode.into(),
+                }
+            },
+            AccountTemplate::BasicMutable { storage_type: storage_mode } => {
+                accounts::AccountTemplate::BasicWallet {
+                    mutable_code: true,
+                    storage_mode: storage_mode.into(),
+                }
+            },
+            AccountTemplate::FungibleFaucet {
+                token_symbol,
+                decimals,
+                max_supply,
+                storage_type: storage_mode,
+            } => accounts::AccountTemplate::FungibleFaucet {
+                token_symbol: TokenSymbol::new(token_symbol)
+                    .map_err(|err| format!("error: token symbol is invalid: {}", err))?,
+                decimals: *decimals,
+                max_supply: *max_supply,
+                storage_mode: storage_mode.into(),
+            },
+            AccountTemplate::NonFungibleFaucet { storage_type: _ } => {
+                return Err("error: non-fungible faucets are not supported yet".to_string())
+            },
+        };
+
+        Ok(client_template)
+    }
+}
+
 impl AccountCmd {
-    pub fn execute<N: NodeRpcClient, R: FeltRng, S: Store>(
+    pub async fn execute<N: NodeRpcClient, R: FeltRng, S: Store>(
         &self,
         mut client: Client<N, R, S>,
     ) -> Result<(), String> {
@@ -119,35 +164,14 @@ impl AccountCmd {
                 list_accounts(client)?;
             },
             AccountCmd::New { template } => {
-                let client_template = match template {
-                    AccountTemplate::BasicImmutable { storage_type: storage_mode } => {
-                        accounts::AccountTemplate::BasicWallet {
-                            mutable_code: false,
-                            storage_mode: storage_mode.into(),
-                        }
-                    },
-                    AccountTemplate::BasicMutable { storage_type: storage_mode } => {
-                        accounts::AccountTemplate::BasicWallet {
-                            mutable_code: true,
-                            storage_mode: storage_mode.into(),
-                        }
-                    },
-                    AccountTemplate::FungibleFaucet {
-                        token_symbol,
-                        decimals,
-                        max_supply,
-                        storage_type: storage_mode,
-                    } => accounts::AccountTemplate::FungibleFaucet {
-                        token_symbol: TokenSymbol::new(token_symbol)
-                            .map_err(|err| format!("error: token symbol is invalid: {}", err))?,
-                        decimals: *decimals,
-                        max_supply: *max_supply,
-                        storage_mode: storage_mode.into(),
-                    },
-                    AccountTemplate::NonFungibleFaucet { storage_type: _ } => todo!(),
-                };
+                let client_template: accounts::AccountTemplate = template.try_into()?;
                 let (_new_account, _account_seed) = client.new_account(client_template)?;
             },
+            AccountCmd::Recover { template } => {
+                let client_template: accounts::AccountTemplate = template.try_into()?;
+                let account = client.recover_account(client_template).await?;
+                println!("Recovered account {}", account.id());
+            },
             AccountCmd::Show { id, keys, vault, storage, code } => {
                 let account_id: AccountId = AccountId::from_hex(id)
                     .map_err(|_| "Input number was not a valid Account Id")?;
@@ -0,0 +1,14 @@
+use miden_client::{
+    client::{rpc::NodeRpcClient, Client},
+    store::Store,
+};
+use miden_objects::crypto::rand::FeltRng;
+
+pub fn compact_store<N: NodeRpcClient, R: FeltRng, S: Store>(
+    mut client: Client<N, R, S>,
+) -> Result<(), String> {
+    let summary = client.compact_store()?;
+    println!("Pruned block headers: {}", summary.pruned_block_headers);
+    println!("Pruned chain MMR nodes: {}", summary.pruned_chain_mmr_nodes);
+    Ok(())
+}
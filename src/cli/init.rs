@@ -1,28 +1,91 @@
 use std::{
     fs::File,
     io::{self, Write},
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
-use miden_client::config::{ClientConfig, Endpoint};
+use miden_client::{
+    client::ClientBuilder,
+    config::{ClientConfig, ClientConfigFile, Endpoint},
+};
+use miden_objects::{Digest, Felt, Word};
+use rand::Rng;
+
+use super::{load_config, load_config_file};
 
-pub(crate) fn initialize_client(config_file_path: PathBuf) -> Result<(), String> {
+/// Creates the client configuration and the store.
+///
+/// Without a `profile`, a new config file with top-level `[rpc]` and `[store]` sections is
+/// created. Otherwise, the profile is added to the config file (creating it if needed) with a
+/// store of its own, and set as the default profile if the file had none.
+pub(crate) fn initialize_client(
+    config_file_path: PathBuf,
+    profile: Option<&str>,
+) -> Result<(), String> {
     let mut client_config = ClientConfig::default();
+    if let Some(profile) = profile {
+        client_config.store.database_filepath = format!("{profile}.sqlite3");
+    }
 
     initialize_rpc_config(&mut client_config)?;
     initialize_store_config(&mut client_config)?;
+    let (master_seed, rng_epoch) = initialize_master_seed()?;
+
+    let client_config = match profile {
+        None => {
+            write_config_file(&config_file_path, &client_config.into(), true)?;
+            load_config(&config_file_path, None)?
+        },
+        Some(profile) => {
+            let file_exists = config_file_path.exists();
+            let mut config_file = if file_exists {
+                load_config_file(&config_file_path)?
+            } else {
+                ClientConfigFile::default()
+            };
+
+            if config_file.profiles.contains_key(profile) {
+                return Err(format!("Profile `{profile}` already exists"));
+            }
+            if config_file.default_profile.is_none() && config_file.rpc.is_none() {
+                config_file.default_profile = Some(profile.to_string());
+            }
+            config_file.profiles.insert(profile.to_string(), client_config);
+            config_file.validate()?;
+
+            write_config_file(&config_file_path, &config_file, !file_exists)?;
+            load_config(&config_file_path, Some(profile))?
+        },
+    };
+
+    let mut client = ClientBuilder::from_config(&client_config)?.build()?;
+    client.set_master_seed(master_seed, rng_epoch)?;
+
+    Ok(())
+}
 
-    let config_as_toml_string = toml::to_string_pretty(&client_config)
+fn write_config_file(
+    config_file_path: &Path,
+    config_file: &ClientConfigFile,
+    create_new: bool,
+) -> Result<(), String> {
+    let config_as_toml_string = toml::to_string_pretty(config_file)
         .map_err(|err| format!("error formatting config: {err}"))?;
 
-    println!("Creating config file at: {:?}", config_file_path);
+    if create_new {
+        println!("Creating config file at: {:?}", config_file_path);
+    } else {
+        println!("Updating config file at: {:?}", config_file_path);
+    }
     let mut file_handle = File::options()
         .write(true)
-        .create_new(true)
+        .truncate(true)
+        .create(!create_new)
+        .create_new(create_new)
         .open(config_file_path)
         .map_err(|err| format!("error opening the file: {err}"))?;
     file_handle
-        .write(config_as_toml_string.as_bytes())
+        .write_all(config_as_toml_string.as_bytes())
         .map_err(|err| format!("error writing to file: {err}"))?;
 
     Ok(())
@@ -61,7 +124,7 @@ fn initialize_rpc_config(client_config: &mut ClientConfig) -> Result<(), String>
 }
 
 fn initialize_store_config(client_config: &mut ClientConfig) -> Result<(), String> {
-    println!("Sqlite file path (default: ./store.sqlite3):");
+    println!("Sqlite file path (default: ./{}):", client_config.store.database_filepath);
     let mut database_filepath: String = String::new();
     io::stdin().read_line(&mut database_filepath).expect("Should read line");
     database_filepath = database_filepath.trim().to_string();
@@ -71,3 +134,41 @@ fn initialize_store_config(client_config: &mut ClientConfig) -> Result<(), Strin
 
     Ok(())
 }
+
+fn initialize_master_seed() -> Result<(Word, u32), String> {
+    println!("Master seed as a hex-encoded word (default: generate a new one):");
+    let mut master_seed: String = String::new();
+    io::stdin().read_line(&mut master_seed).expect("Should read line");
+    master_seed = master_seed.trim().to_string();
+
+    if !master_seed.is_empty() {
+        let master_seed = Digest::try_from(master_seed)
+            .map_err(|err| format!("Error parsing master seed: {err}"))?;
+
+        println!(
+            "Random coin epoch (default: 0). When restoring a wallet, use one more than the last \
+            epoch used with this seed:"
+        );
+        let mut rng_epoch: String = String::new();
+        io::stdin().read_line(&mut rng_epoch).expect("Should read line");
+        rng_epoch = rng_epoch.trim().to_string();
+        let rng_epoch: u32 = if !rng_epoch.is_empty() {
+            rng_epoch
+                .parse()
+                .map_err(|err| format!("Error parsing random coin epoch: {err}"))?
+        } else {
+            0
+        };
+
+        return Ok((master_seed.into(), rng_epoch));
+    }
+
+    let mut rng = rand::thread_rng();
+    let master_seed: Word = rng.gen::<[u64; 4]>().map(Felt::new);
+    println!(
+        "Generated master seed {}. Keep it safe, it is needed to recover your accounts.",
+        Digest::from(master_seed).to_hex()
+    );
+
+    Ok((master_seed, 0))
+}
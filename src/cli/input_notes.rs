@@ -26,6 +26,8 @@ pub enum NoteFilter {
     Pending,
     Committed,
     Consumed,
+    /// Private notes committed for the client's tags whose details haven't been imported
+    Expected,
 }
 
 #[derive(Debug, Parser, Clone)]
@@ -78,10 +80,18 @@ pub enum InputNotes {
         #[clap()]
         filename: PathBuf,
     },
+
+    /// Ask the node whether the input note for the specified note ID was spent, without syncing
+    #[clap(short_flag = 'c')]
+    Check {
+        /// Note ID of the input note to check
+        #[clap()]
+        id: String,
+    },
 }
 
 impl InputNotes {
-    pub fn execute<N: NodeRpcClient, R: FeltRng, S: Store>(
+    pub async fn execute<N: NodeRpcClient, R: FeltRng, S: Store>(
         &self,
         mut client: Client<N, R, S>,
     ) -> Result<(), String> {
@@ -91,6 +101,7 @@ impl InputNotes {
                     Some(NoteFilter::Committed) => ClientNoteFilter::Committed,
                     Some(NoteFilter::Consumed) => ClientNoteFilter::Consumed,
                     Some(NoteFilter::Pending) => ClientNoteFilter::Pending,
+                    Some(NoteFilter::Expected) => return list_expected_notes(client),
                     None => ClientNoteFilter::All,
                 };
 
@@ -104,9 +115,12 @@ impl InputNotes {
                 println!("Succesfully exported note {}", id);
             },
             InputNotes::Import { filename } => {
-                let note_id = import_note(&mut client, filename.clone())?;
+                let note_id = import_note(&mut client, filename.clone()).await?;
                 println!("Succesfully imported note {}", note_id.inner());
             },
+            InputNotes::Check { id } => {
+                check_note_spent(&mut client, id).await?;
+            },
         }
         Ok(())
     }
@@ -123,6 +137,26 @@ fn list_input_notes<N: NodeRpcClient, R: FeltRng, S: Store>(
     Ok(())
 }
 
+fn list_expected_notes<N: NodeRpcClient, R: FeltRng, S: Store>(
+    client: Client<N, R, S>,
+) -> Result<(), String> {
+    let notes = client.get_expected_notes()?;
+
+    let mut table = create_dynamic_table(&["Note ID", "Sender", "Tag", "Commit Height"]);
+    for note in notes {
+        table.add_row(vec![
+            note.id().inner().to_string(),
+            note.sender().to_string(),
+            u32::from(note.tag()).to_string(),
+            note.block_num().to_string(),
+        ]);
+    }
+
+    println!("{table}");
+
+    Ok(())
+}
+
 // EXPORT INPUT NOTE
 // ================================================================================================
 pub fn export_note<N: NodeRpcClient, R: FeltRng, S: Store>(
@@ -150,7 +184,7 @@ pub fn export_note<N: NodeRpcClient, R: FeltRng, S: Store>(
 
 // IMPORT INPUT NOTE
 // ================================================================================================
-pub fn import_note<N: NodeRpcClient, R: FeltRng, S: Store>(
+pub async fn import_note<N: NodeRpcClient, R: FeltRng, S: Store>(
     client: &mut Client<N, R, S>,
     filename: PathBuf,
 ) -> Result<NoteId, String> {
@@ -165,11 +199,37 @@ pub fn import_note<N: NodeRpcClient, R: FeltRng, S: Store>(
         InputNoteRecord::read_from_bytes(&contents).map_err(|err| err.to_string())?;
 
     let note_id = input_note_record.id();
-    client.import_input_note(input_note_record)?;
+    client.import_input_note(input_note_record).await?;
 
     Ok(note_id)
 }
 
+// CHECK INPUT NOTE
+// ================================================================================================
+async fn check_note_spent<N: NodeRpcClient, R: FeltRng, S: Store>(
+    client: &mut Client<N, R, S>,
+    note_id: &str,
+) -> Result<(), String> {
+    let note_id = get_note_with_id_prefix(client, note_id).map_err(|err| err.to_string())?.id();
+    let status = client.check_note_spent(note_id).await?;
+
+    let spent_at = status
+        .spent_at()
+        .map(|block_num| format!("spent in block {block_num}"))
+        .unwrap_or("unspent".to_string());
+
+    let mut table = create_dynamic_table(&["Note ID", "Nullifier", "Status", "Nullifier Root"]);
+    table.add_row(vec![
+        note_id.inner().to_string(),
+        status.nullifier().to_string(),
+        spent_at,
+        status.nullifier_root().to_string(),
+    ]);
+
+    println!("{table}");
+    Ok(())
+}
+
 // SHOW INPUT NOTE
 // ================================================================================================
 fn show_input_note<N: NodeRpcClient, R: FeltRng, S: Store>(
@@ -288,7 +348,7 @@ mod tests {
     use std::env::temp_dir;
 
     use miden_client::{
-        client::get_random_coin,
+        client::{get_random_coin, ClientBuilder},
         config::{ClientConfig, Endpoint},
         errors::NoteIdPrefixFetchError,
         mock::{mock_full_chain_mmr_and_notes, mock_notes, MockClient, MockRpcApi},
@@ -313,16 +373,14 @@ mod tests {
         );
 
         let store = SqliteStore::new((&client_config).into()).unwrap();
-        let rng = get_random_coin();
-        let executor_store = SqliteStore::new((&client_config).into()).unwrap();
-
-        let mut client = MockClient::new(
-            MockRpcApi::new(&Endpoint::default().to_string()),
-            rng,
-            store,
-            executor_store,
-        )
-        .unwrap();
+        let rng = get_random_coin(&store).unwrap();
+
+        let mut client: MockClient = ClientBuilder::new()
+            .with_rpc_api(MockRpcApi::new(&Endpoint::default().to_string()))
+            .with_rng(rng)
+            .with_store(store)
+            .build()
+            .unwrap();
 
         // generate test data
         let assembler = TransactionKernel::assembler();
@@ -332,8 +390,8 @@ mod tests {
         let committed_note: InputNoteRecord = committed_notes.first().unwrap().clone().into();
         let pending_note = InputNoteRecord::from(created_notes.first().unwrap().clone());
 
-        client.import_input_note(committed_note.clone()).unwrap();
-        client.import_input_note(pending_note.clone()).unwrap();
+        client.import_input_note(committed_note.clone()).await.unwrap();
+        client.import_input_note(pending_note.clone()).await.unwrap();
         assert!(pending_note.inclusion_proof().is_none());
         assert!(committed_note.inclusion_proof().is_some());
 
@@ -365,23 +423,22 @@ mod tests {
             Endpoint::default().into(),
         );
         let store = SqliteStore::new((&client_config).into()).unwrap();
-        let executor_store = SqliteStore::new((&client_config).into()).unwrap();
+        let rng = get_random_coin(&store).unwrap();
 
-        let mut client = MockClient::new(
-            MockRpcApi::new(&Endpoint::default().to_string()),
-            rng,
-            store,
-            executor_store,
-        )
-        .unwrap();
+        let mut client: MockClient = ClientBuilder::new()
+            .with_rpc_api(MockRpcApi::new(&Endpoint::default().to_string()))
+            .with_rng(rng)
+            .with_store(store)
+            .build()
+            .unwrap();
 
-        import_note(&mut client, filename_path).unwrap();
+        import_note(&mut client, filename_path).await.unwrap();
         let imported_note_record: InputNoteRecord =
             client.get_input_note(committed_note.id()).unwrap();
 
         assert_eq!(committed_note.id(), imported_note_record.id());
 
-        import_note(&mut client, filename_path_pending).unwrap();
+        import_note(&mut client, filename_path_pending).await.unwrap();
         let imported_pending_note_record = client.get_input_note(pending_note.id()).unwrap();
 
         assert_eq!(imported_pending_note_record.id(), pending_note.id());
@@ -398,16 +455,14 @@ mod tests {
         );
 
         let store = SqliteStore::new((&client_config).into()).unwrap();
-        let rng = get_random_coin();
-        let executor_store = SqliteStore::new((&client_config).into()).unwrap();
-
-        let mut client = MockClient::new(
-            MockRpcApi::new(&Endpoint::default().to_string()),
-            rng,
-            store,
-            executor_store,
-        )
-        .unwrap();
+        let rng = get_random_coin(&store).unwrap();
+
+        let mut client: MockClient = ClientBuilder::new()
+            .with_rpc_api(MockRpcApi::new(&Endpoint::default().to_string()))
+            .with_rng(rng)
+            .with_store(store)
+            .build()
+            .unwrap();
 
         // Ensure we get an error if no note is found
         let non_existent_note_id = "0x123456";
@@ -424,8 +479,8 @@ mod tests {
         let committed_note: InputNoteRecord = notes.first().unwrap().clone().into();
         let pending_note = InputNoteRecord::from(created_notes.first().unwrap().clone());
 
-        client.import_input_note(committed_note.clone()).unwrap();
-        client.import_input_note(pending_note.clone()).unwrap();
+        client.import_input_note(committed_note.clone()).await.unwrap();
+        client.import_input_note(pending_note.clone()).await.unwrap();
         assert!(pending_note.inclusion_proof().is_none());
         assert!(committed_note.inclusion_proof().is_some());
 
@@ -8,12 +8,11 @@ use figment::{
 };
 use miden_client::{
     client::{
-        get_random_coin,
-        rpc::{NodeRpcClient, TonicRpcClient},
-        Client,
+        rpc::{FailoverRpcClient, NodeRpcClient, TonicRpcClient},
+        Client, ClientBuilder,
     },
-    config::ClientConfig,
-    errors::{ClientError, NoteIdPrefixFetchError},
+    config::{ClientConfig, ClientConfigFile},
+    errors::NoteIdPrefixFetchError,
     store::{sqlite_store::SqliteStore, InputNoteRecord, NoteFilter as ClientNoteFilter, Store},
 };
 use miden_objects::crypto::rand::FeltRng;
@@ -21,6 +20,7 @@ use miden_objects::crypto::rand::FeltRng;
 use miden_objects::crypto::rand::RpoRandomCoin;
 
 mod account;
+mod compact;
 mod info;
 mod init;
 mod input_notes;
@@ -37,6 +37,10 @@ const CLIENT_CONFIG_FILE_NAME: &str = "miden-client.toml";
 pub struct Cli {
     #[clap(subcommand)]
     action: Command,
+
+    /// Network profile from the config file to use. Defaults to the file's `default_profile`.
+    #[clap(long, global = true)]
+    profile: Option<String>,
 }
 
 /// CLI actions
@@ -44,6 +48,8 @@ pub struct Cli {
 pub enum Command {
     #[clap(subcommand)]
     Account(account::AccountCmd),
+    /// Prune block headers and chain MMR nodes that are no longer needed from the store
+    Compact,
     Init,
     #[clap(subcommand)]
     InputNotes(input_notes::InputNotes),
@@ -67,28 +73,22 @@ impl Cli {
         // Check if it's an init command before anything else. When we run the init command for the first time we won't
         // have a config file and thus creating the store would not be possible.
         if matches!(&self.action, Command::Init) {
-            init::initialize_client(current_dir.clone())?;
+            init::initialize_client(current_dir.clone(), self.profile.as_deref())?;
             return Ok(());
         }
 
         // Create the client
-        let client_config = load_config(current_dir.as_path())?;
-        let rpc_endpoint = client_config.rpc.endpoint.to_string();
-        let store = SqliteStore::new((&client_config).into()).map_err(ClientError::StoreError)?;
-        let rng = get_random_coin();
-        let executor_store =
-            miden_client::store::sqlite_store::SqliteStore::new((&client_config).into())
-                .map_err(ClientError::StoreError)?;
-
-        let client: Client<TonicRpcClient, RpoRandomCoin, SqliteStore> =
-            Client::new(TonicRpcClient::new(&rpc_endpoint), rng, store, executor_store)?;
+        let client_config = load_config(current_dir.as_path(), self.profile.as_deref())?;
+        let client: Client<FailoverRpcClient<TonicRpcClient>, RpoRandomCoin, SqliteStore> =
+            ClientBuilder::from_config(&client_config)?.build()?;
 
         // Execute cli command
         match &self.action {
-            Command::Account(account) => account.execute(client),
+            Command::Account(account) => account.execute(client).await,
+            Command::Compact => compact::compact_store(client),
             Command::Init => Ok(()),
             Command::Info => info::print_client_info(&client),
-            Command::InputNotes(notes) => notes.execute(client),
+            Command::InputNotes(notes) => notes.execute(client).await,
             Command::Sync => sync::sync_state(client).await,
             Command::Tags(tags) => tags.execute(client).await,
             Command::Transaction(transaction) => transaction.execute(client).await,
@@ -96,11 +96,19 @@ impl Cli {
     }
 }
 
-/// Loads the client configuration.
+/// Loads the client configuration of the specified network profile.
 ///
 /// This function will look for the configuration file at the provided path. If the path is
-/// relative, searches in parent directories all the way to the root as well.
-pub fn load_config(config_file: &Path) -> Result<ClientConfig, String> {
+/// relative, searches in parent directories all the way to the root as well. If `profile` is
+/// `None`, the file's default profile is used (see [ClientConfigFile::select]).
+pub fn load_config(config_file: &Path, profile: Option<&str>) -> Result<ClientConfig, String> {
+    load_config_file(config_file)?
+        .select(profile)
+        .map_err(|err| format!("Failed to load {} config file: {err}", config_file.display()))
+}
+
+/// Loads the whole contents of the client configuration file, including all its profiles.
+pub fn load_config_file(config_file: &Path) -> Result<ClientConfigFile, String> {
     Figment::from(Toml::file(config_file))
         .extract()
         .map_err(|err| format!("Failed to load {} config file: {err}", config_file.display()))
@@ -1,5 +1,7 @@
+use core::fmt;
+
 use miden_client::{
-    client::{rpc::NodeRpcClient, Client},
+    client::{rpc::NodeRpcClient, sync::SyncSummary, Client},
     store::Store,
 };
 use miden_objects::crypto::rand::FeltRng;
@@ -7,7 +9,35 @@ use miden_objects::crypto::rand::FeltRng;
 pub async fn sync_state<N: NodeRpcClient, R: FeltRng, S: Store>(
     mut client: Client<N, R, S>,
 ) -> Result<(), String> {
-    let block_num = client.sync_state().await?;
-    println!("State synced to block {}", block_num);
+    let sync_summary = client.sync_state().await?;
+    print_sync_summary(&sync_summary);
     Ok(())
 }
+
+// HELPERS
+// ================================================================================================
+
+fn print_sync_summary(sync_summary: &SyncSummary) {
+    println!("State synced to block {}", sync_summary.block_num);
+    println!("Blocks processed: {}", sync_summary.blocks_processed.len());
+
+    if sync_summary.is_empty() {
+        println!("No changes to the client's state");
+        return;
+    }
+
+    print_ids("New public notes", &sync_summary.new_public_notes);
+    print_ids("New expected notes", &sync_summary.new_expected_notes);
+    print_ids("Committed notes", &sync_summary.committed_notes);
+    print_ids("Consumed nullifiers", &sync_summary.consumed_nullifiers);
+    print_ids("Committed transactions", &sync_summary.committed_transactions);
+    print_ids("Discarded transactions", &sync_summary.discarded_transactions);
+    print_ids("Updated on-chain accounts", &sync_summary.updated_onchain_accounts);
+}
+
+fn print_ids<T: fmt::Display>(title: &str, ids: &[T]) {
+    println!("{title}: {}", ids.len());
+    for id in ids {
+        println!("  - {id}");
+    }
+}
@@ -1,10 +1,14 @@
 use miden_client::{client::rpc::NodeRpcClient, store::Store};
-use miden_objects::crypto::rand::FeltRng;
+use miden_objects::{
+    accounts::AccountId,
+    crypto::rand::FeltRng,
+    notes::{NoteExecutionMode, NoteTag},
+};
 
-use super::{Client, Parser};
+use super::{create_dynamic_table, Client, Parser};
 
 #[derive(Debug, Parser, Clone)]
-#[clap(about = "View and add tags")]
+#[clap(about = "View, add and remove tags")]
 pub enum TagsCmd {
     /// List all tags monitored by this client
     #[clap(short_flag = 'l')]
@@ -14,7 +18,14 @@ pub enum TagsCmd {
     #[clap(short_flag = 'a')]
     Add {
         #[clap()]
-        tag: u64,
+        tag: u32,
+    },
+
+    /// Remove a tag from the list of tags monitored by this client
+    #[clap(short_flag = 'r')]
+    Remove {
+        #[clap()]
+        tag: u32,
     },
 }
 
@@ -30,6 +41,9 @@ impl TagsCmd {
             TagsCmd::Add { tag } => {
                 add_tag(client, *tag)?;
             },
+            TagsCmd::Remove { tag } => {
+                remove_tag(client, *tag)?;
+            },
         }
         Ok(())
     }
@@ -41,15 +55,78 @@ fn list_tags<N: NodeRpcClient, R: FeltRng, S: Store>(
     client: Client<N, R, S>,
 ) -> Result<(), String> {
     let tags = client.get_note_tags()?;
-    println!("tags: {:?}", tags);
+    let account_ids: Vec<AccountId> =
+        client.get_accounts()?.into_iter().map(|(stub, _)| stub.id()).collect();
+
+    let mut table =
+        create_dynamic_table(&["Tag", "Execution Mode", "Account Prefix", "Tracked Account"]);
+    for tag in tags {
+        let (execution_mode, account_prefix) = decode_tag(tag);
+        let account_prefix =
+            account_prefix.map(|prefix| format!("{prefix:#x}")).unwrap_or("-".to_string());
+        let tracked_account = account_ids
+            .iter()
+            .find(|account_id| {
+                NoteTag::from_account_id(**account_id, execution_mode)
+                    .is_ok_and(|account_tag| account_tag_matches(account_tag, tag))
+            })
+            .map(|account_id| account_id.to_string())
+            .unwrap_or("-".to_string());
+
+        table.add_row(vec![
+            u32::from(tag).to_string(),
+            format!("{execution_mode:?}"),
+            account_prefix,
+            tracked_account,
+        ]);
+    }
+
+    println!("{table}");
     Ok(())
 }
 
 fn add_tag<N: NodeRpcClient, R: FeltRng, S: Store>(
     mut client: Client<N, R, S>,
-    tag: u64,
+    tag: u32,
 ) -> Result<(), String> {
-    client.add_note_tag(tag)?;
+    client.add_note_tag(NoteTag::from(tag))?;
     println!("tag {} added", tag);
     Ok(())
 }
+
+fn remove_tag<N: NodeRpcClient, R: FeltRng, S: Store>(
+    mut client: Client<N, R, S>,
+    tag: u32,
+) -> Result<(), String> {
+    client.remove_note_tag(NoteTag::from(tag))?;
+    println!("tag {} removed", tag);
+    Ok(())
+}
+
+/// Decodes the execution mode of a [NoteTag] and, for tags that target an account, the account
+/// ID prefix it encodes.
+///
+/// The two most significant bits of a tag describe how to interpret the rest of it:
+/// - `0b00`: network execution, the remaining 30 bits are the prefix of the target account ID.
+/// - `0b01`: network execution, the remaining bits are defined by the use case.
+/// - `0b1x`: local execution, the next 14 bits are the prefix of the target account ID and the
+///   lower 16 bits are defined by the use case.
+fn decode_tag(tag: NoteTag) -> (NoteExecutionMode, Option<u32>) {
+    let tag = u32::from(tag);
+    match tag >> 30 {
+        0b00 => (NoteExecutionMode::Network, Some(tag & 0x3fff_ffff)),
+        0b01 => (NoteExecutionMode::Network, None),
+        _ => (NoteExecutionMode::Local, Some((tag >> 16) & 0x3fff)),
+    }
+}
+
+/// Returns whether `tag` targets the same account as `account_tag`, the tag derived from the
+/// account's ID. The use case bits of local execution tags are ignored.
+fn account_tag_matches(account_tag: NoteTag, tag: NoteTag) -> bool {
+    let (account_tag, tag) = (u32::from(account_tag), u32::from(tag));
+    match tag >> 30 {
+        0b00 => account_tag == tag,
+        0b01 => false,
+        _ => account_tag >> 16 == tag >> 16,
+    }
+}
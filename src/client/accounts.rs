@@ -6,14 +6,15 @@ use miden_objects::{
     assets::TokenSymbol,
     crypto::{
         dsa::rpo_falcon512::SecretKey,
+        hash::rpo::Rpo256,
         rand::{FeltRng, RpoRandomCoin},
     },
-    Digest, Felt, Word,
+    Digest, Felt, Word, ZERO,
 };
 
 use super::{rpc::NodeRpcClient, Client};
 use crate::{
-    errors::ClientError,
+    errors::{ClientError, NodeRpcClientError},
     store::{AuthInfo, Store},
 };
 
@@ -51,23 +52,83 @@ impl<N: NodeRpcClient, R: FeltRng, S: Store> Client<N, R, S> {
     // --------------------------------------------------------------------------------------------
 
     /// Creates a new [Account] based on an [AccountTemplate] and saves it in the store
+    ///
+    /// If the client has a master seed, the account's key and init seed are derived from it using
+    /// the next account index, which is only advanced once the account is saved; otherwise they
+    /// are drawn from the client's RNG.
     pub fn new_account(
         &mut self,
         template: AccountTemplate,
     ) -> Result<(Account, Word), ClientError> {
-        let account_and_seed = match template {
-            AccountTemplate::BasicWallet { mutable_code, storage_mode } => {
-                self.new_basic_wallet(mutable_code, storage_mode)
-            },
-            AccountTemplate::FungibleFaucet {
-                token_symbol,
-                decimals,
-                max_supply,
-                storage_mode,
-            } => self.new_fungible_faucet(token_symbol, decimals, max_supply, storage_mode),
-        }?;
-
-        Ok(account_and_seed)
+        if self.store.get_master_seed()?.is_some() {
+            let (master_seed, account_index) = self.store.get_next_account_index()?;
+            let (key_pair, init_seed) = derive_account_key_material(master_seed, account_index);
+
+            let (account, seed) = build_account(template, &key_pair, init_seed)?;
+
+            self.store.insert_derived_account(
+                &account,
+                Some(seed),
+                &AuthInfo::RpoFalcon512(key_pair),
+                account_index,
+            )?;
+            return Ok((account, seed));
+        }
+
+        let key_pair = SecretKey::with_rng(&mut self.rng);
+
+        let mut init_seed = [0u8; 32];
+        self.rng.fill_bytes(&mut init_seed);
+
+        let (account, seed) = build_account(template, &key_pair, init_seed)?;
+
+        self.insert_account(&account, Some(seed), &AuthInfo::RpoFalcon512(key_pair))?;
+        Ok((account, seed))
+    }
+
+    /// Re-creates the next account derived from the client's master seed using the provided
+    /// [AccountTemplate], and saves it in the store.
+    ///
+    /// Accounts are derived by index, so they must be recovered with the same templates and in
+    /// the same order in which they were originally created. For on-chain accounts, the latest
+    /// state is fetched from the node; if the node does not know the account yet, its initial
+    /// state is stored instead. Off-chain accounts are always restored to their initial state.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [StoreError::MasterSeedNotFound](crate::errors::StoreError::MasterSeedNotFound)
+    /// if the client has no master seed, or a [ClientError::NodeRpcClientError] if the state of an
+    /// on-chain account could not be fetched for a reason other than the node not knowing it.
+    pub async fn recover_account(
+        &mut self,
+        template: AccountTemplate,
+    ) -> Result<Account, ClientError> {
+        let (master_seed, account_index) = self.store.get_next_account_index()?;
+        let (key_pair, init_seed) = derive_account_key_material(master_seed, account_index);
+
+        let (account, seed) = build_account(template, &key_pair, init_seed)?;
+
+        let account = if account.id().is_on_chain() {
+            match self.rpc_api.get_account_update(account.id()).await {
+                Ok(onchain_account) => onchain_account,
+                // the account was never used, so its initial state is the latest one
+                Err(NodeRpcClientError::NotFound(..)) => account,
+                Err(err) => return Err(err.into()),
+            }
+        } else {
+            account
+        };
+
+        // the index is only advanced along with the insertion, so a failed recovery can be retried
+        let account_seed = account.is_new().then_some(seed);
+        self.store.insert_derived_account(
+            &account,
+            account_seed,
+            &AuthInfo::RpoFalcon512(key_pair),
+            account_index,
+        )?;
+
+        Ok(account)
     }
 
     /// Saves in the store the [Account] corresponding to `account_data`.
@@ -112,69 +173,6 @@ impl<N: NodeRpcClient, R: FeltRng, S: Store> Client<N, R, S> {
         }
     }
 
-    /// Creates a new regular account and saves it in the store along with its seed and auth data
-    fn new_basic_wallet(
-        &mut self,
-        mutable_code: bool,
-        account_storage_mode: AccountStorageMode,
-    ) -> Result<(Account, Word), ClientError> {
-        let key_pair = SecretKey::with_rng(&mut self.rng);
-
-        let auth_scheme: AuthScheme = AuthScheme::RpoFalcon512 { pub_key: key_pair.public_key() };
-
-        // we need to use an initial seed to create the wallet account
-        let mut init_seed = [0u8; 32];
-        self.rng.fill_bytes(&mut init_seed);
-
-        let (account, seed) = if !mutable_code {
-            miden_lib::accounts::wallets::create_basic_wallet(
-                init_seed,
-                auth_scheme,
-                AccountType::RegularAccountImmutableCode,
-                account_storage_mode.into(),
-            )
-        } else {
-            miden_lib::accounts::wallets::create_basic_wallet(
-                init_seed,
-                auth_scheme,
-                AccountType::RegularAccountUpdatableCode,
-                account_storage_mode.into(),
-            )
-        }?;
-
-        self.insert_account(&account, Some(seed), &AuthInfo::RpoFalcon512(key_pair))?;
-        Ok((account, seed))
-    }
-
-    fn new_fungible_faucet(
-        &mut self,
-        token_symbol: TokenSymbol,
-        decimals: u8,
-        max_supply: u64,
-        account_storage_mode: AccountStorageMode,
-    ) -> Result<(Account, Word), ClientError> {
-        let key_pair = SecretKey::with_rng(&mut self.rng);
-
-        let auth_scheme: AuthScheme = AuthScheme::RpoFalcon512 { pub_key: key_pair.public_key() };
-
-        // we need to use an initial seed to create the wallet account
-        let mut init_seed = [0u8; 32];
-        self.rng.fill_bytes(&mut init_seed);
-
-        let (account, seed) = miden_lib::accounts::faucets::create_basic_fungible_faucet(
-            init_seed,
-            token_symbol,
-            decimals,
-            Felt::try_from(max_supply.to_le_bytes().as_slice())
-                .expect("u64 can be safely converted to a field element"),
-            account_storage_mode.into(),
-            auth_scheme,
-        )?;
-
-        self.insert_account(&account, Some(seed), &AuthInfo::RpoFalcon512(key_pair))?;
-        Ok((account, seed))
-    }
-
     /// Inserts a new account into the client's store.
     ///
     /// # Errors
@@ -231,6 +229,149 @@ impl<N: NodeRpcClient, R: FeltRng, S: Store> Client<N, R, S> {
     }
 }
 
+impl<N: NodeRpcClient, S: Store> Client<N, RpoRandomCoin, S> {
+    /// Sets the master seed from which the keys and init seeds of new accounts are derived. The
+    /// seed of the client's random coins is derived from it as well with `rng_epoch`, so that the
+    /// notes created by the client can be recovered with [Client::recover_created_note].
+    ///
+    /// Clients sharing a master seed draw the same serial numbers if they use the same epoch, so
+    /// every client set up with a master seed must use an epoch no other client used before: a new
+    /// wallet uses 0, and each restore of it uses the epoch after the last one used.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [ClientError::MasterSeedAlreadySet] if the client already has a master seed, or
+    /// a [ClientError::RngSeedAlreadyUsed] if random coins were already derived from the current
+    /// seed, since the serial numbers drawn from them could not be recovered anymore.
+    pub fn set_master_seed(
+        &mut self,
+        master_seed: Word,
+        rng_epoch: u32,
+    ) -> Result<(), ClientError> {
+        if self.store.get_master_seed()?.is_some() {
+            return Err(ClientError::MasterSeedAlreadySet);
+        }
+        if self.store.get_rng_counter()? > 0 {
+            return Err(ClientError::RngSeedAlreadyUsed);
+        }
+
+        let rng_seed = derive_rng_seed(master_seed, rng_epoch);
+        self.store.set_master_seed(master_seed, rng_epoch, rng_seed)?;
+        self.rng = RpoRandomCoin::new(rng_seed);
+
+        Ok(())
+    }
+}
+
+// HELPERS
+// ================================================================================================
+
+/// Domain separator used when deriving account keys from the master seed.
+const ACCOUNT_KEY_DOMAIN: u64 = 0;
+/// Domain separator used when deriving account init seeds from the master seed.
+const ACCOUNT_INIT_SEED_DOMAIN: u64 = 1;
+/// Domain separator used when deriving the random coin seed from the master seed.
+const RNG_SEED_DOMAIN: u64 = 2;
+
+/// Deterministically derives the [SecretKey] and the init seed of the account with index
+/// `account_index` from the provided master seed.
+pub fn derive_account_key_material(master_seed: Word, account_index: u32) -> (SecretKey, [u8; 32]) {
+    let key_seed = derive_from_master_seed(master_seed, ACCOUNT_KEY_DOMAIN, account_index);
+    let key_pair = SecretKey::with_rng(&mut RpoRandomCoin::new(key_seed.into()));
+
+    let init_seed =
+        derive_from_master_seed(master_seed, ACCOUNT_INIT_SEED_DOMAIN, account_index).as_bytes();
+
+    (key_pair, init_seed)
+}
+
+/// Deterministically derives the seed of the random coins of a client set up with the provided
+/// master seed and random coin epoch.
+pub(crate) fn derive_rng_seed(master_seed: Word, rng_epoch: u32) -> Word {
+    derive_from_master_seed(master_seed, RNG_SEED_DOMAIN, rng_epoch).into()
+}
+
+fn derive_from_master_seed(master_seed: Word, domain: u64, index: u32) -> Digest {
+    let path: Word = [Felt::new(domain), Felt::from(index), ZERO, ZERO];
+    Rpo256::merge(&[master_seed.into(), path.into()])
+}
+
+/// Builds the [Account] described by `template` from the provided key pair and init seed,
+/// returning it along with its account seed.
+fn build_account(
+    template: AccountTemplate,
+    key_pair: &SecretKey,
+    init_seed: [u8; 32],
+) -> Result<(Account, Word), ClientError> {
+    match template {
+        AccountTemplate::BasicWallet { mutable_code, storage_mode } => {
+            new_basic_wallet(mutable_code, storage_mode, key_pair, init_seed)
+        },
+        AccountTemplate::FungibleFaucet {
+            token_symbol,
+            decimals,
+            max_supply,
+            storage_mode,
+        } => new_fungible_faucet(
+            token_symbol,
+            decimals,
+            max_supply,
+            storage_mode,
+            key_pair,
+            init_seed,
+        ),
+    }
+}
+
+/// Creates a new regular account from the provided key pair and init seed
+fn new_basic_wallet(
+    mutable_code: bool,
+    account_storage_mode: AccountStorageMode,
+    key_pair: &SecretKey,
+    init_seed: [u8; 32],
+) -> Result<(Account, Word), ClientError> {
+    let auth_scheme: AuthScheme = AuthScheme::RpoFalcon512 { pub_key: key_pair.public_key() };
+
+    let account_type = if mutable_code {
+        AccountType::RegularAccountUpdatableCode
+    } else {
+        AccountType::RegularAccountImmutableCode
+    };
+
+    let (account, seed) = miden_lib::accounts::wallets::create_basic_wallet(
+        init_seed,
+        auth_scheme,
+        account_type,
+        account_storage_mode.into(),
+    )?;
+
+    Ok((account, seed))
+}
+
+/// Creates a new fungible faucet account from the provided key pair and init seed
+fn new_fungible_faucet(
+    token_symbol: TokenSymbol,
+    decimals: u8,
+    max_supply: u64,
+    account_storage_mode: AccountStorageMode,
+    key_pair: &SecretKey,
+    init_seed: [u8; 32],
+) -> Result<(Account, Word), ClientError> {
+    let auth_scheme: AuthScheme = AuthScheme::RpoFalcon512 { pub_key: key_pair.public_key() };
+
+    let (account, seed) = miden_lib::accounts::faucets::create_basic_fungible_faucet(
+        init_seed,
+        token_symbol,
+        decimals,
+        Felt::try_from(max_supply.to_le_bytes().as_slice())
+            .expect("u64 can be safely converted to a field element"),
+        account_storage_mode.into(),
+        auth_scheme,
+    )?;
+
+    Ok((account, seed))
+}
+
 // TESTS
 // ================================================================================================
 
@@ -238,16 +379,30 @@ impl<N: NodeRpcClient, R: FeltRng, S: Store> Client<N, R, S> {
 pub mod tests {
     use miden_objects::{
         accounts::{Account, AccountData, AccountId, AuthData},
-        crypto::dsa::rpo_falcon512::SecretKey,
-        Word,
+        assets::{FungibleAsset, TokenSymbol},
+        crypto::{dsa::rpo_falcon512::SecretKey, rand::RpoRandomCoin},
+        notes::NoteType,
+        transaction::InputNote,
+        Felt, Word,
     };
 
+    use super::{derive_rng_seed, AccountStorageMode, AccountTemplate};
     use crate::{
+        client::{
+            get_random_coin,
+            rpc::{RecordingRpcClient, ReplayRpcClient},
+            transactions::transaction_request::TransactionTemplate,
+            ClientBuilder,
+        },
+        errors::{ClientError, NodeRpcClientError},
         mock::{
             get_account_with_default_account_code, get_new_account_with_default_account_code,
-            ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN, ACCOUNT_ID_REGULAR,
+            MockRpcApi, ACCOUNT_ID_FUNGIBLE_FAUCET_ON_CHAIN, ACCOUNT_ID_REGULAR,
+        },
+        store::{
+            sqlite_store::tests::{create_test_client, create_test_store, create_test_store_path},
+            AuthInfo,
         },
-        store::{sqlite_store::tests::create_test_client, AuthInfo},
     };
 
     fn create_account_data(account_id: u64) -> AccountData {
@@ -315,4 +470,195 @@ pub mod tests {
             assert_eq!(client_acc.0.hash(), expected_acc.hash());
         }
     }
+
+    #[tokio::test]
+    async fn recover_accounts_from_master_seed() {
+        let master_seed: Word = [Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)];
+        let template = || AccountTemplate::BasicWallet {
+            mutable_code: false,
+            storage_mode: AccountStorageMode::Local,
+        };
+
+        let mut client = create_test_client();
+        client.set_master_seed(master_seed, 0).unwrap();
+        assert!(matches!(
+            client.set_master_seed(master_seed, 0),
+            Err(ClientError::MasterSeedAlreadySet)
+        ));
+        assert_eq!(client.store().get_rng_seed().unwrap(), Some(derive_rng_seed(master_seed, 0)));
+
+        let (first_account, _) = client.new_account(template()).unwrap();
+        let (second_account, _) = client.new_account(template()).unwrap();
+        assert_ne!(first_account.id(), second_account.id());
+
+        // a client set up with the same master seed re-derives the same accounts, in order
+        let mut recovered_client = create_test_client();
+        recovered_client.set_master_seed(master_seed, 1).unwrap();
+
+        // it draws serial numbers from a different coin, so its notes don't collide with the ones
+        // of the original client
+        assert_eq!(
+            recovered_client.store().get_rng_seed().unwrap(),
+            Some(derive_rng_seed(master_seed, 1))
+        );
+        assert_ne!(derive_rng_seed(master_seed, 1), derive_rng_seed(master_seed, 0));
+
+        let recovered_first = recovered_client.recover_account(template()).await.unwrap();
+        let recovered_second = recovered_client.recover_account(template()).await.unwrap();
+
+        assert_eq!(recovered_first.id(), first_account.id());
+        assert_eq!(recovered_second.id(), second_account.id());
+        assert_eq!(
+            recovered_client
+                .get_account_auth(first_account.id())
+                .unwrap()
+                .into_advice_inputs(),
+            client.get_account_auth(first_account.id()).unwrap().into_advice_inputs()
+        );
+    }
+
+    #[tokio::test]
+    async fn recover_unused_on_chain_account() {
+        let master_seed: Word = [Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)];
+        let template = || AccountTemplate::BasicWallet {
+            mutable_code: false,
+            storage_mode: AccountStorageMode::OnChain,
+        };
+
+        let mut client = create_test_client();
+        client.set_master_seed(master_seed, 0).unwrap();
+        let (account, _) = client.new_account(template()).unwrap();
+
+        // the mocked node does not know the account, so its initial state is recovered
+        let mut recovered_client = create_test_client();
+        recovered_client.set_master_seed(master_seed, 1).unwrap();
+        let recovered_account = recovered_client.recover_account(template()).await.unwrap();
+
+        assert_eq!(recovered_account.hash(), account.hash());
+        assert!(recovered_client.get_account(account.id()).unwrap().1.is_some());
+    }
+
+    #[tokio::test]
+    async fn recover_unused_on_chain_account_from_recording() {
+        let master_seed: Word = [Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)];
+        let template = || AccountTemplate::BasicWallet {
+            mutable_code: false,
+            storage_mode: AccountStorageMode::OnChain,
+        };
+
+        // record the recovery against the mocked node, which does not know the account
+        let recording_path = create_test_store_path().with_extension("jsonl");
+        let mut recording_client = ClientBuilder::new()
+            .with_rpc_api(RecordingRpcClient::new(MockRpcApi::default(), &recording_path).unwrap())
+            .with_rng(RpoRandomCoin::new(Default::default()))
+            .with_store(create_test_store())
+            .build()
+            .unwrap();
+        recording_client.set_master_seed(master_seed, 0).unwrap();
+        let account = recording_client.recover_account(template()).await.unwrap();
+        drop(recording_client);
+
+        // the replayed not found error falls back to the account's initial state as well
+        let mut client = ClientBuilder::new()
+            .with_rpc_api(ReplayRpcClient::from_file(&recording_path).unwrap())
+            .with_rng(RpoRandomCoin::new(Default::default()))
+            .with_store(create_test_store())
+            .build()
+            .unwrap();
+        client.set_master_seed(master_seed, 1).unwrap();
+
+        let recovered_account = client.recover_account(template()).await.unwrap();
+        assert_eq!(recovered_account.hash(), account.hash());
+        assert!(client.get_account(account.id()).unwrap().1.is_some());
+    }
+
+    #[tokio::test]
+    async fn failed_recovery_keeps_account_index() {
+        let master_seed: Word = [Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)];
+        let template = || AccountTemplate::BasicWallet {
+            mutable_code: false,
+            storage_mode: AccountStorageMode::OnChain,
+        };
+
+        // a node without any recorded responses fails every request
+        let recording_path = create_test_store_path().with_extension("jsonl");
+        std::fs::write(&recording_path, "").unwrap();
+        let mut client = ClientBuilder::new()
+            .with_rpc_api(ReplayRpcClient::from_file(&recording_path).unwrap())
+            .with_rng(RpoRandomCoin::new(Default::default()))
+            .with_store(create_test_store())
+            .build()
+            .unwrap();
+        client.set_master_seed(master_seed, 0).unwrap();
+
+        assert!(matches!(
+            client.recover_account(template()).await,
+            Err(ClientError::NodeRpcClientError(NodeRpcClientError::RecordingError(_)))
+        ));
+        assert_eq!(client.store().get_next_account_index().unwrap(), (master_seed, 0));
+        assert!(client.get_accounts().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn master_seed_is_not_set_after_drawing_random_coins() {
+        let master_seed: Word = [Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)];
+
+        // serial numbers drawn from the current seed could not be recovered with the new one
+        let mut client = create_test_client();
+        get_random_coin(client.store()).unwrap();
+        assert!(matches!(
+            client.set_master_seed(master_seed, 0),
+            Err(ClientError::RngSeedAlreadyUsed)
+        ));
+        assert!(client.store().get_master_seed().unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn recover_notes_created_with_previous_epochs() {
+        let master_seed: Word = [Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)];
+        let template = || AccountTemplate::FungibleFaucet {
+            token_symbol: TokenSymbol::new("TEST").unwrap(),
+            decimals: 2,
+            max_supply: 1000,
+            storage_mode: AccountStorageMode::Local,
+        };
+
+        let mut client = create_test_client();
+        client.set_master_seed(master_seed, 0).unwrap();
+        let (faucet, _) = client.new_account(template()).unwrap();
+
+        let transaction_template = TransactionTemplate::MintFungibleAsset(
+            FungibleAsset::new(faucet.id(), 5).unwrap(),
+            AccountId::try_from(ACCOUNT_ID_REGULAR).unwrap(),
+            NoteType::OffChain,
+        );
+        client.build_transaction_request(transaction_template.clone()).unwrap();
+        let created_note = client
+            .build_transaction_request(transaction_template.clone())
+            .unwrap()
+            .expected_output_notes()[0]
+            .clone();
+
+        // a client restored with the next epoch re-derives the note once the node knows it
+        let mut recovered_client = create_test_client();
+        recovered_client.set_master_seed(master_seed, 1).unwrap();
+        assert!(recovered_client
+            .recover_created_note(&transaction_template, 10)
+            .await
+            .unwrap()
+            .is_none());
+
+        let proof = recovered_client.rpc_api().notes.values().next().unwrap().proof().clone();
+        recovered_client
+            .rpc_api()
+            .notes
+            .insert(created_note.id(), InputNote::new(created_note.clone(), proof));
+
+        let recovered_note = recovered_client
+            .recover_created_note(&transaction_template, 10)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(recovered_note.id(), created_note.id());
+    }
 }
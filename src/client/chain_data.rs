@@ -1,14 +1,85 @@
-use miden_objects::crypto::rand::FeltRng;
-#[cfg(test)]
-use miden_objects::BlockHeader;
+use miden_objects::{
+    crypto::{
+        merkle::{InOrderIndex, MmrPeaks, MmrProof},
+        rand::FeltRng,
+    },
+    notes::{NoteId, NoteInclusionProof, NoteMetadata},
+    BlockHeader, Digest,
+};
 
-#[cfg(test)]
 use crate::{
-    client::{rpc::NodeRpcClient, Client},
-    errors::ClientError,
-    store::Store,
+    client::{rpc::NodeRpcClient, sync::verify_note_inclusion, Client},
+    errors::{ClientError, StoreError, VerificationError},
+    store::{CompactionSummary, Store},
 };
 
+impl<N: NodeRpcClient, R: FeltRng, S: Store> Client<N, R, S> {
+    /// Prunes the block headers and chain MMR nodes that are no longer needed to authenticate the
+    /// client's unspent notes, keeping the current chain MMR peaks. See [Store::compact].
+    pub fn compact_store(&mut self) -> Result<CompactionSummary, ClientError> {
+        self.store.compact().map_err(ClientError::StoreError)
+    }
+
+    /// Makes sure that the block in which a note was committed can be authenticated when
+    /// consuming the note.
+    ///
+    /// If `metadata` is provided, the note's inclusion proof is first verified to open the note
+    /// root it claims at the note's index. Without it, the note path can't be checked, as the
+    /// note tree leaves commit to the note's metadata.
+    ///
+    /// If the client synced past the block without tracking it, the block header and its MMR
+    /// proof are fetched from the node and verified against the current chain MMR peaks, and the
+    /// proof's note root is checked against the header. The header is then stored along with the
+    /// chain MMR nodes needed to authenticate it. Blocks after the sync height aren't part of the
+    /// client's chain MMR yet, so they're left as is.
+    pub(crate) async fn track_note_block(
+        &mut self,
+        note_id: NoteId,
+        metadata: Option<&NoteMetadata>,
+        inclusion_proof: &NoteInclusionProof,
+    ) -> Result<(), ClientError> {
+        if metadata
+            .is_some_and(|metadata| !verify_note_inclusion(note_id, metadata, inclusion_proof))
+        {
+            return Err(VerificationError::InvalidNoteInclusionProofs(vec![note_id]).into());
+        }
+
+        let block_num = inclusion_proof.origin().block_num;
+        let sync_height = self.store.get_sync_height()?;
+        if block_num >= sync_height {
+            return Ok(());
+        }
+
+        match self.store.get_block_header_by_num(block_num) {
+            Ok((_, true)) => return Ok(()),
+            Ok((_, false)) | Err(StoreError::BlockHeaderNotFound(_)) => {},
+            Err(err) => return Err(err.into()),
+        }
+
+        let (block_header, mmr_proof) =
+            self.rpc_api.get_block_header_with_proof(block_num, sync_height).await?;
+        let current_peaks = self.store.get_chain_mmr_peaks_by_block_num(sync_height)?;
+        let block_peaks = verify_block_mmr_proof(&block_header, &mmr_proof, &current_peaks)?;
+
+        if block_header.sub_hash() != inclusion_proof.sub_hash()
+            || block_header.note_root() != inclusion_proof.note_root()
+        {
+            return Err(VerificationError::InvalidNoteInclusionProofs(vec![note_id]).into());
+        }
+
+        let mut index = InOrderIndex::from_leaf_pos(block_num as usize);
+        let mut authentication_nodes = vec![];
+        for node in mmr_proof.merkle_path.iter() {
+            authentication_nodes.push((index.sibling(), *node));
+            index = index.parent();
+        }
+
+        self.store
+            .insert_tracked_block_header(block_header, block_peaks, &authentication_nodes)
+            .map_err(ClientError::StoreError)
+    }
+}
+
 #[cfg(test)]
 impl<N: NodeRpcClient, R: FeltRng, S: Store> Client<N, R, S> {
     pub fn get_block_headers_in_range(
@@ -28,3 +99,59 @@ impl<N: NodeRpcClient, R: FeltRng, S: Store> Client<N, R, S> {
         self.store.get_block_headers(block_numbers).map_err(ClientError::StoreError)
     }
 }
+
+// HELPERS
+// ================================================================================================
+
+/// Verifies that `mmr_proof` authenticates `block_header` against the chain MMR with
+/// `current_peaks`, and returns the chain MMR peaks at the block's height.
+///
+/// The peaks at the block's height are the peaks of the MMR trees before the one containing the
+/// block, followed by the left siblings in the block's authentication path, from the top.
+fn verify_block_mmr_proof(
+    block_header: &BlockHeader,
+    mmr_proof: &MmrProof,
+    current_peaks: &MmrPeaks,
+) -> Result<MmrPeaks, ClientError> {
+    let block_num = block_header.block_num() as usize;
+    let invalid_proof = || VerificationError::InvalidBlockMmrProof(block_header.block_num());
+
+    let forest = current_peaks.num_leaves();
+    let depth = mmr_proof.merkle_path.depth() as u32;
+    if mmr_proof.position != block_num || mmr_proof.forest != forest || depth >= u32::BITS {
+        return Err(invalid_proof().into());
+    }
+
+    // Trees are ordered from largest to smallest, following the bits set in `forest`
+    let tree_index = (forest >> (depth + 1)).count_ones() as usize;
+    let tree_start = forest & !((2 << depth) - 1);
+    if forest & (1 << depth) == 0 || block_num < tree_start || block_num - tree_start >= 1 << depth
+    {
+        return Err(invalid_proof().into());
+    }
+    let leaf_index = block_num - tree_start;
+
+    let tree_root: Digest = mmr_proof
+        .merkle_path
+        .compute_root(leaf_index as u64, block_header.hash())
+        .map_err(|_| invalid_proof())?;
+    if current_peaks.peaks().get(tree_index) != Some(&tree_root) {
+        return Err(invalid_proof().into());
+    }
+
+    let block_peaks = current_peaks.peaks()[..tree_index]
+        .iter()
+        .copied()
+        .chain(
+            mmr_proof
+                .merkle_path
+                .iter()
+                .enumerate()
+                .rev()
+                .filter(|(level, _)| leaf_index & (1 << level) != 0)
+                .map(|(_, node)| *node),
+        )
+        .collect();
+
+    MmrPeaks::new(block_num, block_peaks).map_err(|err| StoreError::MmrError(err).into())
+}
@@ -0,0 +1,59 @@
+use miden_objects::{accounts::AccountId, notes::NoteId, transaction::TransactionId, Digest};
+
+// CLIENT EVENT
+// ================================================================================================
+
+/// Describes a change in the client's state.
+///
+/// Events are emitted by the [Client](super::Client) right after the corresponding change has
+/// been persisted to the store, so listeners can rely on the store reflecting them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClientEvent {
+    /// A new public note that the client was not tracking was received as part of a sync, or a
+    /// note created by a submitted transaction was found to be relevant to the client.
+    NoteReceived { note_id: NoteId },
+    /// A note tracked by the client was included in the block with number `block_num`.
+    NoteCommitted { note_id: NoteId, block_num: u32 },
+    /// An input note tracked by the client was consumed, as per the nullifiers received for the
+    /// block with number `block_num`.
+    NoteConsumed { note_id: NoteId, block_num: u32 },
+    /// A transaction was proven and submitted to the node.
+    TransactionSubmitted {
+        transaction_id: TransactionId,
+        account_id: AccountId,
+    },
+    /// A transaction was committed in the block with number `block_num`.
+    TransactionCommitted {
+        transaction_id: TransactionId,
+        block_num: u32,
+    },
+    /// A pending transaction was found to be impossible to commit, and the changes it made to the
+    /// state of the account with ID `account_id` were rolled back.
+    TransactionDiscarded {
+        transaction_id: TransactionId,
+        account_id: AccountId,
+    },
+    /// The state of an on-chain account tracked by the client was updated from the node.
+    OnchainAccountUpdated {
+        account_id: AccountId,
+        account_hash: Digest,
+    },
+}
+
+// CLIENT EVENT LISTENER
+// ================================================================================================
+
+/// Receives the [ClientEvent]s emitted by a [Client](super::Client).
+///
+/// The trait is implemented for any `FnMut(&ClientEvent)` closure, so listeners that forward
+/// events to a channel or a log can be registered without declaring a new type.
+pub trait ClientEventListener {
+    /// Called once for every event emitted by the client, in the order in which they happen.
+    fn on_event(&mut self, event: &ClientEvent);
+}
+
+impl<F: FnMut(&ClientEvent)> ClientEventListener for F {
+    fn on_event(&mut self, event: &ClientEvent) {
+        self(event)
+    }
+}
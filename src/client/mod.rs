@@ -1,23 +1,40 @@
+use alloc::rc::Rc;
+
 use miden_objects::{
-    crypto::rand::{FeltRng, RpoRandomCoin},
-    Felt,
+    crypto::{
+        hash::rpo::Rpo256,
+        rand::{FeltRng, RpoRandomCoin},
+    },
+    Felt, Word, ZERO,
 };
 use miden_tx::TransactionExecutor;
 use rand::Rng;
 
-use crate::{errors::ClientError, store::Store};
+#[cfg(all(feature = "sqlite", feature = "tonic"))]
+use crate::{config::ClientConfig, store::sqlite_store::SqliteStore};
+use crate::{
+    errors::{ClientError, StoreError},
+    store::Store,
+};
 
 pub mod rpc;
 use rpc::NodeRpcClient;
+#[cfg(all(feature = "sqlite", feature = "tonic"))]
+use rpc::{FailoverRpcClient, TonicRpcClient};
 
 pub mod accounts;
-#[cfg(test)]
 mod chain_data;
+pub mod events;
 mod note_screener;
 mod notes;
-pub(crate) mod sync;
+pub mod sync;
+#[cfg(feature = "sync_service")]
+pub mod sync_service;
 pub mod transactions;
+use events::{ClientEvent, ClientEventListener};
 pub(crate) use note_screener::NoteScreener;
+pub use notes::NoteSpentStatus;
+use sync::{SyncConfig, SyncDecoys};
 
 use crate::store::data_store::ClientDataStore;
 
@@ -34,7 +51,8 @@ use crate::store::data_store::ClientDataStore;
 /// - Executes, proves, and submits transactions to the network as directed by the user.
 pub struct Client<N: NodeRpcClient, R: FeltRng, S: Store> {
     /// The client's store, which provides a way to write and read entities to provide persistence.
-    store: S,
+    /// The same instance is shared with the transaction executor's [ClientDataStore].
+    store: Rc<S>,
     /// An instance of [FeltRng] which provides randomness tools for generating new keys,
     /// serial numbers, etc.
     rng: R,
@@ -42,6 +60,12 @@ pub struct Client<N: NodeRpcClient, R: FeltRng, S: Store> {
     /// Miden node.
     rpc_api: N,
     tx_executor: TransactionExecutor<ClientDataStore<S>>,
+    /// Listeners notified of every [ClientEvent] emitted by the client.
+    event_listeners: Vec<Box<dyn ClientEventListener>>,
+    /// Settings that control what the sync requests reveal about the client's state.
+    sync_config: SyncConfig,
+    /// Decoy values sent in every sync request, drawn according to `sync_config`.
+    sync_decoys: SyncDecoys,
 }
 
 impl<N: NodeRpcClient, R: FeltRng, S: Store> Client<N, R, S> {
@@ -53,42 +77,230 @@ impl<N: NodeRpcClient, R: FeltRng, S: Store> Client<N, R, S> {
     /// ## Arguments
     ///
     /// - `api`: An instance of [NodeRpcClient] which provides a way for the client to connect to the Miden node.
-    /// - `store`: An instance of [Store], which provides a way to write and read entities to provide persistence.
-    /// - `executor_store`: An instance of [Store] that provides a way for [TransactionExecutor] to
-    /// retrieve relevant inputs at the moment of transaction execution. It should be the same
-    /// store as the one for `store`, but it doesn't have to be the **same instance**
+    /// - `rng`: An instance of [FeltRng] which provides randomness tools for generating new keys,
+    /// serial numbers, etc.
+    /// - `store`: A shared instance of [Store], which provides a way to write and read entities to
+    /// provide persistence. The same instance is used by the [TransactionExecutor] to retrieve
+    /// relevant inputs at the moment of transaction execution.
+    ///
+    /// See [ClientBuilder] for a more convenient way of building a client.
     ///
     /// # Errors
     ///
     /// Returns an error if the client could not be instantiated.
-    pub fn new(api: N, rng: R, store: S, executor_store: S) -> Result<Self, ClientError> {
+    pub fn new(api: N, rng: R, store: Rc<S>) -> Result<Self, ClientError> {
+        let executor_store = ClientDataStore::new(store.clone());
+
         Ok(Self {
             store,
             rng,
             rpc_api: api,
-            tx_executor: TransactionExecutor::new(ClientDataStore::new(executor_store)),
+            tx_executor: TransactionExecutor::new(executor_store),
+            event_listeners: Vec::new(),
+            sync_config: SyncConfig::default(),
+            sync_decoys: SyncDecoys::default(),
         })
     }
 
+    // SYNC CONFIG
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the [SyncConfig] used to build sync requests.
+    pub fn sync_config(&self) -> &SyncConfig {
+        &self.sync_config
+    }
+
+    /// Sets the [SyncConfig] used to build sync requests, drawing a new set of decoys.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [ClientError::InvalidSyncConfig] if the configured prefix widths are not
+    /// supported.
+    pub fn set_sync_config(&mut self, sync_config: SyncConfig) -> Result<(), ClientError> {
+        sync_config.validate().map_err(ClientError::InvalidSyncConfig)?;
+
+        self.sync_decoys = SyncDecoys::random(&sync_config);
+        self.sync_config = sync_config;
+        Ok(())
+    }
+
+    // EVENTS
+    // --------------------------------------------------------------------------------------------
+
+    /// Registers a listener that gets notified of every [ClientEvent] emitted from now on.
+    ///
+    /// Events are emitted by [Client::sync_state] and [Client::submit_transaction] once the
+    /// corresponding changes have been persisted to the store.
+    pub fn subscribe<L: ClientEventListener + 'static>(&mut self, listener: L) {
+        self.event_listeners.push(Box::new(listener));
+    }
+
+    /// Notifies all registered listeners of the provided events, in order.
+    fn emit_events(&mut self, events: &[ClientEvent]) {
+        for event in events {
+            for listener in self.event_listeners.iter_mut() {
+                listener.on_event(event);
+            }
+        }
+    }
+
     #[cfg(any(test, feature = "test_utils"))]
     pub fn rpc_api(&mut self) -> &mut N {
         &mut self.rpc_api
     }
 
     #[cfg(any(test, feature = "test_utils"))]
-    pub fn store(&mut self) -> &mut S {
-        &mut self.store
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+}
+
+// CLIENT BUILDER
+// ================================================================================================
+
+/// Builder for [Client] instances.
+///
+/// The builder opens (or receives) a single store instance and shares it between the [Client] and
+/// the [ClientDataStore] used by the transaction executor, so both always observe the same state.
+///
+/// All components are required; [ClientBuilder::build] returns
+/// [ClientError::MissingClientComponent] if any of them was not provided. The [SyncConfig] is
+/// optional and defaults to [SyncConfig::default].
+pub struct ClientBuilder<N: NodeRpcClient, R: FeltRng, S: Store> {
+    rpc_api: Option<N>,
+    rng: Option<R>,
+    store: Option<Rc<S>>,
+    sync_config: SyncConfig,
+}
+
+impl<N: NodeRpcClient, R: FeltRng, S: Store> ClientBuilder<N, R, S> {
+    /// Returns a new, empty [ClientBuilder].
+    pub fn new() -> Self {
+        Self {
+            rpc_api: None,
+            rng: None,
+            store: None,
+            sync_config: SyncConfig::default(),
+        }
+    }
+
+    /// Sets the [NodeRpcClient] used to communicate with the Miden node.
+    pub fn with_rpc_api(mut self, rpc_api: N) -> Self {
+        self.rpc_api = Some(rpc_api);
+        self
+    }
+
+    /// Sets the [FeltRng] used to generate the keys and seeds of new accounts that are not derived
+    /// from a master seed.
+    pub fn with_rng(mut self, rng: R) -> Self {
+        self.rng = Some(rng);
+        self
+    }
+
+    /// Sets the [Store] used by the client. The store is opened only once and shared between the
+    /// client and its transaction executor.
+    pub fn with_store(self, store: S) -> Self {
+        self.with_shared_store(Rc::new(store))
+    }
+
+    /// Sets an already shared [Store] instance to be used by the client.
+    pub fn with_shared_store(mut self, store: Rc<S>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Sets the [SyncConfig] used to build sync requests.
+    pub fn with_sync_config(mut self, sync_config: SyncConfig) -> Self {
+        self.sync_config = sync_config;
+        self
+    }
+
+    /// Builds the [Client].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [ClientError::MissingClientComponent] if the RPC API, the RNG or the store was
+    /// not set, or a [ClientError::InvalidSyncConfig] if the sync configuration is not valid.
+    pub fn build(self) -> Result<Client<N, R, S>, ClientError> {
+        let rpc_api = self.rpc_api.ok_or(ClientError::MissingClientComponent("rpc api"))?;
+        let rng = self.rng.ok_or(ClientError::MissingClientComponent("rng"))?;
+        let store = self.store.ok_or(ClientError::MissingClientComponent("store"))?;
+
+        let mut client = Client::new(rpc_api, rng, store)?;
+        client.set_sync_config(self.sync_config)?;
+        Ok(client)
+    }
+}
+
+impl<N: NodeRpcClient, R: FeltRng, S: Store> Default for ClientBuilder<N, R, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(all(feature = "sqlite", feature = "tonic"))]
+impl ClientBuilder<FailoverRpcClient<TonicRpcClient>, RpoRandomCoin, SqliteStore> {
+    /// Returns a [ClientBuilder] with a [SqliteStore] and a [FailoverRpcClient] over a
+    /// [TonicRpcClient] for each configured endpoint, set up from the provided [ClientConfig].
+    ///
+    /// If the store already holds a genesis block, nodes with a different one are not used.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store could not be opened or the RPC settings are invalid, for
+    /// instance because a TLS certificate could not be read.
+    pub fn from_config(config: &ClientConfig) -> Result<Self, ClientError> {
+        let mut rpc_api = FailoverRpcClient::try_from(&config.rpc)?;
+        let store = SqliteStore::new(config.into())?;
+
+        match store.get_block_header_by_num(0) {
+            Ok((genesis, _)) => rpc_api = rpc_api.with_genesis(genesis.hash()),
+            Err(StoreError::BlockHeaderNotFound(0)) => {},
+            Err(err) => return Err(err.into()),
+        }
+
+        // the client's own coin only seeds the keys of accounts that are not derived from a
+        // master seed, which are kept in the store, so it is not taken from the persisted ones
+        let coin_seed: [u64; 4] = rand::thread_rng().gen();
+        let rng = RpoRandomCoin::new(coin_seed.map(Felt::new));
+
+        Ok(Self::new()
+            .with_rpc_api(rpc_api)
+            .with_rng(rng)
+            .with_store(store)
+            .with_sync_config(config.sync))
     }
 }
 
 // HELPERS
 // --------------------------------------------------------------------------------------------
 
-/// Gets [RpoRandomCoin] from the client
-pub fn get_random_coin() -> RpoRandomCoin {
-    // TODO: Initialize coin status once along with the client and persist status for retrieval
-    let mut rng = rand::thread_rng();
-    let coin_seed: [u64; 4] = rng.gen();
+/// Returns a new [RpoRandomCoin] derived from the seed persisted in the store, advancing the
+/// persisted counter so that every call yields a different coin.
+///
+/// The client calls this once for every note it creates from a transaction template, so each
+/// counter value identifies a single note. If the store does not hold a seed yet, a random one is
+/// generated and persisted first.
+pub fn get_random_coin<S: Store>(store: &S) -> Result<RpoRandomCoin, StoreError> {
+    if store.get_rng_seed()?.is_none() {
+        let mut rng = rand::thread_rng();
+        let coin_seed: [u64; 4] = rng.gen();
+        store.set_rng_seed(coin_seed.map(Felt::new))?;
+    }
+
+    let (seed, counter) = store.advance_rng_counter()?;
+
+    Ok(derive_random_coin(seed, counter))
+}
+
+/// Deterministically derives the [RpoRandomCoin] with index `counter` from the provided `seed`.
+///
+/// The serial number of a note created by the client is the first word drawn from the coin
+/// [get_random_coin] handed out for it. For clients set up with a master seed, the notes created
+/// with these serial numbers can be re-derived with [Client::recover_created_note].
+pub fn derive_random_coin(seed: Word, counter: u64) -> RpoRandomCoin {
+    let counter_word: Word = [Felt::new(counter), ZERO, ZERO, ZERO];
+    let coin_seed = Rpo256::merge(&[seed.into(), counter_word.into()]);
 
-    RpoRandomCoin::new(coin_seed.map(Felt::new))
+    RpoRandomCoin::new(coin_seed.into())
 }
@@ -1,11 +1,54 @@
-use miden_objects::{crypto::rand::FeltRng, notes::NoteId};
+use miden_objects::{
+    crypto::{merkle::SmtProof, rand::FeltRng},
+    notes::NoteId,
+    Digest, Word,
+};
 
 use super::{rpc::NodeRpcClient, Client};
 use crate::{
-    errors::ClientError,
-    store::{InputNoteRecord, NoteFilter, Store},
+    errors::{ClientError, NodeRpcClientError, VerificationError},
+    store::{ExpectedNoteRecord, InputNoteRecord, NoteFilter, NoteStatus, OutputNoteRecord, Store},
 };
 
+// NOTE SPENT STATUS
+// ================================================================================================
+
+/// Whether a note was spent, as proven by the node against its current nullifier tree.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NoteSpentStatus {
+    nullifier: Digest,
+    spent_at: Option<u32>,
+    proof: SmtProof,
+}
+
+impl NoteSpentStatus {
+    /// Returns the nullifier of the note.
+    pub fn nullifier(&self) -> Digest {
+        self.nullifier
+    }
+
+    /// Returns whether the note was spent.
+    pub fn is_spent(&self) -> bool {
+        self.spent_at.is_some()
+    }
+
+    /// Returns the number of the block in which the note was spent, if it was.
+    pub fn spent_at(&self) -> Option<u32> {
+        self.spent_at
+    }
+
+    /// Returns the root of the node's nullifier tree that the proof opens, which matches the
+    /// nullifier root of the block at the client's sync height.
+    pub fn nullifier_root(&self) -> Digest {
+        self.proof.compute_root()
+    }
+
+    /// Returns the proof of the nullifier's value in the node's nullifier tree.
+    pub fn proof(&self) -> &SmtProof {
+        &self.proof
+    }
+}
+
 impl<N: NodeRpcClient, R: FeltRng, S: Store> Client<N, R, S> {
     // INPUT NOTE DATA RETRIEVAL
     // --------------------------------------------------------------------------------------------
@@ -20,11 +63,116 @@ impl<N: NodeRpcClient, R: FeltRng, S: Store> Client<N, R, S> {
         self.store.get_input_note(note_id).map_err(|err| err.into())
     }
 
+    /// Returns the private notes that were committed for the client's tags, but whose details
+    /// haven't been imported yet.
+    pub fn get_expected_notes(&self) -> Result<Vec<ExpectedNoteRecord>, ClientError> {
+        self.store.get_expected_notes().map_err(|err| err.into())
+    }
+
+    /// Asks the node whether the input note with the specified ID was spent, without syncing.
+    ///
+    /// Only the note's nullifier is sent to the node, which answers with a proof of its value in
+    /// the node's nullifier tree. The proof is verified against the nullifier root of the block
+    /// at the client's sync height, whose header was authenticated against the chain MMR when
+    /// syncing, so the client must be synced to the node's chain tip. The local state of the note
+    /// is not updated.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the note is not an input note of the client, if the request fails, if
+    /// the received proof doesn't open the nullifier tree at the note's nullifier or if the tree's
+    /// root differs from the one of the block at the sync height, such as when the node's chain
+    /// advanced since the last sync.
+    pub async fn check_note_spent(
+        &mut self,
+        note_id: NoteId,
+    ) -> Result<NoteSpentStatus, ClientError> {
+        let note = self.store.get_input_note(note_id)?;
+        let nullifier = Digest::try_from(note.nullifier())?;
+
+        let proof = self.rpc_api.check_nullifiers(&[nullifier]).await?.pop().ok_or(
+            NodeRpcClientError::ExpectedFieldMissing(
+                "CheckNullifiers response should have a proof".to_string(),
+            ),
+        )?;
+
+        let sync_height = self.store.get_sync_height()?;
+        let (block_header, _) = self.store.get_block_header_by_num(sync_height)?;
+        let nullifier_root = proof.compute_root();
+        if nullifier_root != block_header.nullifier_root() {
+            return Err(VerificationError::NullifierRootMismatch(
+                sync_height,
+                block_header.nullifier_root(),
+                nullifier_root,
+            )
+            .into());
+        }
+
+        // Spent nullifiers map to the number of the block in which they were spent
+        let value = proof
+            .get(&nullifier)
+            .ok_or(VerificationError::InvalidNullifierProof(nullifier))?;
+        let spent_at = (value != Word::default()).then(|| value[0].as_int() as u32);
+
+        Ok(NoteSpentStatus { nullifier, spent_at, proof })
+    }
+
+    // OUTPUT NOTE DATA RETRIEVAL
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns output notes managed by this client.
+    pub fn get_output_notes(
+        &self,
+        filter: NoteFilter,
+    ) -> Result<Vec<OutputNoteRecord>, ClientError> {
+        self.store.get_output_notes(filter).map_err(|err| err.into())
+    }
+
     // INPUT NOTE CREATION
     // --------------------------------------------------------------------------------------------
 
     /// Imports a new input note into the client's store.
-    pub fn import_input_note(&mut self, note: InputNoteRecord) -> Result<(), ClientError> {
+    ///
+    /// If the note was received during a sync as an expected note, it's imported as committed
+    /// using the inclusion proof received back then. Notes imported without metadata keep the one
+    /// received from the node, whose `aux` is zero as the node does not send it for off-chain
+    /// notes.
+    ///
+    /// If the imported note has metadata, its inclusion proof is verified against the note root
+    /// of the block it was committed in. If the client synced past that block without tracking
+    /// it, the block header and the chain MMR nodes needed to authenticate it are fetched from the
+    /// node, so that the note can be consumed right away.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the inclusion proof doesn't authenticate the note with its metadata or
+    /// if the block it was committed in can't be authenticated against the client's chain MMR.
+    pub async fn import_input_note(&mut self, note: InputNoteRecord) -> Result<(), ClientError> {
+        let expected_note = self
+            .store
+            .get_expected_notes()?
+            .into_iter()
+            .find(|expected_note| expected_note.id() == note.id());
+
+        let imported_metadata = note.metadata().copied();
+        let note = match expected_note {
+            Some(expected_note) if note.inclusion_proof().is_none() => InputNoteRecord::new(
+                note.id(),
+                note.recipient(),
+                note.assets().clone(),
+                NoteStatus::Committed,
+                Some(imported_metadata.unwrap_or(*expected_note.metadata())),
+                Some(expected_note.inclusion_proof().clone()),
+                note.details().clone(),
+            ),
+            _ => note,
+        };
+
+        if let Some(inclusion_proof) = note.inclusion_proof() {
+            self.track_note_block(note.id(), imported_metadata.as_ref(), inclusion_proof)
+                .await?;
+        }
+
         self.store.insert_input_note(&note).map_err(|err| err.into())
     }
 }
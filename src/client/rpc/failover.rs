@@ -0,0 +1,255 @@
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use miden_objects::{
+    accounts::{Account, AccountId},
+    crypto::merkle::{MmrProof, SmtProof},
+    notes::{NoteId, NoteTag},
+    transaction::ProvenTransaction,
+    BlockHeader, Digest,
+};
+use tracing::warn;
+
+use super::{NodeRpcClient, NoteDetails, StateSyncInfo};
+use crate::errors::NodeRpcClientError;
+
+// FAILOVER RPC CLIENT
+// ================================================================================================
+
+/// [NodeRpcClient] that sends requests to the first healthy node of an ordered list.
+///
+/// Nodes are probed with a request for the latest block header before being used, and are only
+/// used if their genesis block matches the expected one. When no genesis block is expected, the
+/// one of the first healthy node is pinned and required from the rest.
+///
+/// When a request fails because the node could not be reached, was unavailable or timed out, the
+/// request is sent again to the next healthy node. Transactions are only submitted to another
+/// node when the connection to the previous one could not be established, so that they are never
+/// submitted twice. Once a node is in use, it keeps being used until one of its requests fails.
+pub struct FailoverRpcClient<N: NodeRpcClient> {
+    nodes: Vec<FailoverNode<N>>,
+    current: Option<usize>,
+    genesis: Option<Digest>,
+}
+
+/// A node of a [FailoverRpcClient], along with the result of its genesis block verification.
+struct FailoverNode<N: NodeRpcClient> {
+    rpc_api: N,
+    genesis_verified: bool,
+    rejected: bool,
+}
+
+impl<N: NodeRpcClient> FailoverRpcClient<N> {
+    /// Returns a new instance of [FailoverRpcClient] over the provided clients, ordered by
+    /// preference.
+    pub fn new(rpc_apis: Vec<N>) -> Self {
+        let nodes = rpc_apis
+            .into_iter()
+            .map(|rpc_api| FailoverNode {
+                rpc_api,
+                genesis_verified: false,
+                rejected: false,
+            })
+            .collect();
+
+        Self { nodes, current: None, genesis: None }
+    }
+
+    /// Sets the hash of the genesis block that nodes must have to be used, typically the one of
+    /// the genesis block in the client's store.
+    pub fn with_genesis(mut self, genesis: Digest) -> Self {
+        self.genesis = Some(genesis);
+        self
+    }
+
+    /// Returns the index of the node requests are sent to, probing the nodes not in `failed` in
+    /// order if no node is in use.
+    async fn select_node(&mut self, failed: &[bool]) -> Result<usize, NodeRpcClientError> {
+        if let Some(current) = self.current {
+            if !failed[current] {
+                return Ok(current);
+            }
+        }
+
+        for index in 0..self.nodes.len() {
+            if failed[index] || self.nodes[index].rejected {
+                continue;
+            }
+
+            match self.probe(index).await {
+                Ok(()) => {
+                    self.current = Some(index);
+                    return Ok(index);
+                },
+                Err(err) => warn!("RPC endpoint {index} is not healthy: {err}"),
+            }
+        }
+
+        Err(NodeRpcClientError::ConnectionError(
+            "no healthy RPC endpoint available".to_string(),
+        ))
+    }
+
+    /// Checks that the node answers requests and, the first time it is probed, that its genesis
+    /// block is the expected one. Nodes with a different genesis block are never used again.
+    async fn probe(&mut self, index: usize) -> Result<(), NodeRpcClientError> {
+        let node = &mut self.nodes[index];
+        node.rpc_api.get_block_header_by_number(None).await?;
+
+        if !node.genesis_verified {
+            let genesis = node.rpc_api.get_block_header_by_number(Some(0)).await?.hash();
+            match self.genesis {
+                Some(expected) if expected != genesis => {
+                    node.rejected = true;
+                    return Err(NodeRpcClientError::ConnectionError(format!(
+                        "genesis block {genesis} differs from the expected {expected}"
+                    )));
+                },
+                Some(_) => {},
+                None => self.genesis = Some(genesis),
+            }
+            node.genesis_verified = true;
+        }
+
+        Ok(())
+    }
+
+    /// Marks the node as failed for the current request, so that the next healthy one is used.
+    fn fail_over(&mut self, index: usize, err: &NodeRpcClientError, failed: &mut [bool]) {
+        warn!("request to RPC endpoint {index} failed, failing over: {err}");
+        failed[index] = true;
+        self.current = None;
+    }
+
+    /// Sends the request made by `request` to the node in use, failing over to the next healthy
+    /// node while the request fails because of the node. Requests that are not `idempotent` are
+    /// only sent again if they could not reach the node.
+    async fn with_failover<T, F>(
+        &mut self,
+        idempotent: bool,
+        mut request: F,
+    ) -> Result<T, NodeRpcClientError>
+    where
+        F: for<'a> FnMut(&'a mut N) -> BoxFuture<'a, Result<T, NodeRpcClientError>>,
+    {
+        let mut failed = vec![false; self.nodes.len()];
+        loop {
+            let index = self.select_node(&failed).await?;
+            match request(&mut self.nodes[index].rpc_api).await {
+                Err(err) if is_node_failure(&err, idempotent) => {
+                    self.fail_over(index, &err, &mut failed)
+                },
+                result => return result,
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<N: NodeRpcClient + Send> NodeRpcClient for FailoverRpcClient<N> {
+    async fn submit_proven_transaction(
+        &mut self,
+        proven_transaction: ProvenTransaction,
+    ) -> Result<(), NodeRpcClientError> {
+        self.with_failover(false, |rpc_api| {
+            rpc_api.submit_proven_transaction(proven_transaction.clone())
+        })
+        .await
+    }
+
+    async fn get_block_header_by_number(
+        &mut self,
+        block_number: Option<u32>,
+    ) -> Result<BlockHeader, NodeRpcClientError> {
+        self.with_failover(true, |rpc_api| rpc_api.get_block_header_by_number(block_number))
+            .await
+    }
+
+    async fn get_block_header_with_proof(
+        &mut self,
+        block_num: u32,
+        forest: u32,
+    ) -> Result<(BlockHeader, MmrProof), NodeRpcClientError> {
+        self.with_failover(true, |rpc_api| rpc_api.get_block_header_with_proof(block_num, forest))
+            .await
+    }
+
+    async fn get_notes_by_id(
+        &mut self,
+        note_ids: &[NoteId],
+    ) -> Result<Vec<NoteDetails>, NodeRpcClientError> {
+        let note_ids = note_ids.to_vec();
+        self.with_failover(true, |rpc_api| {
+            let note_ids = note_ids.clone();
+            Box::pin(async move { rpc_api.get_notes_by_id(&note_ids).await })
+        })
+        .await
+    }
+
+    async fn check_nullifiers(
+        &mut self,
+        nullifiers: &[Digest],
+    ) -> Result<Vec<SmtProof>, NodeRpcClientError> {
+        let nullifiers = nullifiers.to_vec();
+        self.with_failover(true, |rpc_api| {
+            let nullifiers = nullifiers.clone();
+            Box::pin(async move { rpc_api.check_nullifiers(&nullifiers).await })
+        })
+        .await
+    }
+
+    async fn sync_state(
+        &mut self,
+        block_num: u32,
+        account_ids: &[AccountId],
+        note_tags: &[NoteTag],
+        nullifiers_tags: &[u16],
+    ) -> Result<StateSyncInfo, NodeRpcClientError> {
+        let (account_ids, note_tags, nullifiers_tags) =
+            (account_ids.to_vec(), note_tags.to_vec(), nullifiers_tags.to_vec());
+        self.with_failover(true, |rpc_api| {
+            let (account_ids, note_tags, nullifiers_tags) =
+                (account_ids.clone(), note_tags.clone(), nullifiers_tags.clone());
+            Box::pin(async move {
+                rpc_api.sync_state(block_num, &account_ids, &note_tags, &nullifiers_tags).await
+            })
+        })
+        .await
+    }
+
+    async fn get_account_update(
+        &mut self,
+        account_id: AccountId,
+    ) -> Result<Account, NodeRpcClientError> {
+        self.with_failover(true, |rpc_api| rpc_api.get_account_update(account_id)).await
+    }
+
+    async fn get_account_updates(
+        &mut self,
+        account_ids: &[AccountId],
+        max_concurrent_requests: usize,
+    ) -> Result<Vec<Account>, NodeRpcClientError> {
+        let account_ids = account_ids.to_vec();
+        self.with_failover(true, |rpc_api| {
+            let account_ids = account_ids.clone();
+            Box::pin(async move {
+                rpc_api.get_account_updates(&account_ids, max_concurrent_requests).await
+            })
+        })
+        .await
+    }
+}
+
+// HELPERS
+// ================================================================================================
+
+/// Returns whether the error was caused by the node being unreachable or unavailable, in which
+/// case the request can be sent to another node. Requests that are not `idempotent` are only sent
+/// again if they could not reach the node. Errors returned by a node that processed the request
+/// are never failed over, as every node would be expected to return them as well.
+fn is_node_failure(err: &NodeRpcClientError, idempotent: bool) -> bool {
+    match err {
+        NodeRpcClientError::ConnectionError(_) => true,
+        NodeRpcClientError::NodeUnavailable(..) => idempotent,
+        _ => false,
+    }
+}
@@ -1,28 +1,53 @@
-use core::fmt;
+use alloc::collections::BTreeMap;
+use core::{fmt, time::Duration};
 
 use async_trait::async_trait;
 use miden_objects::{
     accounts::{Account, AccountId},
-    crypto::merkle::{MerklePath, MmrDelta},
-    notes::{Note, NoteId, NoteMetadata, NoteTag},
+    crypto::merkle::{MerklePath, MmrDelta, MmrProof, SmtProof},
+    notes::{Note, NoteId, NoteMetadata, NoteTag, NoteType},
     transaction::ProvenTransaction,
     BlockHeader, Digest,
 };
+use serde::{Deserialize, Serialize};
 
 use crate::errors::NodeRpcClientError;
 
+mod failover;
+pub use failover::FailoverRpcClient;
+
+#[cfg(any(test, feature = "test_utils"))]
+mod recording;
+#[cfg(any(test, feature = "test_utils"))]
+pub use recording::{RecordingRpcClient, ReplayRpcClient};
+
+#[cfg(feature = "tonic")]
 mod tonic_client;
+#[cfg(feature = "tonic")]
 pub use tonic_client::TonicRpcClient;
 
 // NOTE DETAILS
 // ================================================================================================
 
 /// Describes the possible responses from  the `GetNotesById` endpoint for a single note
+///
+/// The node does not send the `aux` field of the metadata of off-chain notes, which is set to
+/// zero, so it can't be used to compute the note's leaf in the block's note tree.
 pub enum NoteDetails {
     OffChain(NoteId, NoteMetadata, NoteInclusionDetails),
     Public(Note, NoteInclusionDetails),
 }
 
+impl NoteDetails {
+    /// Returns the ID of the note.
+    pub fn id(&self) -> NoteId {
+        match self {
+            NoteDetails::OffChain(note_id, ..) => *note_id,
+            NoteDetails::Public(note, _) => note.id(),
+        }
+    }
+}
+
 /// Contains information related to the note inclusion, but not related to the block header
 /// that contains the note
 pub struct NoteInclusionDetails {
@@ -63,6 +88,17 @@ pub trait NodeRpcClient {
         block_number: Option<u32>,
     ) -> Result<BlockHeader, NodeRpcClientError>;
 
+    /// Fetches the header of the block with the specified number along with its MMR proof, which
+    /// authenticates the block against the chain MMR with `forest` leaves (that is, the chain MMR
+    /// committed to by block `forest`).
+    ///
+    /// `block_num` must be lower than `forest`.
+    async fn get_block_header_with_proof(
+        &mut self,
+        block_num: u32,
+        forest: u32,
+    ) -> Result<(BlockHeader, MmrProof), NodeRpcClientError>;
+
     /// Fetches note-related data for a list of [NoteId] using the `/GetNotesById` rpc endpoint
     ///
     /// For any NoteType::Offchain note, the return data is only the [NoteMetadata], whereas
@@ -72,6 +108,17 @@ pub trait NodeRpcClient {
         note_ids: &[NoteId],
     ) -> Result<Vec<NoteDetails>, NodeRpcClientError>;
 
+    /// Fetches proofs of whether each of the provided nullifiers is in the node's nullifier tree
+    /// using the `/CheckNullifiers` rpc endpoint, returning them in the same order as
+    /// `nullifiers`.
+    ///
+    /// The value a proof opens a spent nullifier to holds the number of the block in which it was
+    /// spent, while unspent nullifiers open to an empty word.
+    async fn check_nullifiers(
+        &mut self,
+        nullifiers: &[Digest],
+    ) -> Result<Vec<SmtProof>, NodeRpcClientError>;
+
     /// Fetches info from the node necessary to perform a state sync using the
     /// `/SyncState` rpc endpoint
     ///
@@ -99,6 +146,24 @@ pub trait NodeRpcClient {
         &mut self,
         account_id: AccountId,
     ) -> Result<Account, NodeRpcClientError>;
+
+    /// Fetches the current state of each of the provided accounts using the `/GetAccountDetails`
+    /// rpc endpoint, returning them in the same order as `account_ids`.
+    ///
+    /// Implementations may send up to `max_concurrent_requests` requests at a time. The default
+    /// implementation calls [NodeRpcClient::get_account_update] for one account at a time.
+    async fn get_account_updates(
+        &mut self,
+        account_ids: &[AccountId],
+        max_concurrent_requests: usize,
+    ) -> Result<Vec<Account>, NodeRpcClientError> {
+        let _ = max_concurrent_requests;
+        let mut accounts = Vec::with_capacity(account_ids.len());
+        for account_id in account_ids {
+            accounts.push(self.get_account_update(*account_id).await?);
+        }
+        Ok(accounts)
+    }
 }
 
 // STATE SYNC INFO
@@ -116,14 +181,30 @@ pub struct StateSyncInfo {
     pub account_hash_updates: Vec<(AccountId, Digest)>,
     /// List of tuples of Note ID, Note Index and Merkle Path for all new notes
     pub note_inclusions: Vec<CommittedNote>,
-    /// List of nullifiers that identify spent notes
-    pub nullifiers: Vec<Digest>,
+    /// List of nullifiers that identify spent notes, along with the blocks they were consumed in
+    pub nullifiers: Vec<NullifierUpdate>,
+}
+
+// NULLIFIER UPDATE
+// ================================================================================================
+
+/// Represents a nullifier returned as part of a `SyncStateResponse`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NullifierUpdate {
+    /// Nullifier of the consumed note
+    pub nullifier: Digest,
+    /// Number of the block in which the note was consumed
+    pub block_num: u32,
 }
 
 // COMMITTED NOTE
 // ================================================================================================
 
 /// Represents a committed note, returned as part of a `SyncStateResponse`
+///
+/// The node does not send the `aux` field of the note's [NoteMetadata], so the value of the
+/// note's leaf in the block's note tree can only be computed for notes whose metadata the client
+/// already knows.
 pub struct CommittedNote {
     /// Note ID of the committed note
     note_id: NoteId,
@@ -131,8 +212,12 @@ pub struct CommittedNote {
     note_index: u32,
     /// Merkle path for the note merkle tree up to the block's note root
     merkle_path: MerklePath,
-    /// Note metadata
-    metadata: NoteMetadata,
+    /// ID of the account that created the note
+    sender: AccountId,
+    /// Type of the note
+    note_type: NoteType,
+    /// Tag of the note
+    tag: NoteTag,
 }
 
 impl CommittedNote {
@@ -140,13 +225,17 @@ impl CommittedNote {
         note_id: NoteId,
         note_index: u32,
         merkle_path: MerklePath,
-        metadata: NoteMetadata,
+        sender: AccountId,
+        note_type: NoteType,
+        tag: NoteTag,
     ) -> Self {
         Self {
             note_id,
             note_index,
             merkle_path,
-            metadata,
+            sender,
+            note_type,
+            tag,
         }
     }
 
@@ -162,32 +251,113 @@ impl CommittedNote {
         &self.merkle_path
     }
 
-    #[allow(dead_code)]
-    pub fn metadata(&self) -> NoteMetadata {
-        self.metadata
+    pub fn sender(&self) -> AccountId {
+        self.sender
+    }
+
+    pub fn note_type(&self) -> NoteType {
+        self.note_type
+    }
+
+    pub fn tag(&self) -> NoteTag {
+        self.tag
     }
 }
 
 // RPC API ENDPOINT
 // ================================================================================================
 //
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum NodeRpcClientEndpoint {
+    CheckNullifiers,
     GetAccountDetails,
     GetBlockHeaderByNumber,
+    GetNotesById,
     SyncState,
+    #[serde(rename = "submit_proven_transaction")]
     SubmitProvenTx,
 }
 
+impl NodeRpcClientEndpoint {
+    /// Returns whether a request to this endpoint can be safely sent again after a failure.
+    pub fn is_idempotent(&self) -> bool {
+        !matches!(self, NodeRpcClientEndpoint::SubmitProvenTx)
+    }
+}
+
 impl fmt::Display for NodeRpcClientEndpoint {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            NodeRpcClientEndpoint::CheckNullifiers => write!(f, "check_nullifiers"),
             NodeRpcClientEndpoint::GetAccountDetails => write!(f, "get_account_details"),
             NodeRpcClientEndpoint::GetBlockHeaderByNumber => {
                 write!(f, "get_block_header_by_number")
             },
+            NodeRpcClientEndpoint::GetNotesById => write!(f, "get_notes_by_id"),
             NodeRpcClientEndpoint::SyncState => write!(f, "sync_state"),
             NodeRpcClientEndpoint::SubmitProvenTx => write!(f, "submit_proven_transaction"),
         }
     }
 }
+
+// REQUEST POLICY
+// ================================================================================================
+
+/// Timeouts and retry settings applied to the requests sent to the node.
+///
+/// Requests to idempotent endpoints that time out or fail because of a transport error are
+/// retried up to `max_retries` times, waiting an exponentially increasing backoff between
+/// attempts. Requests to the other endpoints are only retried if the connection to the node
+/// could not be established, as in that case nothing was sent.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RequestPolicy {
+    /// Timeout applied to the requests of endpoints without a specific timeout.
+    pub timeout: Duration,
+    /// Timeouts of specific endpoints.
+    pub endpoint_timeouts: BTreeMap<NodeRpcClientEndpoint, Duration>,
+    /// Maximum number of times a failed request is retried.
+    pub max_retries: u32,
+    /// Time waited before the first retry, doubled on every subsequent one.
+    pub initial_backoff: Duration,
+    /// Upper bound of the time waited between retries.
+    pub max_backoff: Duration,
+}
+
+impl RequestPolicy {
+    /// Returns the timeout of the requests to `endpoint`.
+    pub fn timeout(&self, endpoint: NodeRpcClientEndpoint) -> Duration {
+        self.endpoint_timeouts.get(&endpoint).copied().unwrap_or(self.timeout)
+    }
+
+    /// Returns the time to wait before the specified retry, starting at 0.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .checked_mul(1 << retry.min(31))
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff))
+    }
+}
+
+impl Default for RequestPolicy {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_millis(DEFAULT_TIMEOUT_MS),
+            endpoint_timeouts: BTreeMap::new(),
+            max_retries: DEFAULT_MAX_RETRIES,
+            initial_backoff: Duration::from_millis(DEFAULT_INITIAL_BACKOFF_MS),
+            max_backoff: Duration::from_millis(DEFAULT_MAX_BACKOFF_MS),
+        }
+    }
+}
+
+/// Default timeout of the requests sent to the node, in milliseconds.
+const DEFAULT_TIMEOUT_MS: u64 = 10_000;
+
+/// Default number of times a failed request is retried.
+const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// Default time waited before the first retry, in milliseconds.
+const DEFAULT_INITIAL_BACKOFF_MS: u64 = 250;
+
+/// Default upper bound of the time waited between retries, in milliseconds.
+const DEFAULT_MAX_BACKOFF_MS: u64 = 5_000;
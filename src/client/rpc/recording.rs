@@ -0,0 +1,664 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Write},
+    path::Path,
+};
+
+use async_trait::async_trait;
+use miden_objects::{
+    accounts::{Account, AccountId},
+    crypto::merkle::{MerklePath, MmrDelta, MmrProof, SmtProof},
+    notes::{Note, NoteId, NoteMetadata, NoteTag, NoteType},
+    transaction::ProvenTransaction,
+    BlockHeader, Digest, Felt,
+};
+use miden_tx::utils::{Deserializable, DeserializationError, Serializable};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    CommittedNote, NodeRpcClient, NoteDetails, NoteInclusionDetails, NullifierUpdate, StateSyncInfo,
+};
+use crate::errors::NodeRpcClientError;
+
+// RECORDING RPC CLIENT
+// ================================================================================================
+
+/// [NodeRpcClient] that forwards requests to an inner client and records every request along
+/// with its response, so that they can be served back by a [ReplayRpcClient].
+///
+/// Each interaction is written as a line of JSON to the recording file as soon as the response
+/// is received. Accounts are always requested one at a time through
+/// [NodeRpcClient::get_account_update], so that each of them is recorded separately.
+pub struct RecordingRpcClient<N: NodeRpcClient> {
+    rpc_api: N,
+    recording: File,
+}
+
+impl<N: NodeRpcClient> RecordingRpcClient<N> {
+    /// Returns a new instance of [RecordingRpcClient] that forwards requests to `rpc_api` and
+    /// records them to the file at `path`, replacing its contents.
+    pub fn new(rpc_api: N, path: impl AsRef<Path>) -> Result<Self, NodeRpcClientError> {
+        let recording = File::create(path)
+            .map_err(|err| NodeRpcClientError::RecordingError(err.to_string()))?;
+
+        Ok(Self { rpc_api, recording })
+    }
+
+    /// Returns the inner client.
+    pub fn into_inner(self) -> N {
+        self.rpc_api
+    }
+
+    /// Writes the request along with the response built from `result` to the recording file.
+    fn record<T>(
+        &mut self,
+        request: RecordedRequest,
+        result: &Result<T, NodeRpcClientError>,
+        to_response: impl FnOnce(&T) -> RecordedResponse,
+    ) -> Result<(), NodeRpcClientError> {
+        let response = match result {
+            Ok(value) => Ok(to_response(value)),
+            Err(err) => Err(err.into()),
+        };
+
+        let interaction = serde_json::to_string(&RecordedInteraction { request, response })
+            .map_err(|err| NodeRpcClientError::RecordingError(err.to_string()))?;
+        writeln!(self.recording, "{interaction}")
+            .map_err(|err| NodeRpcClientError::RecordingError(err.to_string()))
+    }
+}
+
+#[async_trait]
+impl<N: NodeRpcClient + Send> NodeRpcClient for RecordingRpcClient<N> {
+    async fn submit_proven_transaction(
+        &mut self,
+        proven_transaction: ProvenTransaction,
+    ) -> Result<(), NodeRpcClientError> {
+        let request = RecordedRequest::SubmitProvenTransaction {
+            transaction: proven_transaction.to_bytes(),
+        };
+        let result = self.rpc_api.submit_proven_transaction(proven_transaction).await;
+        self.record(request, &result, |_| RecordedResponse::TransactionSubmitted)?;
+        result
+    }
+
+    async fn get_block_header_by_number(
+        &mut self,
+        block_number: Option<u32>,
+    ) -> Result<BlockHeader, NodeRpcClientError> {
+        let result = self.rpc_api.get_block_header_by_number(block_number).await;
+        let request = RecordedRequest::GetBlockHeaderByNumber { block_num: block_number };
+        self.record(request, &result, |block_header| RecordedResponse::BlockHeader(*block_header))?;
+        result
+    }
+
+    async fn get_block_header_with_proof(
+        &mut self,
+        block_num: u32,
+        forest: u32,
+    ) -> Result<(BlockHeader, MmrProof), NodeRpcClientError> {
+        let result = self.rpc_api.get_block_header_with_proof(block_num, forest).await;
+        let request = RecordedRequest::GetBlockHeaderWithProof { block_num, forest };
+        self.record(request, &result, |(block_header, proof)| {
+            RecordedResponse::BlockHeaderWithProof {
+                block_header: *block_header,
+                forest: proof.forest,
+                position: proof.position,
+                merkle_path: proof.merkle_path.clone(),
+            }
+        })?;
+        result
+    }
+
+    async fn get_notes_by_id(
+        &mut self,
+        note_ids: &[NoteId],
+    ) -> Result<Vec<NoteDetails>, NodeRpcClientError> {
+        let result = self.rpc_api.get_notes_by_id(note_ids).await;
+        let request = RecordedRequest::GetNotesById {
+            note_ids: note_ids.iter().map(|note_id| note_id.inner()).collect(),
+        };
+        self.record(request, &result, |notes| {
+            RecordedResponse::Notes(notes.iter().map(RecordedNoteDetails::from).collect())
+        })?;
+        result
+    }
+
+    async fn check_nullifiers(
+        &mut self,
+        nullifiers: &[Digest],
+    ) -> Result<Vec<SmtProof>, NodeRpcClientError> {
+        let result = self.rpc_api.check_nullifiers(nullifiers).await;
+        let request = RecordedRequest::CheckNullifiers { nullifiers: nullifiers.to_vec() };
+        self.record(request, &result, |proofs| {
+            RecordedResponse::NullifierProofs(proofs.iter().map(|proof| proof.to_bytes()).collect())
+        })?;
+        result
+    }
+
+    async fn sync_state(
+        &mut self,
+        block_num: u32,
+        account_ids: &[AccountId],
+        note_tags: &[NoteTag],
+        nullifiers_tags: &[u16],
+    ) -> Result<StateSyncInfo, NodeRpcClientError> {
+        let result = self
+            .rpc_api
+            .sync_state(block_num, account_ids, note_tags, nullifiers_tags)
+            .await;
+        let request =
+            RecordedRequest::sync_state(block_num, account_ids, note_tags, nullifiers_tags);
+        self.record(request, &result, |sync_info| {
+            RecordedResponse::SyncState(RecordedStateSyncInfo::from(sync_info))
+        })?;
+        result
+    }
+
+    async fn get_account_update(
+        &mut self,
+        account_id: AccountId,
+    ) -> Result<Account, NodeRpcClientError> {
+        let result = self.rpc_api.get_account_update(account_id).await;
+        let request = RecordedRequest::GetAccountUpdate { account_id: account_id.into() };
+        self.record(request, &result, |account| RecordedResponse::Account(account.to_bytes()))?;
+        result
+    }
+}
+
+// REPLAY RPC CLIENT
+// ================================================================================================
+
+/// [NodeRpcClient] that serves the responses recorded by a [RecordingRpcClient], without
+/// connecting to a node.
+///
+/// Each request is answered with the response of the first recorded interaction with an equal
+/// request that hasn't been served yet, so requests don't need to be sent in the order they were
+/// recorded. Recorded errors are returned with the same variant they were recorded with, except
+/// for [NodeRpcClientError::NoteError]s, which are returned as
+/// [NodeRpcClientError::ConversionFailure]s with the note error's message.
+pub struct ReplayRpcClient {
+    interactions: Vec<RecordedInteraction>,
+}
+
+impl ReplayRpcClient {
+    /// Returns a new instance of [ReplayRpcClient] that serves the interactions recorded to the
+    /// file at `path`.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, NodeRpcClientError> {
+        let recording =
+            File::open(path).map_err(|err| NodeRpcClientError::RecordingError(err.to_string()))?;
+
+        let mut interactions = Vec::new();
+        for line in BufReader::new(recording).lines() {
+            let line = line.map_err(|err| NodeRpcClientError::RecordingError(err.to_string()))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let interaction = serde_json::from_str(&line)
+                .map_err(|err| NodeRpcClientError::RecordingError(err.to_string()))?;
+            interactions.push(interaction);
+        }
+
+        Ok(Self { interactions })
+    }
+
+    /// Returns the number of recorded interactions that haven't been served yet.
+    pub fn remaining_interactions(&self) -> usize {
+        self.interactions.len()
+    }
+
+    /// Removes the first recorded interaction with the specified request and returns its
+    /// response.
+    fn replay(&mut self, request: RecordedRequest) -> Result<RecordedResponse, NodeRpcClientError> {
+        let index = self
+            .interactions
+            .iter()
+            .position(|interaction| interaction.request == request)
+            .ok_or_else(|| {
+                NodeRpcClientError::RecordingError(format!(
+                    "no recorded response left for {request:?}"
+                ))
+            })?;
+
+        self.interactions.remove(index).response.map_err(NodeRpcClientError::from)
+    }
+}
+
+#[async_trait]
+impl NodeRpcClient for ReplayRpcClient {
+    async fn submit_proven_transaction(
+        &mut self,
+        proven_transaction: ProvenTransaction,
+    ) -> Result<(), NodeRpcClientError> {
+        let request = RecordedRequest::SubmitProvenTransaction {
+            transaction: proven_transaction.to_bytes(),
+        };
+        match self.replay(request)? {
+            RecordedResponse::TransactionSubmitted => Ok(()),
+            response => Err(unexpected_response(&response)),
+        }
+    }
+
+    async fn get_block_header_by_number(
+        &mut self,
+        block_number: Option<u32>,
+    ) -> Result<BlockHeader, NodeRpcClientError> {
+        match self.replay(RecordedRequest::GetBlockHeaderByNumber { block_num: block_number })? {
+            RecordedResponse::BlockHeader(block_header) => Ok(block_header),
+            response => Err(unexpected_response(&response)),
+        }
+    }
+
+    async fn get_block_header_with_proof(
+        &mut self,
+        block_num: u32,
+        forest: u32,
+    ) -> Result<(BlockHeader, MmrProof), NodeRpcClientError> {
+        match self.replay(RecordedRequest::GetBlockHeaderWithProof { block_num, forest })? {
+            RecordedResponse::BlockHeaderWithProof {
+                block_header,
+                forest,
+                position,
+                merkle_path,
+            } => Ok((block_header, MmrProof { forest, position, merkle_path })),
+            response => Err(unexpected_response(&response)),
+        }
+    }
+
+    async fn get_notes_by_id(
+        &mut self,
+        note_ids: &[NoteId],
+    ) -> Result<Vec<NoteDetails>, NodeRpcClientError> {
+        let request = RecordedRequest::GetNotesById {
+            note_ids: note_ids.iter().map(|note_id| note_id.inner()).collect(),
+        };
+        match self.replay(request)? {
+            RecordedResponse::Notes(notes) => {
+                notes.into_iter().map(NoteDetails::try_from).collect()
+            },
+            response => Err(unexpected_response(&response)),
+        }
+    }
+
+    async fn check_nullifiers(
+        &mut self,
+        nullifiers: &[Digest],
+    ) -> Result<Vec<SmtProof>, NodeRpcClientError> {
+        match self.replay(RecordedRequest::CheckNullifiers { nullifiers: nullifiers.to_vec() })? {
+            RecordedResponse::NullifierProofs(proofs) => proofs
+                .iter()
+                .map(|proof| SmtProof::read_from_bytes(proof).map_err(NodeRpcClientError::from))
+                .collect(),
+            response => Err(unexpected_response(&response)),
+        }
+    }
+
+    async fn sync_state(
+        &mut self,
+        block_num: u32,
+        account_ids: &[AccountId],
+        note_tags: &[NoteTag],
+        nullifiers_tags: &[u16],
+    ) -> Result<StateSyncInfo, NodeRpcClientError> {
+        let request =
+            RecordedRequest::sync_state(block_num, account_ids, note_tags, nullifiers_tags);
+        match self.replay(request)? {
+            RecordedResponse::SyncState(sync_info) => sync_info.try_into(),
+            response => Err(unexpected_response(&response)),
+        }
+    }
+
+    async fn get_account_update(
+        &mut self,
+        account_id: AccountId,
+    ) -> Result<Account, NodeRpcClientError> {
+        match self.replay(RecordedRequest::GetAccountUpdate { account_id: account_id.into() })? {
+            RecordedResponse::Account(account) => Ok(Account::read_from_bytes(&account)?),
+            response => Err(unexpected_response(&response)),
+        }
+    }
+}
+
+// RECORDED INTERACTIONS
+// ================================================================================================
+
+/// A request sent to the node along with its response, or the error it failed with.
+#[derive(Deserialize, Serialize)]
+struct RecordedInteraction {
+    request: RecordedRequest,
+    response: Result<RecordedResponse, RecordedError>,
+}
+
+/// The parameters of a request to the node.
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[serde(tag = "endpoint", rename_all = "snake_case")]
+enum RecordedRequest {
+    SubmitProvenTransaction {
+        transaction: Vec<u8>,
+    },
+    GetBlockHeaderByNumber {
+        block_num: Option<u32>,
+    },
+    GetBlockHeaderWithProof {
+        block_num: u32,
+        forest: u32,
+    },
+    GetNotesById {
+        note_ids: Vec<Digest>,
+    },
+    CheckNullifiers {
+        nullifiers: Vec<Digest>,
+    },
+    SyncState {
+        block_num: u32,
+        account_ids: Vec<u64>,
+        note_tags: Vec<u32>,
+        nullifiers_tags: Vec<u16>,
+    },
+    GetAccountUpdate {
+        account_id: u64,
+    },
+}
+
+impl RecordedRequest {
+    fn sync_state(
+        block_num: u32,
+        account_ids: &[AccountId],
+        note_tags: &[NoteTag],
+        nullifiers_tags: &[u16],
+    ) -> Self {
+        RecordedRequest::SyncState {
+            block_num,
+            account_ids: account_ids.iter().map(|&account_id| account_id.into()).collect(),
+            note_tags: note_tags.iter().map(|&note_tag| note_tag.into()).collect(),
+            nullifiers_tags: nullifiers_tags.to_vec(),
+        }
+    }
+}
+
+/// A response received from the node, with the objects that don't support serde serialized to
+/// bytes.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum RecordedResponse {
+    TransactionSubmitted,
+    BlockHeader(BlockHeader),
+    BlockHeaderWithProof {
+        block_header: BlockHeader,
+        forest: usize,
+        position: usize,
+        merkle_path: MerklePath,
+    },
+    Notes(Vec<RecordedNoteDetails>),
+    NullifierProofs(Vec<Vec<u8>>),
+    SyncState(RecordedStateSyncInfo),
+    Account(Vec<u8>),
+}
+
+/// An error a request to the node failed with, keeping its variant so that it's replayed as the
+/// same [NodeRpcClientError].
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum RecordedError {
+    ConnectionError(String),
+    ConversionFailure(String),
+    DeserializationError(String),
+    ExpectedFieldMissing(String),
+    InvalidAccountReceived(String),
+    InvalidConfig(String),
+    NodeUnavailable { endpoint: String, message: String },
+    NoteError(String),
+    NotFound { endpoint: String, message: String },
+    RecordingError(String),
+    RequestError { endpoint: String, message: String },
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum RecordedNoteDetails {
+    OffChain {
+        note_id: Digest,
+        metadata: NoteMetadata,
+        inclusion_details: RecordedInclusionDetails,
+    },
+    Public {
+        note: Vec<u8>,
+        inclusion_details: RecordedInclusionDetails,
+    },
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct RecordedInclusionDetails {
+    block_num: u32,
+    note_index: u32,
+    merkle_path: MerklePath,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct RecordedStateSyncInfo {
+    chain_tip: u32,
+    block_header: BlockHeader,
+    mmr_delta_forest: usize,
+    mmr_delta_data: Vec<Digest>,
+    account_hash_updates: Vec<(u64, Digest)>,
+    note_inclusions: Vec<RecordedCommittedNote>,
+    nullifiers: Vec<(Digest, u32)>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct RecordedCommittedNote {
+    note_id: Digest,
+    note_index: u32,
+    merkle_path: MerklePath,
+    sender: u64,
+    note_type: u8,
+    tag: u32,
+}
+
+// CONVERSIONS
+// ================================================================================================
+
+impl From<&NodeRpcClientError> for RecordedError {
+    fn from(err: &NodeRpcClientError) -> Self {
+        match err {
+            NodeRpcClientError::ConnectionError(err) => RecordedError::ConnectionError(err.clone()),
+            NodeRpcClientError::ConversionFailure(err) => {
+                RecordedError::ConversionFailure(err.clone())
+            },
+            NodeRpcClientError::DeserializationError(err) => {
+                RecordedError::DeserializationError(err.to_string())
+            },
+            NodeRpcClientError::ExpectedFieldMissing(err) => {
+                RecordedError::ExpectedFieldMissing(err.clone())
+            },
+            NodeRpcClientError::InvalidAccountReceived(err) => {
+                RecordedError::InvalidAccountReceived(err.clone())
+            },
+            NodeRpcClientError::InvalidConfig(err) => RecordedError::InvalidConfig(err.clone()),
+            NodeRpcClientError::NodeUnavailable(endpoint, message) => {
+                RecordedError::NodeUnavailable {
+                    endpoint: endpoint.clone(),
+                    message: message.clone(),
+                }
+            },
+            NodeRpcClientError::NoteError(err) => RecordedError::NoteError(err.to_string()),
+            NodeRpcClientError::NotFound(endpoint, message) => RecordedError::NotFound {
+                endpoint: endpoint.clone(),
+                message: message.clone(),
+            },
+            NodeRpcClientError::RecordingError(err) => RecordedError::RecordingError(err.clone()),
+            NodeRpcClientError::RequestError(endpoint, message) => RecordedError::RequestError {
+                endpoint: endpoint.clone(),
+                message: message.clone(),
+            },
+        }
+    }
+}
+
+impl From<RecordedError> for NodeRpcClientError {
+    fn from(err: RecordedError) -> Self {
+        match err {
+            RecordedError::ConnectionError(err) => NodeRpcClientError::ConnectionError(err),
+            RecordedError::ConversionFailure(err) => NodeRpcClientError::ConversionFailure(err),
+            RecordedError::DeserializationError(err) => {
+                NodeRpcClientError::DeserializationError(DeserializationError::InvalidValue(err))
+            },
+            RecordedError::ExpectedFieldMissing(err) => {
+                NodeRpcClientError::ExpectedFieldMissing(err)
+            },
+            RecordedError::InvalidAccountReceived(err) => {
+                NodeRpcClientError::InvalidAccountReceived(err)
+            },
+            RecordedError::InvalidConfig(err) => NodeRpcClientError::InvalidConfig(err),
+            RecordedError::NodeUnavailable { endpoint, message } => {
+                NodeRpcClientError::NodeUnavailable(endpoint, message)
+            },
+            // Note errors can't be built back from their message
+            RecordedError::NoteError(err) => NodeRpcClientError::ConversionFailure(err),
+            RecordedError::NotFound { endpoint, message } => {
+                NodeRpcClientError::NotFound(endpoint, message)
+            },
+            RecordedError::RecordingError(err) => NodeRpcClientError::RecordingError(err),
+            RecordedError::RequestError { endpoint, message } => {
+                NodeRpcClientError::RequestError(endpoint, message)
+            },
+        }
+    }
+}
+
+impl From<&NoteInclusionDetails> for RecordedInclusionDetails {
+    fn from(details: &NoteInclusionDetails) -> Self {
+        Self {
+            block_num: details.block_num,
+            note_index: details.note_index,
+            merkle_path: details.merkle_path.clone(),
+        }
+    }
+}
+
+impl From<RecordedInclusionDetails> for NoteInclusionDetails {
+    fn from(details: RecordedInclusionDetails) -> Self {
+        NoteInclusionDetails::new(details.block_num, details.note_index, details.merkle_path)
+    }
+}
+
+impl From<&NoteDetails> for RecordedNoteDetails {
+    fn from(details: &NoteDetails) -> Self {
+        match details {
+            NoteDetails::OffChain(note_id, metadata, inclusion_details) => {
+                RecordedNoteDetails::OffChain {
+                    note_id: note_id.inner(),
+                    metadata: *metadata,
+                    inclusion_details: inclusion_details.into(),
+                }
+            },
+            NoteDetails::Public(note, inclusion_details) => RecordedNoteDetails::Public {
+                note: note.to_bytes(),
+                inclusion_details: inclusion_details.into(),
+            },
+        }
+    }
+}
+
+impl TryFrom<RecordedNoteDetails> for NoteDetails {
+    type Error = NodeRpcClientError;
+
+    fn try_from(details: RecordedNoteDetails) -> Result<Self, Self::Error> {
+        match details {
+            RecordedNoteDetails::OffChain { note_id, metadata, inclusion_details } => {
+                Ok(NoteDetails::OffChain(NoteId::from(note_id), metadata, inclusion_details.into()))
+            },
+            RecordedNoteDetails::Public { note, inclusion_details } => {
+                Ok(NoteDetails::Public(Note::read_from_bytes(&note)?, inclusion_details.into()))
+            },
+        }
+    }
+}
+
+impl From<&StateSyncInfo> for RecordedStateSyncInfo {
+    fn from(sync_info: &StateSyncInfo) -> Self {
+        Self {
+            chain_tip: sync_info.chain_tip,
+            block_header: sync_info.block_header,
+            mmr_delta_forest: sync_info.mmr_delta.forest,
+            mmr_delta_data: sync_info.mmr_delta.data.clone(),
+            account_hash_updates: sync_info
+                .account_hash_updates
+                .iter()
+                .map(|(account_id, account_hash)| ((*account_id).into(), *account_hash))
+                .collect(),
+            note_inclusions: sync_info
+                .note_inclusions
+                .iter()
+                .map(|note| RecordedCommittedNote {
+                    note_id: note.note_id().inner(),
+                    note_index: note.note_index(),
+                    merkle_path: note.merkle_path().clone(),
+                    sender: note.sender().into(),
+                    note_type: note.note_type() as u8,
+                    tag: note.tag().into(),
+                })
+                .collect(),
+            nullifiers: sync_info
+                .nullifiers
+                .iter()
+                .map(|update| (update.nullifier, update.block_num))
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<RecordedStateSyncInfo> for StateSyncInfo {
+    type Error = NodeRpcClientError;
+
+    fn try_from(sync_info: RecordedStateSyncInfo) -> Result<Self, Self::Error> {
+        let account_hash_updates = sync_info
+            .account_hash_updates
+            .into_iter()
+            .map(|(account_id, account_hash)| {
+                AccountId::try_from(account_id).map(|account_id| (account_id, account_hash))
+            })
+            .collect::<Result<_, _>>()?;
+
+        let note_inclusions = sync_info
+            .note_inclusions
+            .into_iter()
+            .map(|note| {
+                Ok(CommittedNote::new(
+                    NoteId::from(note.note_id),
+                    note.note_index,
+                    note.merkle_path,
+                    AccountId::try_from(note.sender)?,
+                    NoteType::try_from(Felt::from(note.note_type))?,
+                    NoteTag::from(note.tag),
+                ))
+            })
+            .collect::<Result<_, NodeRpcClientError>>()?;
+
+        let nullifiers = sync_info
+            .nullifiers
+            .into_iter()
+            .map(|(nullifier, block_num)| NullifierUpdate { nullifier, block_num })
+            .collect();
+
+        Ok(StateSyncInfo {
+            chain_tip: sync_info.chain_tip,
+            block_header: sync_info.block_header,
+            mmr_delta: MmrDelta {
+                forest: sync_info.mmr_delta_forest,
+                data: sync_info.mmr_delta_data,
+            },
+            account_hash_updates,
+            note_inclusions,
+            nullifiers,
+        })
+    }
+}
+
+// HELPERS
+// ================================================================================================
+
+/// Returns the error for a recorded response that doesn't match the type of its request.
+fn unexpected_response(response: &RecordedResponse) -> NodeRpcClientError {
+    NodeRpcClientError::RecordingError(format!("unexpected recorded response {response:?}"))
+}
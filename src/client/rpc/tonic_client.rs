@@ -1,10 +1,13 @@
+use core::{future::Future, time::Duration};
+
 use async_trait::async_trait;
+use futures::{stream, StreamExt, TryStreamExt};
 use miden_node_proto::{
     errors::ConversionError,
     generated::{
         requests::{
-            GetAccountDetailsRequest, GetBlockHeaderByNumberRequest, GetNotesByIdRequest,
-            SubmitProvenTransactionRequest, SyncStateRequest,
+            CheckNullifiersRequest, GetAccountDetailsRequest, GetBlockHeaderByNumberRequest,
+            GetNotesByIdRequest, SubmitProvenTransactionRequest, SyncStateRequest,
         },
         responses::SyncStateResponse,
         rpc::api_client::ApiClient,
@@ -12,18 +15,27 @@ use miden_node_proto::{
 };
 use miden_objects::{
     accounts::{Account, AccountId},
+    crypto::merkle::{MerklePath, MerkleTree, MmrProof, NodeIndex, SmtProof},
     notes::{Note, NoteId, NoteMetadata, NoteTag, NoteType},
     transaction::ProvenTransaction,
     utils::Deserializable,
-    BlockHeader, Digest, Felt,
+    BlockHeader, Digest, Felt, Word,
 };
 use miden_tx::utils::Serializable;
-use tonic::transport::Channel;
+use tonic::{
+    metadata::{Ascii, MetadataKey, MetadataValue},
+    service::{interceptor::InterceptedService, Interceptor},
+    transport::{Certificate, Channel, ClientTlsConfig, Endpoint, Identity},
+    Code, Request, Response, Status,
+};
+use tracing::warn;
 
 use super::{
     CommittedNote, NodeRpcClient, NodeRpcClientEndpoint, NoteDetails, NoteInclusionDetails,
-    StateSyncInfo,
+    NullifierUpdate, RequestPolicy, StateSyncInfo,
 };
+#[cfg(feature = "config")]
+use crate::config::{Endpoint as EndpointConfig, RpcConfig, TlsConfig};
 use crate::errors::NodeRpcClientError;
 
 // TONIC RPC CLIENT
@@ -31,32 +43,205 @@ use crate::errors::NodeRpcClientError;
 
 /// Client for the Node RPC API using tonic
 ///
-/// Wraps the ApiClient which defers establishing a connection with a node until necessary
+/// Wraps the ApiClient which defers establishing a connection with a node until necessary. The
+/// connection is dropped and established again after a transport error, and requests are timed
+/// out and retried as specified by the client's [RequestPolicy].
+///
+/// Connections to `https` endpoints use TLS, with the default settings unless others are
+/// provided through [TonicRpcClient::with_tls_config]. TLS settings and metadata headers are
+/// rejected for any other endpoint, which would be connected to in plaintext.
+#[derive(Clone)]
 pub struct TonicRpcClient {
-    rpc_api: Option<ApiClient<Channel>>,
+    rpc_api: Option<RpcApi>,
     endpoint: String,
+    request_policy: RequestPolicy,
+    tls_config: Option<ClientTlsConfig>,
+    metadata: MetadataInterceptor,
 }
 
+/// The inner ApiClient, which adds the client's metadata headers to every request.
+type RpcApi = ApiClient<InterceptedService<Channel, MetadataInterceptor>>;
+
 impl TonicRpcClient {
     /// Returns a new instance of [TonicRpcClient] that'll do calls the `config_endpoint` provided
     pub fn new(config_endpoint: &str) -> TonicRpcClient {
         TonicRpcClient {
             rpc_api: None,
             endpoint: config_endpoint.to_string(),
+            request_policy: RequestPolicy::default(),
+            tls_config: None,
+            metadata: MetadataInterceptor::default(),
         }
     }
 
-    /// Takes care of establishing the RPC connection if not connected yet and returns a reference
-    /// to the inner ApiClient
-    async fn rpc_api(&mut self) -> Result<&mut ApiClient<Channel>, NodeRpcClientError> {
-        if self.rpc_api.is_some() {
-            Ok(self.rpc_api.as_mut().unwrap())
-        } else {
-            let rpc_api = ApiClient::connect(self.endpoint.clone())
-                .await
-                .map_err(|err| NodeRpcClientError::ConnectionError(err.to_string()))?;
-            Ok(self.rpc_api.insert(rpc_api))
+    /// Sets the timeouts and retry settings used for the requests sent to the node.
+    pub fn with_request_policy(mut self, request_policy: RequestPolicy) -> Self {
+        self.request_policy = request_policy;
+        self
+    }
+
+    /// Sets the TLS settings used to connect to the node, such as a custom certificate authority
+    /// or a client certificate. The endpoint must use the `https` protocol, or connecting to the
+    /// node fails with [NodeRpcClientError::InvalidConfig].
+    pub fn with_tls_config(mut self, tls_config: ClientTlsConfig) -> Self {
+        self.tls_config = Some(tls_config);
+        self
+    }
+
+    /// Adds a metadata header sent with every request, such as an API key or an authorization
+    /// token. The value is marked as sensitive so that it is not logged. The endpoint must use the
+    /// `https` protocol, so that the header is never sent in plaintext.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `key` is not a valid ASCII metadata key or `value` contains characters
+    /// not allowed in metadata values.
+    pub fn with_metadata(mut self, key: &str, value: &str) -> Result<Self, NodeRpcClientError> {
+        let metadata_key = MetadataKey::from_bytes(key.as_bytes()).map_err(|err| {
+            NodeRpcClientError::InvalidConfig(format!("invalid metadata key `{key}`: {err}"))
+        })?;
+        let mut metadata_value = MetadataValue::try_from(value).map_err(|err| {
+            NodeRpcClientError::InvalidConfig(format!("invalid value for metadata `{key}`: {err}"))
+        })?;
+        metadata_value.set_sensitive(true);
+
+        self.metadata.headers.push((metadata_key, metadata_value));
+        Ok(self)
+    }
+
+    /// Sends the request built by `send` to the specified endpoint, connecting to the node first
+    /// if not connected yet.
+    async fn send_request<T, F, Fut>(
+        &mut self,
+        endpoint: NodeRpcClientEndpoint,
+        send: F,
+    ) -> Result<T, NodeRpcClientError>
+    where
+        F: Fn(RpcApi) -> Fut + Send + Sync,
+        Fut: Future<Output = Result<Response<T>, Status>> + Send,
+        T: Send,
+    {
+        let timeout = self.request_policy.timeout(endpoint);
+        let mut retry = 0;
+
+        loop {
+            let (error, retriable) = match self.connect(timeout).await {
+                Ok(rpc_api) => match tokio::time::timeout(timeout, send(rpc_api)).await {
+                    Ok(Ok(response)) => return Ok(response.into_inner()),
+                    Ok(Err(status)) => {
+                        let transport_error = is_transport_error(&status);
+                        if transport_error {
+                            self.rpc_api = None;
+                        }
+                        let retriable = transport_error
+                            || matches!(
+                                status.code(),
+                                Code::DeadlineExceeded | Code::ResourceExhausted
+                            );
+
+                        let error = if status.code() == Code::NotFound {
+                            NodeRpcClientError::NotFound(
+                                endpoint.to_string(),
+                                status.message().to_string(),
+                            )
+                        } else if transport_error || status.code() == Code::DeadlineExceeded {
+                            NodeRpcClientError::NodeUnavailable(
+                                endpoint.to_string(),
+                                status.to_string(),
+                            )
+                        } else {
+                            NodeRpcClientError::RequestError(
+                                endpoint.to_string(),
+                                status.to_string(),
+                            )
+                        };
+
+                        (error, retriable && endpoint.is_idempotent())
+                    },
+                    Err(_) => {
+                        // The connection may be stalled, so a new one is established on retry
+                        self.rpc_api = None;
+                        (
+                            NodeRpcClientError::NodeUnavailable(
+                                endpoint.to_string(),
+                                format!("request timed out after {}ms", timeout.as_millis()),
+                            ),
+                            endpoint.is_idempotent(),
+                        )
+                    },
+                },
+                // Nothing was sent to the node, so connecting again is safe for every endpoint.
+                // Invalid settings fail the same way on every attempt, so they're not retried
+                Err(err) => {
+                    let retriable = !matches!(err, NodeRpcClientError::InvalidConfig(_));
+                    (err, retriable)
+                },
+            };
+
+            if !retriable || retry >= self.request_policy.max_retries {
+                return Err(error);
+            }
+
+            let backoff = self.request_policy.backoff(retry);
+            warn!("{error}, retrying in {}ms", backoff.as_millis());
+            tokio::time::sleep(backoff).await;
+            retry += 1;
+        }
+    }
+
+    /// Takes care of establishing the RPC connection if not connected yet and returns a handle
+    /// to the inner ApiClient, which shares the connection
+    ///
+    /// Returns [NodeRpcClientError::InvalidConfig] if the endpoint or the TLS settings are
+    /// invalid, and [NodeRpcClientError::ConnectionError] if the connection could not be
+    /// established.
+    async fn connect(&mut self, timeout: Duration) -> Result<RpcApi, NodeRpcClientError> {
+        if let Some(rpc_api) = &self.rpc_api {
+            return Ok(rpc_api.clone());
+        }
+
+        let mut endpoint = Endpoint::from_shared(self.endpoint.clone())
+            .map_err(|err| {
+                NodeRpcClientError::InvalidConfig(format!(
+                    "invalid endpoint `{}`: {err}",
+                    self.endpoint
+                ))
+            })?
+            .connect_timeout(timeout);
+
+        if self.check_tls_settings(endpoint.uri().scheme_str())? {
+            let tls_config = self.tls_config.clone().unwrap_or_else(ClientTlsConfig::new);
+            endpoint = endpoint.tls_config(tls_config).map_err(|err| {
+                NodeRpcClientError::InvalidConfig(format!("invalid TLS settings: {err}"))
+            })?;
+        }
+
+        let channel = endpoint
+            .connect()
+            .await
+            .map_err(|err| NodeRpcClientError::ConnectionError(err.to_string()))?;
+        let rpc_api = ApiClient::with_interceptor(channel, self.metadata.clone());
+
+        Ok(self.rpc_api.insert(rpc_api).clone())
+    }
+
+    /// Returns whether an endpoint with the `scheme` protocol is connected to over TLS.
+    ///
+    /// tonic only uses TLS for `https` endpoints, so TLS settings and metadata headers are
+    /// rejected for any other protocol rather than silently connecting in plaintext.
+    fn check_tls_settings(&self, scheme: Option<&str>) -> Result<bool, NodeRpcClientError> {
+        if scheme == Some("https") {
+            return Ok(true);
         }
+
+        if self.tls_config.is_some() || !self.metadata.headers.is_empty() {
+            return Err(NodeRpcClientError::InvalidConfig(format!(
+                "TLS settings and metadata headers require an `https` endpoint, got `{}`",
+                self.endpoint
+            )));
+        }
+
+        Ok(false)
     }
 }
 
@@ -69,13 +254,11 @@ impl NodeRpcClient for TonicRpcClient {
         let request = SubmitProvenTransactionRequest {
             transaction: proven_transaction.to_bytes(),
         };
-        let rpc_api = self.rpc_api().await?;
-        rpc_api.submit_proven_transaction(request).await.map_err(|err| {
-            NodeRpcClientError::RequestError(
-                NodeRpcClientEndpoint::SubmitProvenTx.to_string(),
-                err.to_string(),
-            )
-        })?;
+        self.send_request(NodeRpcClientEndpoint::SubmitProvenTx, |mut rpc_api| {
+            let request = request.clone();
+            async move { rpc_api.submit_proven_transaction(request).await }
+        })
+        .await?;
 
         Ok(())
     }
@@ -85,22 +268,98 @@ impl NodeRpcClient for TonicRpcClient {
         block_num: Option<u32>,
     ) -> Result<BlockHeader, NodeRpcClientError> {
         let request = GetBlockHeaderByNumberRequest { block_num };
-        let rpc_api = self.rpc_api().await?;
-        let api_response = rpc_api.get_block_header_by_number(request).await.map_err(|err| {
-            NodeRpcClientError::RequestError(
-                NodeRpcClientEndpoint::GetBlockHeaderByNumber.to_string(),
-                err.to_string(),
-            )
-        })?;
+        let api_response = self
+            .send_request(NodeRpcClientEndpoint::GetBlockHeaderByNumber, |mut rpc_api| {
+                let request = request.clone();
+                async move { rpc_api.get_block_header_by_number(request).await }
+            })
+            .await?;
 
         api_response
-            .into_inner()
             .block_header
             .ok_or(NodeRpcClientError::ExpectedFieldMissing("BlockHeader".into()))?
             .try_into()
             .map_err(|err: ConversionError| NodeRpcClientError::ConversionFailure(err.to_string()))
     }
 
+    /// The `/GetBlockHeaderByNumber` endpoint doesn't return MMR proofs, so the proof is built
+    /// from the headers of all the blocks in the chain MMR tree that contains the block. Up to
+    /// [MMR_PROOF_CONCURRENT_REQUESTS] headers are requested at a time, and blocks in trees deeper
+    /// than [MAX_MMR_PROOF_TREE_DEPTH] are rejected rather than downloading the whole tree.
+    async fn get_block_header_with_proof(
+        &mut self,
+        block_num: u32,
+        forest: u32,
+    ) -> Result<(BlockHeader, MmrProof), NodeRpcClientError> {
+        // Find the tree of the MMR that contains the block. Trees are ordered from largest to
+        // smallest, following the bits set in `forest`
+        let mut tree_start = 0;
+        let mut tree_depth = None;
+        for depth in (0..u32::BITS).rev() {
+            let tree_size = 1 << depth;
+            if forest & tree_size == 0 {
+                continue;
+            }
+            if block_num < tree_start + tree_size {
+                tree_depth = Some(depth);
+                break;
+            }
+            tree_start += tree_size;
+        }
+        let tree_depth = tree_depth.ok_or_else(|| {
+            NodeRpcClientError::RequestError(
+                NodeRpcClientEndpoint::GetBlockHeaderByNumber.to_string(),
+                format!("block {block_num} is not part of a chain MMR with {forest} leaves"),
+            )
+        })?;
+
+        if tree_depth > MAX_MMR_PROOF_TREE_DEPTH {
+            return Err(NodeRpcClientError::RequestError(
+                NodeRpcClientEndpoint::GetBlockHeaderByNumber.to_string(),
+                format!(
+                    "proving block {block_num} requires the headers of the {} blocks in its chain \
+                    MMR tree, more than the maximum of {}",
+                    1u64 << tree_depth,
+                    1u64 << MAX_MMR_PROOF_TREE_DEPTH
+                ),
+            ));
+        }
+
+        let timeout = self.request_policy.timeout(NodeRpcClientEndpoint::GetBlockHeaderByNumber);
+        self.connect(timeout).await?;
+        let rpc_client = self.clone();
+
+        let tree_headers: Vec<BlockHeader> =
+            stream::iter((tree_start..tree_start + (1 << tree_depth)).map(|tree_block_num| {
+                let mut rpc_client = rpc_client.clone();
+                async move { rpc_client.get_block_header_by_number(Some(tree_block_num)).await }
+            }))
+            .buffered(MMR_PROOF_CONCURRENT_REQUESTS)
+            .try_collect()
+            .await?;
+        let block_header = tree_headers[(block_num - tree_start) as usize];
+
+        let merkle_path = if tree_depth == 0 {
+            MerklePath::new(vec![])
+        } else {
+            let leaves: Vec<Word> =
+                tree_headers.iter().map(|header| header.hash().into()).collect();
+            let leaf_index = NodeIndex::new(tree_depth as u8, (block_num - tree_start) as u64)
+                .map_err(|err| NodeRpcClientError::ConversionFailure(err.to_string()))?;
+            MerkleTree::new(leaves)
+                .and_then(|tree| tree.get_path(leaf_index))
+                .map_err(|err| NodeRpcClientError::ConversionFailure(err.to_string()))?
+        };
+
+        let proof = MmrProof {
+            forest: forest as usize,
+            position: block_num as usize,
+            merkle_path,
+        };
+
+        Ok((block_header, proof))
+    }
+
     async fn get_notes_by_id(
         &mut self,
         note_ids: &[NoteId],
@@ -108,15 +367,14 @@ impl NodeRpcClient for TonicRpcClient {
         let request = GetNotesByIdRequest {
             note_ids: note_ids.iter().map(|id| id.inner().into()).collect(),
         };
-        let rpc_api = self.rpc_api().await?;
-        let api_response = rpc_api.get_notes_by_id(request).await.map_err(|err| {
-            NodeRpcClientError::RequestError(
-                NodeRpcClientEndpoint::GetBlockHeaderByNumber.to_string(),
-                err.to_string(),
-            )
-        })?;
+        let api_response = self
+            .send_request(NodeRpcClientEndpoint::GetNotesById, |mut rpc_api| {
+                let request = request.clone();
+                async move { rpc_api.get_notes_by_id(request).await }
+            })
+            .await?;
 
-        let rpc_notes = api_response.into_inner().notes;
+        let rpc_notes = api_response.notes;
         let mut response_notes = Vec::with_capacity(rpc_notes.len());
         for note in rpc_notes {
             let sender_id =
@@ -160,6 +418,28 @@ impl NodeRpcClient for TonicRpcClient {
         Ok(response_notes)
     }
 
+    async fn check_nullifiers(
+        &mut self,
+        nullifiers: &[Digest],
+    ) -> Result<Vec<SmtProof>, NodeRpcClientError> {
+        let request = CheckNullifiersRequest {
+            nullifiers: nullifiers.iter().map(|nullifier| (*nullifier).into()).collect(),
+        };
+
+        let api_response = self
+            .send_request(NodeRpcClientEndpoint::CheckNullifiers, |mut rpc_api| {
+                let request = request.clone();
+                async move { rpc_api.check_nullifiers(request).await }
+            })
+            .await?;
+
+        api_response
+            .proofs
+            .into_iter()
+            .map(|proof| proof.try_into().map_err(NodeRpcClientError::from))
+            .collect()
+    }
+
     /// Sends a sync state request to the Miden node, validates and converts the response
     /// into a [StateSyncInfo] struct.
     async fn sync_state(
@@ -182,14 +462,13 @@ impl NodeRpcClient for TonicRpcClient {
             nullifiers,
         };
 
-        let rpc_api = self.rpc_api().await?;
-        let response = rpc_api.sync_state(request).await.map_err(|err| {
-            NodeRpcClientError::RequestError(
-                NodeRpcClientEndpoint::SyncState.to_string(),
-                err.to_string(),
-            )
-        })?;
-        response.into_inner().try_into()
+        let response = self
+            .send_request(NodeRpcClientEndpoint::SyncState, |mut rpc_api| {
+                let request = request.clone();
+                async move { rpc_api.sync_state(request).await }
+            })
+            .await?;
+        response.try_into()
     }
 
     /// Sends a [GetAccountDetailsRequest] to the Miden node, and extracts an [Account] from the
@@ -217,15 +496,12 @@ impl NodeRpcClient for TonicRpcClient {
         let account_id = account_id.into();
         let request = GetAccountDetailsRequest { account_id: Some(account_id) };
 
-        let rpc_api = self.rpc_api().await?;
-
-        let response = rpc_api.get_account_details(request).await.map_err(|err| {
-            NodeRpcClientError::RequestError(
-                NodeRpcClientEndpoint::GetAccountDetails.to_string(),
-                err.to_string(),
-            )
-        })?;
-        let response = response.into_inner();
+        let response = self
+            .send_request(NodeRpcClientEndpoint::GetAccountDetails, |mut rpc_api| {
+                let request = request.clone();
+                async move { rpc_api.get_account_details(request).await }
+            })
+            .await?;
         let account_info = response.account.ok_or(NodeRpcClientError::ExpectedFieldMissing(
             "GetAccountDetails response should have an `account`".to_string(),
         ))?;
@@ -239,6 +515,145 @@ impl NodeRpcClient for TonicRpcClient {
 
         Ok(details)
     }
+
+    /// Requests the accounts concurrently over clones of the client, which share the same
+    /// connection.
+    async fn get_account_updates(
+        &mut self,
+        account_ids: &[AccountId],
+        max_concurrent_requests: usize,
+    ) -> Result<Vec<Account>, NodeRpcClientError> {
+        let timeout = self.request_policy.timeout(NodeRpcClientEndpoint::GetAccountDetails);
+        self.connect(timeout).await?;
+        let rpc_client = self.clone();
+
+        stream::iter(account_ids.iter().map(|account_id| {
+            let mut rpc_client = rpc_client.clone();
+            async move { rpc_client.get_account_update(*account_id).await }
+        }))
+        .buffered(max_concurrent_requests.max(1))
+        .try_collect()
+        .await
+    }
+}
+
+#[cfg(feature = "config")]
+impl TryFrom<&RpcConfig> for TonicRpcClient {
+    type Error = NodeRpcClientError;
+
+    /// Builds a client for the configured endpoint, reading the TLS certificates and keys from
+    /// the files specified in `config`.
+    fn try_from(config: &RpcConfig) -> Result<Self, Self::Error> {
+        configured_client(&config.endpoint, config)
+    }
+}
+
+#[cfg(feature = "config")]
+impl TryFrom<&RpcConfig> for super::FailoverRpcClient<TonicRpcClient> {
+    type Error = NodeRpcClientError;
+
+    /// Builds a client for each of the configured endpoints, in order, sharing the rest of the
+    /// settings in `config`.
+    fn try_from(config: &RpcConfig) -> Result<Self, Self::Error> {
+        let rpc_clients = config
+            .endpoints()
+            .map(|endpoint| configured_client(endpoint, config))
+            .collect::<Result<_, _>>()?;
+
+        Ok(super::FailoverRpcClient::new(rpc_clients))
+    }
+}
+
+/// Maximum depth of the chain MMR tree whose headers are downloaded to build an MMR proof, which
+/// bounds the number of headers requested for a single proof to 1024.
+const MAX_MMR_PROOF_TREE_DEPTH: u32 = 10;
+
+/// Number of block headers requested at a time when building an MMR proof.
+const MMR_PROOF_CONCURRENT_REQUESTS: usize = 16;
+
+// METADATA INTERCEPTOR
+// ================================================================================================
+
+/// Adds a fixed set of metadata headers to every request.
+#[derive(Clone, Default)]
+struct MetadataInterceptor {
+    headers: Vec<(MetadataKey<Ascii>, MetadataValue<Ascii>)>,
+}
+
+impl Interceptor for MetadataInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        for (key, value) in &self.headers {
+            request.metadata_mut().insert(key.clone(), value.clone());
+        }
+        Ok(request)
+    }
+}
+
+// HELPERS
+// ================================================================================================
+
+/// Builds a client for `endpoint` with the request, TLS and metadata settings in `config`.
+#[cfg(feature = "config")]
+fn configured_client(
+    endpoint: &EndpointConfig,
+    config: &RpcConfig,
+) -> Result<TonicRpcClient, NodeRpcClientError> {
+    let mut rpc_client =
+        TonicRpcClient::new(&endpoint.to_string()).with_request_policy(config.into());
+
+    if let Some(tls) = &config.tls {
+        rpc_client = rpc_client.with_tls_config(client_tls_config(tls)?);
+    }
+
+    for (key, value) in &config.metadata {
+        rpc_client = rpc_client.with_metadata(key, value)?;
+    }
+    rpc_client.check_tls_settings(Some(endpoint.protocol()))?;
+
+    Ok(rpc_client)
+}
+
+/// Builds the tonic TLS settings from `config`, reading the PEM files it points to.
+#[cfg(feature = "config")]
+fn client_tls_config(config: &TlsConfig) -> Result<ClientTlsConfig, NodeRpcClientError> {
+    let read_pem = |path: &std::path::Path| {
+        std::fs::read(path).map_err(|err| {
+            NodeRpcClientError::InvalidConfig(format!("failed to read {}: {err}", path.display()))
+        })
+    };
+
+    let mut tls_config = ClientTlsConfig::new();
+
+    if let Some(domain_name) = &config.domain_name {
+        tls_config = tls_config.domain_name(domain_name);
+    }
+
+    if let Some(ca_certificate) = &config.ca_certificate {
+        tls_config = tls_config.ca_certificate(Certificate::from_pem(read_pem(ca_certificate)?));
+    }
+
+    match (&config.client_certificate, &config.client_key) {
+        (Some(certificate), Some(key)) => {
+            tls_config =
+                tls_config.identity(Identity::from_pem(read_pem(certificate)?, read_pem(key)?));
+        },
+        (None, None) => {},
+        _ => {
+            return Err(NodeRpcClientError::InvalidConfig(
+                "`client_certificate` and `client_key` must be set together".to_string(),
+            ))
+        },
+    }
+
+    Ok(tls_config)
+}
+
+/// Returns whether the request failed because of the connection to the node rather than being
+/// rejected by it.
+fn is_transport_error(status: &Status) -> bool {
+    status.code() == Code::Unavailable
+        || std::error::Error::source(status)
+            .is_some_and(|source| source.is::<tonic::transport::Error>())
 }
 
 // STATE SYNC INFO CONVERSION
@@ -301,15 +716,15 @@ impl TryFrom<SyncStateResponse> for StateSyncInfo {
                 .try_into()?;
 
             let note_type = NoteType::try_from(Felt::new(note.note_type.into()))?;
-            let metadata = NoteMetadata::new(
+
+            let committed_note = CommittedNote::new(
+                note_id,
+                note.note_index,
+                merkle_path,
                 sender_account_id,
                 note_type,
                 note.tag.into(),
-                Default::default(),
-            )?;
-
-            let committed_note =
-                CommittedNote::new(note_id, note.note_index, merkle_path, metadata);
+            );
 
             note_inclusions.push(committed_note);
         }
@@ -326,8 +741,12 @@ impl TryFrom<SyncStateResponse> for StateSyncInfo {
                         Digest::try_from(n)
                             .map_err(|err| NodeRpcClientError::ConversionFailure(err.to_string()))
                     })
+                    .map(|nullifier| NullifierUpdate {
+                        nullifier,
+                        block_num: nul_update.block_num,
+                    })
             })
-            .collect::<Result<Vec<Digest>, NodeRpcClientError>>()?;
+            .collect::<Result<Vec<NullifierUpdate>, NodeRpcClientError>>()?;
 
         Ok(Self {
             chain_tip,
@@ -1,30 +1,94 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 
 use crypto::merkle::{InOrderIndex, MmrDelta, MmrPeaks, PartialMmr};
+use futures::join;
 use miden_objects::{
     accounts::{Account, AccountId, AccountStub},
-    crypto::{self, rand::FeltRng},
-    notes::{NoteExecutionMode, NoteId, NoteInclusionProof, NoteTag},
+    crypto::{self, hash::rpo::Rpo256, rand::FeltRng},
+    notes::{NoteExecutionMode, NoteId, NoteInclusionProof, NoteMetadata, NoteTag},
     transaction::{InputNote, TransactionId},
-    BlockHeader, Digest,
+    BlockHeader, Digest, Word,
 };
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 use tracing::{info, warn};
 
 use super::{
-    rpc::{CommittedNote, NodeRpcClient, NoteDetails},
+    events::ClientEvent,
+    rpc::{CommittedNote, NodeRpcClient, NoteDetails, NullifierUpdate, StateSyncInfo},
     transactions::TransactionRecord,
     Client,
 };
 use crate::{
-    errors::{ClientError, StoreError},
-    store::{ChainMmrNodeFilter, NoteFilter, Store, TransactionFilter},
+    errors::{ClientError, StoreError, VerificationError},
+    store::{
+        ChainMmrNodeFilter, ExpectedNoteRecord, NoteFilter, NoteStatus, Store, TransactionFilter,
+    },
 };
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SyncStatus {
     SyncedToLastBlock(u32),
     SyncedToBlock(u32),
 }
 
+/// Contains the changes applied to the client's state by a sync, aggregated over all the requests
+/// made to the node.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncSummary {
+    /// Block number up to which the client has been synced.
+    pub block_num: u32,
+    /// Numbers of the blocks whose headers were received and applied during the sync.
+    pub blocks_processed: Vec<u32>,
+    /// IDs of the public notes that the client was not tracking and were received.
+    pub new_public_notes: Vec<NoteId>,
+    /// IDs of the private notes that were committed for the client's tags, but whose details
+    /// are unknown.
+    pub new_expected_notes: Vec<NoteId>,
+    /// IDs of the tracked notes that were committed.
+    pub committed_notes: Vec<NoteId>,
+    /// Nullifiers of the tracked input and output notes that were consumed.
+    pub consumed_nullifiers: Vec<Digest>,
+    /// IDs of the transactions that were committed.
+    pub committed_transactions: Vec<TransactionId>,
+    /// IDs of the pending transactions that were discarded.
+    pub discarded_transactions: Vec<TransactionId>,
+    /// IDs of the on-chain accounts whose state was updated from the node.
+    pub updated_onchain_accounts: Vec<AccountId>,
+}
+
+impl SyncSummary {
+    /// Returns an empty [SyncSummary] for a sync that reached block `block_num`.
+    pub fn new_empty(block_num: u32) -> Self {
+        Self { block_num, ..Default::default() }
+    }
+
+    /// Returns whether the sync didn't change the client's state, other than advancing its
+    /// block number.
+    pub fn is_empty(&self) -> bool {
+        self.new_public_notes.is_empty()
+            && self.new_expected_notes.is_empty()
+            && self.committed_notes.is_empty()
+            && self.consumed_nullifiers.is_empty()
+            && self.committed_transactions.is_empty()
+            && self.discarded_transactions.is_empty()
+            && self.updated_onchain_accounts.is_empty()
+    }
+
+    /// Appends the changes of a later sync step to this summary.
+    pub fn combine_with(&mut self, other: SyncSummary) {
+        self.block_num = other.block_num;
+        self.blocks_processed.extend(other.blocks_processed);
+        self.new_public_notes.extend(other.new_public_notes);
+        self.new_expected_notes.extend(other.new_expected_notes);
+        self.committed_notes.extend(other.committed_notes);
+        self.consumed_nullifiers.extend(other.consumed_nullifiers);
+        self.committed_transactions.extend(other.committed_transactions);
+        self.discarded_transactions.extend(other.discarded_transactions);
+        self.updated_onchain_accounts.extend(other.updated_onchain_accounts);
+    }
+}
+
 /// Contains information about new notes as consequence of a sync
 pub struct SyncedNewNotes {
     /// A list of public notes that have been received on sync
@@ -32,14 +96,21 @@ pub struct SyncedNewNotes {
     /// A list of note IDs alongside their inclusion proofs for locally-tracked
     /// notes
     new_inclusion_proofs: Vec<(NoteId, NoteInclusionProof)>,
+    /// A list of private notes that have been received on sync, but whose details are unknown
+    new_expected_notes: Vec<ExpectedNoteRecord>,
 }
 
 impl SyncedNewNotes {
     pub fn new(
         new_public_notes: Vec<InputNote>,
         new_inclusion_proofs: Vec<(NoteId, NoteInclusionProof)>,
+        new_expected_notes: Vec<ExpectedNoteRecord>,
     ) -> Self {
-        Self { new_public_notes, new_inclusion_proofs }
+        Self {
+            new_public_notes,
+            new_inclusion_proofs,
+            new_expected_notes,
+        }
     }
 
     pub fn new_public_notes(&self) -> &[InputNote] {
@@ -50,9 +121,133 @@ impl SyncedNewNotes {
         &self.new_inclusion_proofs
     }
 
+    pub fn new_expected_notes(&self) -> &[ExpectedNoteRecord] {
+        &self.new_expected_notes
+    }
+
     /// Returns whether no new note-related information has been retrieved
     pub fn is_empty(&self) -> bool {
-        self.new_inclusion_proofs.is_empty() && self.new_public_notes.is_empty()
+        self.new_inclusion_proofs.is_empty()
+            && self.new_public_notes.is_empty()
+            && self.new_expected_notes.is_empty()
+    }
+}
+
+// SYNC CONFIG
+// ================================================================================================
+
+/// Controls how much the sync requests sent to the node reveal about the notes and accounts
+/// tracked by the client.
+///
+/// The node only learns prefixes of the tracked nullifiers and tags, but sending the same exact
+/// prefixes on every request lets it link them over time. Reducing the prefix widths makes the
+/// client request every value that shares the shortened prefix, and decoys add random values that
+/// don't belong to the client. The extra results returned by the node are filtered out locally.
+///
+/// Shorter prefixes multiply the size of the requests: each nullifier prefix expands to
+/// `2^(16 - nullifier_prefix_bits)` values and each account tag to
+/// `2^(14 - account_tag_prefix_bits)` tags. To keep requests bounded, the widths can't go below
+/// 8 and 6 bits respectively, and at most 256 decoys of each kind can be added.
+///
+/// It also bounds how many on-chain account states are requested from the node at a time.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct SyncConfig {
+    /// Number of bits of each nullifier's 16-bit prefix that identify it in sync requests. Must
+    /// be between 8 and 16.
+    pub nullifier_prefix_bits: u8,
+    /// Number of bits of the 14-bit account ID prefix encoded in the tags derived from the
+    /// client's accounts that identify them in sync requests. Must be between 6 and 14.
+    pub account_tag_prefix_bits: u8,
+    /// Number of random nullifier prefixes sent along with the real ones. Must be at most 256.
+    pub decoy_nullifier_prefixes: usize,
+    /// Number of random account tags sent along with the real ones. Must be at most 256.
+    pub decoy_note_tags: usize,
+    /// Maximum number of updated on-chain accounts requested from the node concurrently.
+    pub max_concurrent_account_requests: usize,
+}
+
+impl SyncConfig {
+    /// Checks that the prefix widths are within the range supported by the node, and that the
+    /// widened prefixes and decoys keep sync requests bounded.
+    pub fn validate(&self) -> Result<(), String> {
+        if !(MIN_NULLIFIER_PREFIX_BITS..=NULLIFIER_PREFIX_BITS)
+            .contains(&self.nullifier_prefix_bits)
+        {
+            return Err(format!(
+                "nullifier prefix width must be between {MIN_NULLIFIER_PREFIX_BITS} and \
+                {NULLIFIER_PREFIX_BITS} bits, got {}",
+                self.nullifier_prefix_bits
+            ));
+        }
+
+        if !(MIN_ACCOUNT_TAG_PREFIX_BITS..=ACCOUNT_TAG_PREFIX_BITS)
+            .contains(&self.account_tag_prefix_bits)
+        {
+            return Err(format!(
+                "account tag prefix width must be between {MIN_ACCOUNT_TAG_PREFIX_BITS} and \
+                {ACCOUNT_TAG_PREFIX_BITS} bits, got {}",
+                self.account_tag_prefix_bits
+            ));
+        }
+
+        if self.decoy_nullifier_prefixes > MAX_SYNC_DECOYS {
+            return Err(format!(
+                "number of decoy nullifier prefixes must be at most {MAX_SYNC_DECOYS}, got {}",
+                self.decoy_nullifier_prefixes
+            ));
+        }
+
+        if self.decoy_note_tags > MAX_SYNC_DECOYS {
+            return Err(format!(
+                "number of decoy note tags must be at most {MAX_SYNC_DECOYS}, got {}",
+                self.decoy_note_tags
+            ));
+        }
+
+        if self.max_concurrent_account_requests == 0 {
+            return Err("maximum number of concurrent account requests must be at least 1".into());
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for SyncConfig {
+    fn default() -> Self {
+        Self {
+            nullifier_prefix_bits: NULLIFIER_PREFIX_BITS,
+            account_tag_prefix_bits: ACCOUNT_TAG_PREFIX_BITS,
+            decoy_nullifier_prefixes: 0,
+            decoy_note_tags: 0,
+            max_concurrent_account_requests: DEFAULT_MAX_CONCURRENT_ACCOUNT_REQUESTS,
+        }
+    }
+}
+
+/// Random nullifier prefixes and tags sent in every sync request along with the real ones.
+///
+/// Decoys are drawn once per [Client] so that they can't be told apart from the real values by
+/// comparing consecutive requests.
+#[derive(Debug, Default)]
+pub(crate) struct SyncDecoys {
+    nullifier_prefixes: Vec<u16>,
+    note_tags: Vec<NoteTag>,
+}
+
+impl SyncDecoys {
+    /// Draws the number of decoys specified in `config`.
+    pub(crate) fn random(config: &SyncConfig) -> Self {
+        let mut rng = rand::thread_rng();
+
+        let nullifier_prefixes =
+            (0..config.decoy_nullifier_prefixes).map(|_| rng.gen::<u16>()).collect();
+        // Decoy tags look like tags derived from accounts
+        let note_tags = (0..config.decoy_note_tags)
+            .map(|_| NoteTag::from(LOCAL_ACCOUNT_TAG_PREFIX | (rng.gen::<u32>() & 0x3fff_0000)))
+            .collect();
+
+        Self { nullifier_prefixes, note_tags }
     }
 }
 
@@ -62,6 +257,27 @@ impl SyncedNewNotes {
 /// The number of bits to shift identifiers for in use of filters.
 pub const FILTER_ID_SHIFT: u8 = 48;
 
+/// Width of the nullifier prefixes the node filters nullifiers by.
+const NULLIFIER_PREFIX_BITS: u8 = 16;
+
+/// Width of the account ID prefix encoded in tags derived from accounts.
+const ACCOUNT_TAG_PREFIX_BITS: u8 = 14;
+
+/// Minimum nullifier prefix width, which widens each nullifier prefix to at most 256 values.
+const MIN_NULLIFIER_PREFIX_BITS: u8 = 8;
+
+/// Minimum account tag prefix width, which widens each account tag to at most 256 tags.
+const MIN_ACCOUNT_TAG_PREFIX_BITS: u8 = 6;
+
+/// Maximum number of decoy nullifier prefixes and decoy note tags added to sync requests.
+const MAX_SYNC_DECOYS: usize = 256;
+
+/// The high bits of tags derived from accounts for local execution.
+const LOCAL_ACCOUNT_TAG_PREFIX: u32 = 0b11 << 30;
+
+/// Default number of updated on-chain accounts requested from the node concurrently.
+const DEFAULT_MAX_CONCURRENT_ACCOUNT_REQUESTS: usize = 8;
+
 impl<N: NodeRpcClient, R: FeltRng, S: Store> Client<N, R, S> {
     // SYNC STATE
     // --------------------------------------------------------------------------------------------
@@ -72,16 +288,33 @@ impl<N: NodeRpcClient, R: FeltRng, S: Store> Client<N, R, S> {
     }
 
     /// Returns the list of note tags tracked by the client.
-    pub fn get_note_tags(&self) -> Result<Vec<u64>, ClientError> {
+    ///
+    /// These are the tags added through [Client::add_note_tag]. The tags derived from the
+    /// client's accounts are always tracked and not included in this list.
+    pub fn get_note_tags(&self) -> Result<Vec<NoteTag>, ClientError> {
         self.store.get_note_tags().map_err(|err| err.into())
     }
 
     /// Adds a note tag for the client to track.
-    pub fn add_note_tag(&mut self, tag: u64) -> Result<(), ClientError> {
+    pub fn add_note_tag(&mut self, tag: NoteTag) -> Result<(), ClientError> {
         match self.store.add_note_tag(tag).map_err(|err| err.into()) {
             Ok(true) => Ok(()),
             Ok(false) => {
-                warn!("Tag {} is already being tracked", tag);
+                warn!("Tag {} is already being tracked", u32::from(tag));
+                Ok(())
+            },
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Removes a note tag for the client to track.
+    ///
+    /// Notes that were already received for the tag are kept.
+    pub fn remove_note_tag(&mut self, tag: NoteTag) -> Result<(), ClientError> {
+        match self.store.remove_note_tag(tag).map_err(|err| err.into()) {
+            Ok(true) => Ok(()),
+            Ok(false) => {
+                warn!("Tag {} wasn't being tracked", u32::from(tag));
                 Ok(())
             },
             Err(err) => Err(err),
@@ -91,15 +324,217 @@ impl<N: NodeRpcClient, R: FeltRng, S: Store> Client<N, R, S> {
     /// Syncs the client's state with the current state of the Miden network.
     /// Before doing so, it ensures the genesis block exists in the local store.
     ///
-    /// Returns the block number the client has been synced to.
-    pub async fn sync_state(&mut self) -> Result<u32, ClientError> {
+    /// Returns a [SyncSummary] with the block number the client has been synced to and the
+    /// changes applied to its state along the way.
+    pub async fn sync_state(&mut self) -> Result<SyncSummary, ClientError> {
+        self.sync_state_to(u32::MAX).await
+    }
+
+    /// Syncs the client's state with the Miden network until the sync height reaches
+    /// `block_num` or the chain tip, whichever comes first.
+    ///
+    /// The node decides how many blocks each sync request covers, so the client may end up
+    /// synced past `block_num`.
+    ///
+    /// The request for the next chunk of blocks is sent while the previous one is being applied to
+    /// the store. Chunks are still applied in order, each of them atomically.
+    pub async fn sync_state_to(&mut self, block_num: u32) -> Result<SyncSummary, ClientError> {
         self.ensure_genesis_in_place().await?;
-        loop {
-            let response = self.sync_state_once().await?;
-            if let SyncStatus::SyncedToLastBlock(v) = response {
-                return Ok(v);
+        let mut total_summary = SyncSummary::new_empty(self.store.get_sync_height()?);
+        let mut prefetched_response = None;
+        while total_summary.block_num < block_num {
+            let (status, summary, next_response) =
+                self.sync_state_once(prefetched_response, block_num).await?;
+            total_summary.combine_with(summary);
+            prefetched_response = next_response;
+
+            if let SyncStatus::SyncedToLastBlock(_) = status {
+                break;
             }
         }
+
+        Ok(total_summary)
+    }
+
+    /// Rescans the blocks after `block_num`, up to the current sync height, for notes matching
+    /// `tags` and for the nullifiers of the tracked input notes. The sync height is not changed.
+    ///
+    /// This allows finding notes that were committed or consumed in blocks the client already
+    /// synced past, for example after importing a pending note or adding a tag. The inclusion
+    /// proofs and nullifiers found are merged into the store, and the received block headers are
+    /// verified against the chain MMR as in [Client::sync_state].
+    ///
+    /// Rescanning requires the chain MMR peaks at the starting block, so it starts from
+    /// `block_num` if its header is stored, or from the genesis block otherwise. Blocks after the
+    /// sync height are left to the next sync.
+    pub async fn rescan_from(
+        &mut self,
+        block_num: u32,
+        tags: &[NoteTag],
+    ) -> Result<SyncSummary, ClientError> {
+        self.ensure_genesis_in_place().await?;
+        let sync_height = self.store.get_sync_height()?;
+        let mut summary = SyncSummary::new_empty(sync_height);
+        if block_num >= sync_height {
+            return Ok(summary);
+        }
+
+        let (mut current_block, _) = match self.store.get_block_header_by_num(block_num) {
+            Ok(block) => block,
+            Err(StoreError::BlockHeaderNotFound(_)) => self.store.get_block_header_by_num(0)?,
+            Err(err) => return Err(err.into()),
+        };
+        let mut current_block_has_notes = false;
+        let mut partial_mmr = PartialMmr::from_peaks(
+            self.store.get_chain_mmr_peaks_by_block_num(current_block.block_num())?,
+        );
+
+        let input_notes = self.store.get_input_notes(NoteFilter::All)?;
+        let output_notes = self.store.get_output_notes(NoteFilter::All)?;
+
+        // Notes that may have been consumed in the rescanned blocks, by nullifier
+        let mut unspent_notes: BTreeMap<Digest, NoteId> = BTreeMap::new();
+        for note in input_notes.iter() {
+            if note.status() != NoteStatus::Consumed {
+                unspent_notes.insert(Digest::try_from(note.nullifier())?, note.id());
+            }
+        }
+        let mut unspent_output_nullifiers: BTreeSet<Digest> = BTreeSet::new();
+        for note in output_notes.iter().filter(|note| note.status() != NoteStatus::Consumed) {
+            if let Some(details) = note.details() {
+                unspent_output_nullifiers.insert(Digest::try_from(details.nullifier())?);
+            }
+        }
+        let nullifier_prefixes: Vec<u16> = unspent_notes
+            .keys()
+            .chain(unspent_output_nullifiers.iter())
+            .map(|nullifier| (nullifier[3].as_int() >> FILTER_ID_SHIFT) as u16)
+            .flat_map(|prefix| {
+                widen_nullifier_prefix(prefix, self.sync_config.nullifier_prefix_bits)
+            })
+            .chain(self.sync_decoys.nullifier_prefixes.iter().copied())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+
+        // Notes that already have an inclusion proof don't need to be processed again
+        let known_notes: BTreeSet<NoteId> = input_notes
+            .iter()
+            .filter(|note| note.status() != NoteStatus::Pending)
+            .map(|note| note.id())
+            .chain(
+                output_notes
+                    .iter()
+                    .filter(|note| note.status() != NoteStatus::Pending)
+                    .map(|note| note.id()),
+            )
+            .collect();
+
+        let mut tracked_block_headers = vec![];
+        let mut new_authentication_nodes = vec![];
+        let mut nullifiers = vec![];
+        let mut new_note_details = SyncedNewNotes::new(vec![], vec![], vec![]);
+        let mut events = vec![];
+
+        while current_block.block_num() < sync_height {
+            let response = self
+                .rpc_api
+                .sync_state(current_block.block_num(), &[], tags, &nullifier_prefixes)
+                .await?;
+            let block_header = response.block_header;
+            let block_num = block_header.block_num();
+            if block_num == current_block.block_num() {
+                break;
+            }
+
+            let mut step_authentication_nodes =
+                partial_mmr.add(current_block.hash(), current_block_has_notes);
+            step_authentication_nodes
+                .extend(partial_mmr.apply(response.mmr_delta).map_err(StoreError::MmrError)?);
+            verify_chain_root(&partial_mmr.peaks(), &block_header)?;
+            new_authentication_nodes.extend(step_authentication_nodes);
+
+            // The notes of a block past the sync height are left to the next sync, while its MMR
+            // delta still provides the authentication nodes for the rescanned blocks
+            let past_sync_height = block_num > sync_height;
+            if !past_sync_height {
+                let committed_notes = response
+                    .note_inclusions
+                    .into_iter()
+                    .filter(|note| !known_notes.contains(note.note_id()))
+                    .collect();
+                let step_note_details =
+                    self.get_note_details(committed_notes, &block_header, &BTreeSet::new()).await?;
+
+                current_block_has_notes = !step_note_details.is_empty();
+                if current_block_has_notes {
+                    tracked_block_headers.push((block_header, partial_mmr.peaks()));
+                    summary.blocks_processed.push(block_num);
+                }
+
+                for note in step_note_details.new_public_notes.iter() {
+                    events.push(ClientEvent::NoteReceived { note_id: note.id() });
+                }
+                for (note_id, _) in step_note_details.new_inclusion_proofs.iter() {
+                    events.push(ClientEvent::NoteCommitted { note_id: *note_id, block_num });
+                }
+                new_note_details.new_public_notes.extend(step_note_details.new_public_notes);
+                new_note_details
+                    .new_inclusion_proofs
+                    .extend(step_note_details.new_inclusion_proofs);
+                new_note_details.new_expected_notes.extend(step_note_details.new_expected_notes);
+            }
+
+            // Nullifiers of the rescanned blocks are kept even if the response goes past the sync
+            // height, since the next sync only returns the ones of later blocks
+            for nullifier_update in response.nullifiers {
+                if nullifier_update.block_num > sync_height {
+                    continue;
+                }
+                let input_note = unspent_notes.remove(&nullifier_update.nullifier);
+                let output_note = unspent_output_nullifiers.remove(&nullifier_update.nullifier);
+                if let Some(note_id) = input_note {
+                    events.push(ClientEvent::NoteConsumed {
+                        note_id,
+                        block_num: nullifier_update.block_num,
+                    });
+                }
+                if input_note.is_some() || output_note {
+                    nullifiers.push(nullifier_update);
+                }
+            }
+
+            if past_sync_height {
+                break;
+            }
+            current_block = block_header;
+        }
+
+        // Nodes past the sync height are added by the next sync
+        new_authentication_nodes.retain(|(index, _)| u64::from(*index) < 2 * sync_height as u64);
+
+        summary.new_public_notes =
+            new_note_details.new_public_notes.iter().map(|note| note.id()).collect();
+        summary.new_expected_notes =
+            new_note_details.new_expected_notes.iter().map(|note| note.id()).collect();
+        summary.committed_notes = new_note_details
+            .new_inclusion_proofs
+            .iter()
+            .map(|(note_id, _)| *note_id)
+            .collect();
+        summary.consumed_nullifiers =
+            nullifiers.iter().map(|nullifier_update| nullifier_update.nullifier).collect();
+
+        self.store.apply_rescan(
+            &tracked_block_headers,
+            nullifiers,
+            new_note_details,
+            &new_authentication_nodes,
+        )?;
+
+        self.emit_events(&events);
+
+        Ok(summary)
     }
 
     /// Attempts to retrieve the genesis block from the store. If not found,
@@ -127,7 +562,16 @@ impl<N: NodeRpcClient, R: FeltRng, S: Store> Client<N, R, S> {
         Ok(())
     }
 
-    async fn sync_state_once(&mut self) -> Result<SyncStatus, ClientError> {
+    /// Applies the next chunk of blocks to the client's state, taken from `prefetched_response`
+    /// if the request for it was already sent.
+    ///
+    /// Unless the chunk reaches the chain tip or `target_block_num`, the request for the chunk
+    /// that follows is sent while this one is being applied, and its response is returned.
+    async fn sync_state_once(
+        &mut self,
+        prefetched_response: Option<StateSyncInfo>,
+        target_block_num: u32,
+    ) -> Result<(SyncStatus, SyncSummary, Option<StateSyncInfo>), ClientError> {
         let current_block_num = self.store.get_sync_height()?;
 
         let accounts: Vec<AccountStub> = self
@@ -137,35 +581,43 @@ impl<N: NodeRpcClient, R: FeltRng, S: Store> Client<N, R, S> {
             .map(|(acc_stub, _)| acc_stub)
             .collect();
 
-        let note_tags: Vec<NoteTag> = accounts
-            .iter()
-            .map(|acc| NoteTag::from_account_id(acc.id(), NoteExecutionMode::Local))
-            .collect::<Result<Vec<_>, _>>()?;
+        let (note_tags, extra_note_tags) = self.get_sync_note_tags(&accounts)?;
 
         // To receive information about added nullifiers, we reduce them to the higher 16 bits
         // Note that besides filtering by nullifier prefixes, the node also filters by block number
         // (it only returns nullifiers from current_block_num until response.block_header.block_num())
-        let nullifiers_tags: Vec<u16> = self
-            .store
-            .get_unspent_input_note_nullifiers()?
-            .iter()
-            .map(|nullifier| (nullifier.inner()[3].as_int() >> FILTER_ID_SHIFT) as u16)
-            .collect();
+        let nullifiers_tags = self.get_sync_nullifier_prefixes()?;
 
-        // Send request
+        // Send request, unless it was already sent while applying the previous chunk
         let account_ids: Vec<AccountId> = accounts.iter().map(|acc| acc.id()).collect();
-        let response = self
-            .rpc_api
-            .sync_state(current_block_num, &account_ids, &note_tags, &nullifiers_tags)
-            .await?;
+        let response = match prefetched_response {
+            Some(response) => response,
+            None => {
+                self.rpc_api
+                    .sync_state(current_block_num, &account_ids, &note_tags, &nullifiers_tags)
+                    .await?
+            },
+        };
 
         // We don't need to continue if the chain has not advanced
         if response.block_header.block_num() == current_block_num {
-            return Ok(SyncStatus::SyncedToLastBlock(current_block_num));
+            return Ok((
+                SyncStatus::SyncedToLastBlock(current_block_num),
+                SyncSummary::new_empty(current_block_num),
+                None,
+            ));
         }
 
-        let new_note_details =
-            self.get_note_details(response.note_inclusions, &response.block_header).await?;
+        // Senders of the committed notes, used as evidence of the transactions that created them
+        let note_senders: BTreeMap<NoteId, AccountId> = response
+            .note_inclusions
+            .iter()
+            .map(|note| (*note.note_id(), note.sender()))
+            .collect();
+
+        let new_note_details = self
+            .get_note_details(response.note_inclusions, &response.block_header, &extra_note_tags)
+            .await?;
 
         let (onchain_accounts, offchain_accounts): (Vec<_>, Vec<_>) =
             accounts.into_iter().partition(|account_stub| account_stub.id().is_on_chain());
@@ -173,10 +625,21 @@ impl<N: NodeRpcClient, R: FeltRng, S: Store> Client<N, R, S> {
         let updated_onchain_accounts = self
             .get_updated_onchain_accounts(&response.account_hash_updates, &onchain_accounts)
             .await?;
-        self.validate_local_account_hashes(&response.account_hash_updates, &offchain_accounts)?;
+
+        let uncommitted_transactions =
+            self.store.get_transactions(TransactionFilter::Uncomitted)?;
+        self.validate_local_account_hashes(
+            &response.account_hash_updates,
+            &offchain_accounts,
+            &uncommitted_transactions,
+        )?;
 
         // Derive new nullifiers data
         let new_nullifiers = self.get_new_nullifiers(response.nullifiers)?;
+        let consumed_nullifiers: Vec<Digest> = new_nullifiers
+            .iter()
+            .map(|nullifier_update| nullifier_update.nullifier)
+            .collect();
 
         // Build PartialMmr with current data and apply updates
         let (new_peaks, new_authentication_nodes) = {
@@ -193,48 +656,267 @@ impl<N: NodeRpcClient, R: FeltRng, S: Store> Client<N, R, S> {
             )?
         };
 
+        // Make sure the received header belongs to the chain we were tracking before persisting
+        // anything from this step
+        verify_chain_root(&new_peaks, &response.block_header)?;
+
         let note_ids: Vec<NoteId> =
             new_note_details.new_inclusion_proofs.iter().map(|(id, _)| (*id)).collect();
 
-        let uncommitted_transactions =
-            self.store.get_transactions(TransactionFilter::Uncomitted)?;
+        let committed_notes: Vec<(NoteId, AccountId)> = note_ids
+            .iter()
+            .filter_map(|note_id| note_senders.get(note_id).map(|sender| (*note_id, *sender)))
+            .collect();
 
         let transactions_to_commit = get_transactions_to_commit(
             &uncommitted_transactions,
-            &note_ids,
-            &new_nullifiers,
+            &committed_notes,
+            &response.account_hash_updates,
+        );
+
+        let transactions_to_discard = get_transactions_to_discard(
+            &uncommitted_transactions,
+            &transactions_to_commit,
+            &consumed_nullifiers,
             &response.account_hash_updates,
         );
 
+        let summary = SyncSummary {
+            block_num: response.block_header.block_num(),
+            blocks_processed: vec![response.block_header.block_num()],
+            new_public_notes: new_note_details
+                .new_public_notes()
+                .iter()
+                .map(|note| note.id())
+                .collect(),
+            new_expected_notes: new_note_details
+                .new_expected_notes()
+                .iter()
+                .map(|note| note.id())
+                .collect(),
+            committed_notes: note_ids.clone(),
+            consumed_nullifiers: consumed_nullifiers.clone(),
+            committed_transactions: transactions_to_commit.clone(),
+            discarded_transactions: transactions_to_discard
+                .iter()
+                .map(|transaction| transaction.id)
+                .collect(),
+            updated_onchain_accounts: updated_onchain_accounts
+                .iter()
+                .map(|account| account.id())
+                .collect(),
+        };
+
+        let events = {
+            let block_num = response.block_header.block_num();
+            let consumed_note_ids = self.get_consumed_note_ids(&consumed_nullifiers)?;
+
+            let received_notes = new_note_details
+                .new_public_notes()
+                .iter()
+                .map(|note| ClientEvent::NoteReceived { note_id: note.id() });
+            let committed_notes = note_ids
+                .iter()
+                .map(|note_id| ClientEvent::NoteCommitted { note_id: *note_id, block_num });
+            let consumed_notes = consumed_note_ids
+                .into_iter()
+                .map(|note_id| ClientEvent::NoteConsumed { note_id, block_num });
+            let committed_transactions = transactions_to_commit.iter().map(|transaction_id| {
+                ClientEvent::TransactionCommitted {
+                    transaction_id: *transaction_id,
+                    block_num,
+                }
+            });
+            let discarded_transactions = transactions_to_discard.iter().map(|transaction| {
+                ClientEvent::TransactionDiscarded {
+                    transaction_id: transaction.id,
+                    account_id: transaction.account_id,
+                }
+            });
+            let updated_accounts =
+                updated_onchain_accounts
+                    .iter()
+                    .map(|account| ClientEvent::OnchainAccountUpdated {
+                        account_id: account.id(),
+                        account_hash: account.hash(),
+                    });
+
+            received_notes
+                .chain(committed_notes)
+                .chain(consumed_notes)
+                .chain(committed_transactions)
+                .chain(discarded_transactions)
+                .chain(updated_accounts)
+                .collect::<Vec<_>>()
+        };
+
+        let block_num = response.block_header.block_num();
+        let status = if response.chain_tip == block_num {
+            SyncStatus::SyncedToLastBlock(response.chain_tip)
+        } else {
+            SyncStatus::SyncedToBlock(block_num)
+        };
+
+        // The next request doesn't depend on the store being updated: accounts and tags don't
+        // change while syncing, and the nullifiers of the notes committed in this chunk are added
+        // to the current ones
+        let next_nullifier_prefixes = match status {
+            SyncStatus::SyncedToBlock(_) if block_num < target_block_num => {
+                Some(self.get_next_sync_nullifier_prefixes(&nullifiers_tags, &new_note_details)?)
+            },
+            _ => None,
+        };
+
+        let rpc_api = &mut self.rpc_api;
+        let next_response = async move {
+            match next_nullifier_prefixes {
+                Some(nullifier_prefixes) => rpc_api
+                    .sync_state(block_num, &account_ids, &note_tags, &nullifier_prefixes)
+                    .await
+                    .map(Some),
+                None => Ok(None),
+            }
+        };
+
         // Apply received and computed updates to the store
-        self.store
-            .apply_state_sync(
+        let store = &self.store;
+        let discarded_transactions = &summary.discarded_transactions;
+        let applied_state_sync = async move {
+            store.apply_state_sync(
                 response.block_header,
                 new_nullifiers,
                 new_note_details,
                 &transactions_to_commit,
+                discarded_transactions,
                 new_peaks,
                 &new_authentication_nodes,
                 &updated_onchain_accounts,
             )
-            .map_err(ClientError::StoreError)?;
+        };
 
-        if response.chain_tip == response.block_header.block_num() {
-            Ok(SyncStatus::SyncedToLastBlock(response.chain_tip))
-        } else {
-            Ok(SyncStatus::SyncedToBlock(response.block_header.block_num()))
-        }
+        // The request is polled first so that it's sent before the store is updated
+        let (next_response, applied_state_sync) = join!(next_response, applied_state_sync);
+        applied_state_sync.map_err(ClientError::StoreError)?;
+
+        self.emit_events(&events);
+
+        Ok((status, summary, next_response?))
     }
 
     // HELPERS
     // --------------------------------------------------------------------------------------------
 
+    /// Returns the note tags to request notes for: the tags derived from the provided accounts
+    /// along with the tags added by the user, without duplicates.
+    ///
+    /// Account tags are widened to the configured prefix width and decoy tags are added, so the
+    /// tags that don't belong to the client are also returned to filter out the notes received
+    /// for them.
+    fn get_sync_note_tags(
+        &self,
+        accounts: &[AccountStub],
+    ) -> Result<(Vec<NoteTag>, BTreeSet<u32>), ClientError> {
+        let account_tags = accounts
+            .iter()
+            .map(|acc| NoteTag::from_account_id(acc.id(), NoteExecutionMode::Local))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let tracked_tags: BTreeSet<u32> = account_tags
+            .iter()
+            .copied()
+            .chain(self.store.get_note_tags()?)
+            .map(u32::from)
+            .collect();
+
+        let extra_tags: BTreeSet<u32> = account_tags
+            .into_iter()
+            .flat_map(|tag| widen_account_tag(tag, self.sync_config.account_tag_prefix_bits))
+            .chain(self.sync_decoys.note_tags.iter().copied())
+            .map(u32::from)
+            .filter(|tag| !tracked_tags.contains(tag))
+            .collect();
+
+        let note_tags = tracked_tags
+            .iter()
+            .chain(extra_tags.iter())
+            .copied()
+            .map(NoteTag::from)
+            .collect();
+
+        Ok((note_tags, extra_tags))
+    }
+
+    /// Returns the nullifier prefixes to request nullifiers for: the prefixes of the unspent
+    /// input notes' and committed output notes' nullifiers, widened to the configured prefix width,
+    /// along with the decoy prefixes.
+    fn get_sync_nullifier_prefixes(&self) -> Result<Vec<u16>, ClientError> {
+        let nullifier_prefixes: BTreeSet<u16> = self
+            .store
+            .get_unspent_input_note_nullifiers()?
+            .into_iter()
+            .chain(self.store.get_unspent_output_note_nullifiers()?)
+            .map(|nullifier| (nullifier.inner()[3].as_int() >> FILTER_ID_SHIFT) as u16)
+            .flat_map(|prefix| {
+                widen_nullifier_prefix(prefix, self.sync_config.nullifier_prefix_bits)
+            })
+            .chain(self.sync_decoys.nullifier_prefixes.iter().copied())
+            .collect();
+
+        Ok(nullifier_prefixes.into_iter().collect())
+    }
+
+    /// Returns the nullifier prefixes for the sync request that follows the one that received
+    /// `new_notes`, before they are applied to the store: the prefixes of the current request
+    /// along with the prefixes of the notes that are tracked once `new_notes` is applied.
+    fn get_next_sync_nullifier_prefixes(
+        &self,
+        current_prefixes: &[u16],
+        new_notes: &SyncedNewNotes,
+    ) -> Result<Vec<u16>, ClientError> {
+        let committed_note_ids: BTreeSet<NoteId> =
+            new_notes.new_inclusion_proofs().iter().map(|(note_id, _)| *note_id).collect();
+
+        let mut new_nullifiers: Vec<Digest> = new_notes
+            .new_public_notes()
+            .iter()
+            .map(|note| note.note().nullifier().inner())
+            .collect();
+        for note in self.store.get_input_notes(NoteFilter::Pending)? {
+            if committed_note_ids.contains(&note.id()) {
+                new_nullifiers.push(Digest::try_from(note.nullifier())?);
+            }
+        }
+        for note in self.store.get_output_notes(NoteFilter::Pending)? {
+            if !committed_note_ids.contains(&note.id()) {
+                continue;
+            }
+            if let Some(details) = note.details() {
+                new_nullifiers.push(Digest::try_from(details.nullifier())?);
+            }
+        }
+
+        let nullifier_prefixes: BTreeSet<u16> = new_nullifiers
+            .iter()
+            .map(|nullifier| (nullifier[3].as_int() >> FILTER_ID_SHIFT) as u16)
+            .flat_map(|prefix| {
+                widen_nullifier_prefix(prefix, self.sync_config.nullifier_prefix_bits)
+            })
+            .chain(current_prefixes.iter().copied())
+            .collect();
+
+        Ok(nullifier_prefixes.into_iter().collect())
+    }
+
     /// Extracts information about notes that the client is interested in, creating the note inclusion
     /// proof in order to correctly update store data
+    ///
+    /// Notes received only because their tag is in `extra_note_tags` (widened account tags and
+    /// decoys) are ignored unless the client is already tracking them.
     async fn get_note_details(
         &mut self,
         committed_notes: Vec<CommittedNote>,
         block_header: &BlockHeader,
+        extra_note_tags: &BTreeSet<u32>,
     ) -> Result<SyncedNewNotes, ClientError> {
         // We'll only pick committed notes that we are tracking as input/output notes. Since the
         // sync response contains notes matching either the provided accounts or the provided tag
@@ -242,21 +924,27 @@ impl<N: NodeRpcClient, R: FeltRng, S: Store> Client<N, R, S> {
 
         let mut new_public_notes = vec![];
         let mut local_notes_proofs = vec![];
+        let mut invalid_notes = vec![];
 
-        let pending_input_notes =
-            self.store.get_input_notes(NoteFilter::Pending)?.into_iter().map(|n| n.id());
-
-        let pending_output_notes =
-            self.store.get_output_notes(NoteFilter::Pending)?.into_iter().map(|n| n.id());
+        // The node does not send the notes' `aux`, so their inclusion is verified with the
+        // metadata stored by the client
+        let pending_input_notes = self
+            .store
+            .get_input_notes(NoteFilter::Pending)?
+            .into_iter()
+            .map(|n| (n.id(), n.metadata().copied()));
 
-        let mut all_pending_notes: BTreeSet<NoteId> = BTreeSet::new();
+        let pending_output_notes = self
+            .store
+            .get_output_notes(NoteFilter::Pending)?
+            .into_iter()
+            .map(|n| (n.id(), Some(*n.metadata())));
 
-        pending_input_notes.chain(pending_output_notes).for_each(|id| {
-            all_pending_notes.insert(id);
-        });
+        let all_pending_notes: BTreeMap<NoteId, Option<NoteMetadata>> =
+            pending_input_notes.chain(pending_output_notes).collect();
 
         for committed_note in committed_notes {
-            if all_pending_notes.contains(committed_note.note_id()) {
+            if let Some(local_metadata) = all_pending_notes.get(committed_note.note_id()) {
                 // The note belongs to our locally tracked set of pending notes, build the inclusion proof
                 let note_with_inclusion_proof = NoteInclusionProof::new(
                     block_header.block_num(),
@@ -268,42 +956,77 @@ impl<N: NodeRpcClient, R: FeltRng, S: Store> Client<N, R, S> {
                 .map_err(ClientError::NoteError)
                 .map(|proof| (*committed_note.note_id(), proof))?;
 
+                // Notes imported without their metadata can't be verified until it is known, which
+                // happens at the latest when they are consumed, as the transaction kernel
+                // authenticates them against the block's note root
+                let verified = local_metadata.map_or(true, |metadata| {
+                    verify_note_inclusion(
+                        *committed_note.note_id(),
+                        &metadata,
+                        &note_with_inclusion_proof.1,
+                    )
+                });
+                if !verified {
+                    invalid_notes.push(*committed_note.note_id());
+                    continue;
+                }
+
                 local_notes_proofs.push(note_with_inclusion_proof);
+            } else if extra_note_tags.contains(&u32::from(committed_note.tag())) {
+                // The note was only received to hide the client's tags from the node
+                continue;
             } else {
                 // The note is public and we are not tracking it, push to the list of IDs to query
                 new_public_notes.push(*committed_note.note_id());
             }
         }
 
+        if !invalid_notes.is_empty() {
+            return Err(VerificationError::InvalidNoteInclusionProofs(invalid_notes).into());
+        }
+
         // Query the node for input note data and build the entities
-        let new_public_notes =
+        let (new_public_notes, new_expected_notes) =
             self.fetch_public_note_details(&new_public_notes, block_header).await?;
 
-        Ok(SyncedNewNotes::new(new_public_notes, local_notes_proofs))
+        Ok(SyncedNewNotes::new(new_public_notes, local_notes_proofs, new_expected_notes))
     }
 
     /// Queries the node for all received notes that are not being locally tracked in the client
     ///
-    /// The client can receive metadata for private notes that it's not tracking. In this case,
-    /// notes are ignored for now as they become useless until details are imported.
+    /// The client can receive metadata for private notes that it's not tracking. These are
+    /// returned as [ExpectedNoteRecord]s, so that they can be committed once their details are
+    /// imported.
     async fn fetch_public_note_details(
         &mut self,
         query_notes: &[NoteId],
         block_header: &BlockHeader,
-    ) -> Result<Vec<InputNote>, ClientError> {
+    ) -> Result<(Vec<InputNote>, Vec<ExpectedNoteRecord>), ClientError> {
         if query_notes.is_empty() {
-            return Ok(vec![]);
+            return Ok((vec![], vec![]));
         }
         info!("Getting note details for notes that are not being tracked.");
 
         let notes_data = self.rpc_api.get_notes_by_id(query_notes).await?;
         let mut return_notes = Vec::with_capacity(query_notes.len());
+        let mut expected_notes = vec![];
+        let mut invalid_notes = vec![];
         for note_data in notes_data {
             match note_data {
-                NoteDetails::OffChain(id, ..) => {
-                    // TODO: Is there any benefit to not ignoring these? In any case we do not have
-                    // the recipient which is mandatory right now.
-                    info!("Note {} is private but the client is not tracking it, ignoring.", id);
+                NoteDetails::OffChain(id, metadata, inclusion_proof) => {
+                    info!("Note {} is private but the client is not tracking it, expecting its details.", id);
+                    let note_inclusion_proof = NoteInclusionProof::new(
+                        block_header.block_num(),
+                        block_header.sub_hash(),
+                        block_header.note_root(),
+                        inclusion_proof.note_index as u64,
+                        inclusion_proof.merkle_path,
+                    )
+                    .map_err(ClientError::NoteError)?;
+
+                    // The node does not send the note's `aux`, so its inclusion is only verified
+                    // once its details are imported (see [Client::import_input_note])
+                    expected_notes.push(ExpectedNoteRecord::new(id, metadata, note_inclusion_proof))
                 },
                 NoteDetails::Public(note, inclusion_proof) => {
                     info!("Retrieved details for Note ID {}.", note.id());
@@ -316,11 +1039,21 @@ impl<N: NodeRpcClient, R: FeltRng, S: Store> Client<N, R, S> {
                     )
                     .map_err(ClientError::NoteError)?;
 
+                    if !verify_note_inclusion(note.id(), note.metadata(), &note_inclusion_proof) {
+                        invalid_notes.push(note.id());
+                        continue;
+                    }
+
                     return_notes.push(InputNote::new(note, note_inclusion_proof))
                 },
             }
         }
-        Ok(return_notes)
+
+        if !invalid_notes.is_empty() {
+            return Err(VerificationError::InvalidNoteInclusionProofs(invalid_notes).into());
+        }
+
+        Ok((return_notes, expected_notes))
     }
 
     /// Builds the current view of the chain's [PartialMmr]. Because we want to add all new
@@ -348,31 +1081,51 @@ impl<N: NodeRpcClient, R: FeltRng, S: Store> Client<N, R, S> {
         Ok(PartialMmr::from_parts(current_peaks, tracked_nodes, track_latest))
     }
 
-    /// Extracts information about nullifiers for unspent input notes that the client is tracking
-    /// from the received [SyncStateResponse]
-    fn get_new_nullifiers(&self, new_nullifiers: Vec<Digest>) -> Result<Vec<Digest>, ClientError> {
+    /// Extracts information about nullifiers for unspent input notes and committed output notes
+    /// that the client is tracking from the received [SyncStateResponse]
+    ///
+    /// Nullifiers received for widened or decoy prefixes are filtered out here, as they don't
+    /// match any of the tracked nullifiers.
+    fn get_new_nullifiers(
+        &self,
+        new_nullifiers: Vec<NullifierUpdate>,
+    ) -> Result<Vec<NullifierUpdate>, ClientError> {
         // Get current unspent nullifiers
         let nullifiers = self
             .store
             .get_unspent_input_note_nullifiers()?
-            .iter()
+            .into_iter()
+            .chain(self.store.get_unspent_output_note_nullifiers()?)
             .map(|nullifier| nullifier.inner())
-            .collect::<Vec<_>>();
+            .collect::<BTreeSet<_>>();
 
         let new_nullifiers = new_nullifiers
             .into_iter()
-            .filter(|nullifier| nullifiers.contains(nullifier))
+            .filter(|nullifier_update| nullifiers.contains(&nullifier_update.nullifier))
             .collect();
 
         Ok(new_nullifiers)
     }
 
+    /// Returns the IDs of the tracked input notes that are consumed according to the provided
+    /// nullifiers
+    fn get_consumed_note_ids(&self, nullifiers: &[Digest]) -> Result<Vec<NoteId>, ClientError> {
+        let mut consumed_note_ids = Vec::new();
+        for note in self.store.get_input_notes(NoteFilter::Committed)? {
+            if nullifiers.contains(&Digest::try_from(note.nullifier())?) {
+                consumed_note_ids.push(note.id());
+            }
+        }
+
+        Ok(consumed_note_ids)
+    }
+
     async fn get_updated_onchain_accounts(
         &mut self,
         account_updates: &[(AccountId, Digest)],
         current_onchain_accounts: &[AccountStub],
     ) -> Result<Vec<Account>, ClientError> {
-        let mut accounts_to_update: Vec<Account> = Vec::new();
+        let mut accounts_to_update: Vec<AccountId> = Vec::new();
         for (remote_account_id, remote_account_hash) in account_updates {
             // check if this updated account is tracked by the client
             let current_account = current_onchain_accounts
@@ -381,20 +1134,42 @@ impl<N: NodeRpcClient, R: FeltRng, S: Store> Client<N, R, S> {
 
             if let Some(tracked_account) = current_account {
                 info!("On-chain account hash difference detected for account with ID: {}. Fetching node for updates...", tracked_account.id());
-                let account = self.rpc_api.get_account_update(tracked_account.id()).await?;
-                accounts_to_update.push(account);
+                accounts_to_update.push(tracked_account.id());
             }
         }
-        Ok(accounts_to_update)
+
+        if accounts_to_update.is_empty() {
+            return Ok(vec![]);
+        }
+
+        self.rpc_api
+            .get_account_updates(
+                &accounts_to_update,
+                self.sync_config.max_concurrent_account_requests,
+            )
+            .await
+            .map_err(|err| err.into())
     }
 
     /// Validates account hash updates and returns an error if there is a mismatch.
+    ///
+    /// Accounts with uncommitted transactions are skipped, as their local state is the one
+    /// produced by those transactions. Their hash updates are checked against the transactions
+    /// instead, which are either committed or discarded.
     fn validate_local_account_hashes(
         &mut self,
         account_updates: &[(AccountId, Digest)],
         current_offchain_accounts: &[AccountStub],
+        uncommitted_transactions: &[TransactionRecord],
     ) -> Result<(), ClientError> {
         for (remote_account_id, remote_account_hash) in account_updates {
+            if uncommitted_transactions
+                .iter()
+                .any(|transaction| transaction.account_id == *remote_account_id)
+            {
+                continue;
+            }
+
             // ensure that if we track that account, it has the same hash
             let mismatched_accounts = current_offchain_accounts
                 .iter()
@@ -438,38 +1213,260 @@ fn apply_mmr_changes(
     Ok((partial_mmr.peaks(), new_authentication_nodes))
 }
 
+/// Verifies that the chain MMR peaks, once updated up to the block before `block_header`, hash to
+/// the chain root committed to by `block_header`.
+///
+/// # Errors
+///
+/// Returns a [VerificationError::ChainRootMismatch] if the peaks and the header's chain root
+/// don't match, which means that the header and the MMR delta received from the node are not
+/// consistent with the chain tracked by the client.
+fn verify_chain_root(
+    peaks: &MmrPeaks,
+    block_header: &BlockHeader,
+) -> Result<(), VerificationError> {
+    let computed_chain_root = peaks.hash_peaks();
+    if computed_chain_root != block_header.chain_root() {
+        return Err(VerificationError::ChainRootMismatch(
+            block_header.block_num(),
+            block_header.chain_root(),
+            computed_chain_root,
+        ));
+    }
+
+    Ok(())
+}
+
+/// Returns the value that the note tree of a block holds for a note with the provided ID and
+/// metadata.
+///
+/// Each note takes up two adjacent leaves of the tree, holding its ID and metadata, so the note is
+/// committed to by their parent node.
+pub(crate) fn note_tree_leaf(note_id: NoteId, metadata: &NoteMetadata) -> Digest {
+    Rpo256::merge(&[note_id.inner(), Word::from(metadata).into()])
+}
+
+/// Returns whether `proof` opens its note root at the note with the provided ID and metadata.
+///
+/// A successful check only means that the note is part of the chain tracked by the client if the
+/// note root of the proof is the one of a block header verified against the chain MMR (see
+/// [verify_chain_root]).
+pub(crate) fn verify_note_inclusion(
+    note_id: NoteId,
+    metadata: &NoteMetadata,
+    proof: &NoteInclusionProof,
+) -> bool {
+    proof.note_path().verify(
+        proof.origin().node_index.value(),
+        note_tree_leaf(note_id, metadata),
+        &proof.note_root(),
+    )
+}
+
+/// Returns every nullifier prefix that shares the `prefix_bits` most significant bits with
+/// `prefix`.
+fn widen_nullifier_prefix(prefix: u16, prefix_bits: u8) -> impl Iterator<Item = u16> {
+    let free_bits = u32::from(NULLIFIER_PREFIX_BITS - prefix_bits);
+    let base = u32::from(prefix) >> free_bits << free_bits;
+
+    (0..1u32 << free_bits).map(move |low_bits| (base | low_bits) as u16)
+}
+
+/// Returns every account tag whose account ID prefix shares the `prefix_bits` most significant
+/// bits with the one encoded in `tag`.
+///
+/// Only local execution tags encode an account ID prefix, so any other tag is returned as is.
+fn widen_account_tag(tag: NoteTag, prefix_bits: u8) -> impl Iterator<Item = NoteTag> {
+    let tag = u32::from(tag);
+    let free_bits = if tag & LOCAL_ACCOUNT_TAG_PREFIX == LOCAL_ACCOUNT_TAG_PREFIX {
+        u32::from(ACCOUNT_TAG_PREFIX_BITS - prefix_bits)
+    } else {
+        0
+    };
+    let base = tag & !(((1 << free_bits) - 1) << 16);
+
+    (0..1u32 << free_bits).map(move |low_bits| NoteTag::from(base | (low_bits << 16)))
+}
+
+/// Returns the uncommitted transactions that can no longer be committed based on the state
+/// update info, and should be discarded.
+///
+/// A transaction that is not being committed is discarded if:
+///
+/// - Any of its input notes was consumed, which means that another transaction consumed it
+/// - Its account was updated to a state that is neither its initial state nor the final state of
+/// another uncommitted transaction of the account, which means that the account moved on
+/// without it
+///
+/// Uncommitted transactions executed on top of a discarded one are discarded as well.
+fn get_transactions_to_discard<'a>(
+    uncommitted_transactions: &'a [TransactionRecord],
+    transactions_to_commit: &[TransactionId],
+    nullifiers: &[Digest],
+    account_hash_updates: &[(AccountId, Digest)],
+) -> Vec<&'a TransactionRecord> {
+    let pending_transactions: Vec<&TransactionRecord> = uncommitted_transactions
+        .iter()
+        .filter(|t| !transactions_to_commit.contains(&t.id))
+        .collect();
+
+    let is_stale = |t: &TransactionRecord| {
+        let consumed_input_notes = t.input_note_nullifiers.iter().any(|n| nullifiers.contains(n));
+        let outdated_account = account_hash_updates.iter().any(|(account_id, account_hash)| {
+            *account_id == t.account_id
+                && *account_hash != t.init_account_state
+                && !uncommitted_transactions.iter().any(|other| {
+                    other.account_id == t.account_id && other.final_account_state == *account_hash
+                })
+        });
+
+        consumed_input_notes || outdated_account
+    };
+
+    let mut discarded_transactions: Vec<&TransactionRecord> =
+        pending_transactions.iter().copied().filter(|t| is_stale(t)).collect();
+
+    // Transactions that build on the state produced by a discarded transaction are stale too
+    let mut new_discards = discarded_transactions.clone();
+    while !new_discards.is_empty() {
+        new_discards = pending_transactions
+            .iter()
+            .copied()
+            .filter(|t| {
+                !discarded_transactions.iter().any(|discarded| discarded.id == t.id)
+                    && new_discards.iter().any(|discarded| {
+                        discarded.account_id == t.account_id
+                            && discarded.final_account_state == t.init_account_state
+                    })
+            })
+            .collect();
+        discarded_transactions.extend(new_discards.iter().copied());
+    }
+
+    discarded_transactions
+}
+
 /// Returns the list of transactions that should be marked as committed based on the state update info
 ///
-/// To set an uncommitted transaction as committed three things must hold:
+/// An uncommitted transaction is known to be included in the chain if either:
 ///
-/// - All of the transaction's output notes are committed
-/// - All of the transaction's input notes are consumed, which means we got their nullifiers as
-/// part of the update
-/// - The account corresponding to the transaction hash matches the transaction's
-// final_account_state
+/// - The account's on-chain hash matches the transaction's `final_account_state`
+/// - Any of the transaction's output notes was committed with the transaction's account as its
+/// sender. Since we only receive the notes matching our tags, the absence of the rest of the
+/// output notes is not evidence of anything
+///
+/// Several transactions against the same account can be included in a single block, in which
+/// case only the last one is reflected by the on-chain hash. Uncommitted transactions are chained
+/// per account by `init_account_state` -> `final_account_state`, and all the transactions that
+/// lead to one known to be included are committed along with it.
 fn get_transactions_to_commit(
     uncommitted_transactions: &[TransactionRecord],
-    _note_ids: &[NoteId],
-    nullifiers: &[Digest],
+    committed_notes: &[(NoteId, AccountId)],
     account_hash_updates: &[(AccountId, Digest)],
 ) -> Vec<TransactionId> {
+    let included_transactions = uncommitted_transactions.iter().filter(|t| {
+        let account_hash_matches = account_hash_updates.iter().any(|(account_id, account_hash)| {
+            *account_id == t.account_id && *account_hash == t.final_account_state
+        });
+        let output_note_committed = t
+            .output_notes
+            .iter()
+            .any(|note| committed_notes.contains(&(note.id(), t.account_id)));
+
+        account_hash_matches || output_note_committed
+    });
+
+    let mut transactions_to_commit: Vec<TransactionId> = Vec::new();
+    for transaction in included_transactions {
+        // Walk back the chain of transactions that lead to this one
+        let mut current = Some(transaction);
+        while let Some(t) = current {
+            if transactions_to_commit.contains(&t.id) {
+                break;
+            }
+            transactions_to_commit.push(t.id);
+
+            current = uncommitted_transactions.iter().find(|previous| {
+                previous.account_id == t.account_id
+                    && previous.final_account_state == t.init_account_state
+            });
+        }
+    }
+
+    // Keep the order in which the transactions were retrieved
     uncommitted_transactions
         .iter()
-        .filter(|t| {
-            // TODO: based on the discussion in
-            // https://github.com/0xPolygonMiden/miden-client/issues/144, we should be aware
-            // that in the future it'll be possible to have many transactions modifying an
-            // account be included in a single block. If that happens, we'll need to rewrite
-            // this check
-
-            // TODO: Review this. Because we receive note IDs based on account ID tags,
-            // we cannot base the status change on output notes alone;
-            t.input_note_nullifiers.iter().all(|n| nullifiers.contains(n))
-                //&& t.output_notes.iter().all(|n| note_ids.contains(&n.id()))
-                && account_hash_updates.iter().any(|(account_id, account_hash)| {
-                    *account_id == t.account_id && *account_hash == t.final_account_state
-                })
-        })
         .map(|t| t.id)
+        .filter(|id| transactions_to_commit.contains(id))
         .collect()
 }
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::{
+        accounts::AccountId,
+        transaction::{OutputNotes, TransactionId},
+        Digest, Felt, ZERO,
+    };
+
+    use super::{get_transactions_to_commit, get_transactions_to_discard};
+    use crate::{
+        client::transactions::{TransactionRecord, TransactionStatus},
+        mock::ACCOUNT_ID_REGULAR,
+    };
+
+    fn digest(value: u64) -> Digest {
+        Digest::from([Felt::new(value), ZERO, ZERO, ZERO])
+    }
+
+    /// Returns a pending transaction of the mock account that moves it from state `init` to
+    /// state `fin`.
+    fn transaction(id: u64, init: u64, fin: u64) -> TransactionRecord {
+        TransactionRecord::new(
+            TransactionId::from(digest(100 + id)),
+            AccountId::try_from(ACCOUNT_ID_REGULAR).unwrap(),
+            digest(init),
+            digest(fin),
+            vec![],
+            OutputNotes::new(vec![]).unwrap(),
+            None,
+            0,
+            TransactionStatus::Pending,
+        )
+    }
+
+    #[test]
+    fn commit_chain_of_transactions() {
+        let account_id = AccountId::try_from(ACCOUNT_ID_REGULAR).unwrap();
+        let transactions = [transaction(1, 1, 2), transaction(2, 2, 3), transaction(3, 3, 4)];
+
+        // the on-chain state reflects the first two transactions, included in the same block
+        let account_hash_updates = [(account_id, digest(3))];
+        let to_commit = get_transactions_to_commit(&transactions, &[], &account_hash_updates);
+        assert_eq!(to_commit, vec![transactions[0].id, transactions[1].id]);
+
+        // the last transaction builds on the committed ones, so it's still pending
+        let to_discard =
+            get_transactions_to_discard(&transactions, &to_commit, &[], &account_hash_updates);
+        assert!(to_discard.is_empty());
+    }
+
+    #[test]
+    fn discard_transactions_on_diverged_account() {
+        let account_id = AccountId::try_from(ACCOUNT_ID_REGULAR).unwrap();
+        let transactions = [transaction(1, 1, 2), transaction(2, 2, 3)];
+
+        // the account moved to a state none of the transactions lead to
+        let account_hash_updates = [(account_id, digest(5))];
+        let to_commit = get_transactions_to_commit(&transactions, &[], &account_hash_updates);
+        assert!(to_commit.is_empty());
+
+        let to_discard =
+            get_transactions_to_discard(&transactions, &to_commit, &[], &account_hash_updates);
+        let to_discard: Vec<TransactionId> = to_discard.iter().map(|t| t.id).collect();
+        assert_eq!(to_discard, vec![transactions[0].id, transactions[1].id]);
+    }
+}
@@ -0,0 +1,183 @@
+use core::time::Duration;
+
+use miden_objects::crypto::rand::FeltRng;
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+use super::{rpc::NodeRpcClient, sync::SyncStatus, Client};
+use crate::{errors::ClientError, store::Store};
+
+// SYNC SERVICE CONFIG
+// ================================================================================================
+
+/// Configuration for the [SyncService].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncServiceConfig {
+    /// Time to wait between two successful syncs.
+    pub interval: Duration,
+    /// Time to wait before retrying after the first failed attempt to reach the node. The delay
+    /// doubles after every consecutive failure.
+    pub initial_backoff: Duration,
+    /// Upper bound for the delay between retries.
+    pub max_backoff: Duration,
+}
+
+impl Default for SyncServiceConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(10),
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(300),
+        }
+    }
+}
+
+// SYNC SERVICE STATE
+// ================================================================================================
+
+/// Outcome of the syncs performed so far by a [SyncService].
+#[derive(Debug, Clone, Default)]
+pub struct SyncServiceState {
+    /// Status of the latest successful sync, if any.
+    pub latest_status: Option<SyncStatus>,
+    /// Description of the error returned by the latest sync attempt. It is cleared after a
+    /// successful sync.
+    pub last_error: Option<String>,
+    /// Number of consecutive failed sync attempts.
+    pub consecutive_failures: u32,
+}
+
+// SYNC SERVICE
+// ================================================================================================
+
+/// Periodically syncs a [Client] with the Miden node.
+///
+/// The service calls [Client::sync_state] every [SyncServiceConfig::interval]. When the node
+/// cannot be reached (that is, the sync fails with a [ClientError::NodeRpcClientError]) the
+/// service retries with an exponential backoff instead. The outcome of every attempt is published
+/// through the [SyncServiceHandle] returned alongside the service.
+///
+/// [SyncService::run] drives the service and must be polled by a tokio runtime. Since [Client] is
+/// not `Send`, it can be spawned with [tokio::task::spawn_local] inside a
+/// [LocalSet](tokio::task::LocalSet), or awaited directly.
+pub struct SyncService<N: NodeRpcClient, R: FeltRng, S: Store> {
+    client: Client<N, R, S>,
+    config: SyncServiceConfig,
+    state: watch::Sender<SyncServiceState>,
+    shutdown: watch::Receiver<bool>,
+}
+
+impl<N: NodeRpcClient, R: FeltRng, S: Store> SyncService<N, R, S> {
+    /// Returns a new [SyncService] that owns `client`, along with a [SyncServiceHandle] that can
+    /// be used to inspect and stop it.
+    pub fn new(client: Client<N, R, S>, config: SyncServiceConfig) -> (Self, SyncServiceHandle) {
+        let (state_sender, state_receiver) = watch::channel(SyncServiceState::default());
+        let (shutdown_sender, shutdown_receiver) = watch::channel(false);
+
+        let service = Self {
+            client,
+            config,
+            state: state_sender,
+            shutdown: shutdown_receiver,
+        };
+        let handle = SyncServiceHandle {
+            state: state_receiver,
+            shutdown: shutdown_sender,
+        };
+
+        (service, handle)
+    }
+
+    /// Runs the service until it is stopped through its [SyncServiceHandle] (or the handle is
+    /// dropped), and returns the [Client] back.
+    ///
+    /// The first sync happens right away. A sync that is in progress when the service is stopped
+    /// is allowed to finish, so the store is never left mid-update.
+    pub async fn run(mut self) -> Client<N, R, S> {
+        let mut backoff = self.config.initial_backoff;
+
+        loop {
+            if *self.shutdown.borrow() {
+                break;
+            }
+
+            let delay = match self.client.sync_state().await {
+                Ok(summary) => {
+                    let block_num = summary.block_num;
+                    info!("Background sync reached block {}", block_num);
+                    backoff = self.config.initial_backoff;
+                    self.state.send_modify(|state| {
+                        state.latest_status = Some(SyncStatus::SyncedToLastBlock(block_num));
+                        state.last_error = None;
+                        state.consecutive_failures = 0;
+                    });
+
+                    self.config.interval
+                },
+                Err(err) => {
+                    warn!("Background sync failed: {err}");
+                    let delay = if matches!(err, ClientError::NodeRpcClientError(_)) {
+                        let delay = backoff;
+                        backoff = (backoff * 2).min(self.config.max_backoff);
+                        delay
+                    } else {
+                        self.config.interval
+                    };
+                    self.state.send_modify(|state| {
+                        state.last_error = Some(err.to_string());
+                        state.consecutive_failures += 1;
+                    });
+
+                    delay
+                },
+            };
+
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {},
+                // Either a stop was requested or the handle was dropped
+                _ = self.shutdown.changed() => break,
+            }
+        }
+
+        info!("Background sync stopped");
+        self.client
+    }
+}
+
+// SYNC SERVICE HANDLE
+// ================================================================================================
+
+/// Handle to a running [SyncService], used to inspect its state and stop it.
+pub struct SyncServiceHandle {
+    state: watch::Receiver<SyncServiceState>,
+    shutdown: watch::Sender<bool>,
+}
+
+impl SyncServiceHandle {
+    /// Returns the current state of the service.
+    pub fn state(&self) -> SyncServiceState {
+        self.state.borrow().clone()
+    }
+
+    /// Returns the status of the latest successful sync, if any.
+    pub fn latest_status(&self) -> Option<SyncStatus> {
+        self.state.borrow().latest_status
+    }
+
+    /// Returns the error returned by the latest sync attempt, if it failed.
+    pub fn last_error(&self) -> Option<String> {
+        self.state.borrow().last_error.clone()
+    }
+
+    /// Waits until the service publishes a new state. Returns `false` if the service is no
+    /// longer running.
+    pub async fn changed(&mut self) -> bool {
+        self.state.changed().await.is_ok()
+    }
+
+    /// Requests the service to stop. The service finishes any sync in progress and then makes
+    /// [SyncService::run] return.
+    pub fn stop(&self) {
+        self.shutdown.send_replace(true);
+    }
+}
@@ -14,14 +14,16 @@ use miden_objects::{
     Digest, Felt, Word,
 };
 use miden_tx::{ProvingOptions, ScriptTarget, TransactionProver};
-use rand::Rng;
 use tracing::info;
 
 use self::transaction_request::{PaymentTransactionData, TransactionRequest, TransactionTemplate};
-use super::{note_screener::NoteRelevance, rpc::NodeRpcClient, Client, FeltRng};
+use super::{
+    accounts::derive_rng_seed, derive_random_coin, events::ClientEvent, get_random_coin,
+    note_screener::NoteRelevance, rpc::NodeRpcClient, Client, FeltRng,
+};
 use crate::{
     client::NoteScreener,
-    errors::ClientError,
+    errors::{ClientError, StoreError},
     store::{AuthInfo, Store, TransactionFilter},
 };
 
@@ -141,6 +143,9 @@ pub enum TransactionStatus {
     Pending,
     /// Transaction has been committed and included at the specified block number
     Committed(u32),
+    /// Transaction can no longer be included in the chain, either because the node rejected it
+    /// or because its input notes or account state were changed by other transactions
+    Discarded,
 }
 
 impl std::fmt::Display for TransactionStatus {
@@ -150,6 +155,7 @@ impl std::fmt::Display for TransactionStatus {
             TransactionStatus::Committed(block_number) => {
                 write!(f, "Committed (Block: {})", block_number)
             },
+            TransactionStatus::Discarded => write!(f, "Discarded"),
         }
     }
 }
@@ -263,7 +269,7 @@ impl<N: NodeRpcClient, R: FeltRng, S: Store> Client<N, R, S> {
 
         self.submit_proven_transaction_request(proven_transaction.clone()).await?;
 
-        let note_screener = NoteScreener::new(&self.store);
+        let note_screener = NoteScreener::new(self.store.as_ref());
         let mut relevant_notes = BTreeMap::new();
 
         for (idx, note) in tx_result.created_notes().iter().enumerate() {
@@ -276,9 +282,23 @@ impl<N: NodeRpcClient, R: FeltRng, S: Store> Client<N, R, S> {
         let mut tx_result = tx_result;
         tx_result.set_relevant_notes(relevant_notes);
 
+        let events: Vec<ClientEvent> = core::iter::once(ClientEvent::TransactionSubmitted {
+            transaction_id: proven_transaction.id(),
+            account_id: proven_transaction.account_id(),
+        })
+        .chain(
+            tx_result
+                .relevant_notes()
+                .into_iter()
+                .map(|note| ClientEvent::NoteReceived { note_id: note.id() }),
+        )
+        .collect();
+
         // Transaction was proven and submitted to the node correctly, persist note details and update account
         self.store.apply_transaction(tx_result)?;
 
+        self.emit_events(&events);
+
         Ok(())
     }
 
@@ -306,16 +326,62 @@ impl<N: NodeRpcClient, R: FeltRng, S: Store> Client<N, R, S> {
         Ok(self.rpc_api.submit_proven_transaction(proven_transaction).await?)
     }
 
-    // HELPERS
+    // NOTE RECOVERY
     // --------------------------------------------------------------------------------------------
 
-    /// Gets [RpoRandomCoin] from the client
-    fn get_random_coin(&self) -> RpoRandomCoin {
-        // TODO: Initialize coin status once along with the client and persist status for retrieval
-        let mut rng = rand::thread_rng();
-        let coin_seed: [u64; 4] = rng.gen();
+    /// Re-derives the note created by a transaction built from `transaction_template` by any
+    /// client set up with this client's master seed, such as the wallet this client was restored
+    /// from.
+    ///
+    /// The note is re-created with the serial numbers of the first `max_notes` notes created with
+    /// every random coin epoch up to the client's own, and the one whose ID the node knows is
+    /// returned. Returns `None` if the node knows none of them or if the template does not create
+    /// notes.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [StoreError::MasterSeedNotFound] if the client has no master seed.
+    pub async fn recover_created_note(
+        &mut self,
+        transaction_template: &TransactionTemplate,
+        max_notes: u64,
+    ) -> Result<Option<Note>, ClientError> {
+        let (master_seed, rng_epoch) =
+            self.store.get_master_seed()?.ok_or(StoreError::MasterSeedNotFound)?;
+
+        for epoch in 0..=rng_epoch {
+            let rng_seed = derive_rng_seed(master_seed, epoch);
+            let mut counters = 0..max_notes;
+            loop {
+                let mut candidates = BTreeMap::new();
+                for counter in counters.by_ref().take(NOTE_RECOVERY_BATCH_SIZE) {
+                    let random_coin = derive_random_coin(rng_seed, counter);
+                    match create_template_note(transaction_template, random_coin)? {
+                        Some(note) => candidates.insert(note.id(), note),
+                        None => return Ok(None),
+                    };
+                }
+                if candidates.is_empty() {
+                    break;
+                }
+
+                let note_ids: Vec<NoteId> = candidates.keys().copied().collect();
+                let known_notes = self.rpc_api.get_notes_by_id(&note_ids).await?;
+                if let Some(note) = known_notes.first() {
+                    return Ok(candidates.remove(&note.id()));
+                }
+            }
+        }
 
-        RpoRandomCoin::new(coin_seed.map(Felt::new))
+        Ok(None)
+    }
+
+    // HELPERS
+    // --------------------------------------------------------------------------------------------
+
+    /// Gets a new [RpoRandomCoin] derived from the client's persisted seed
+    fn get_random_coin(&self) -> Result<RpoRandomCoin, ClientError> {
+        Ok(get_random_coin(self.store.as_ref())?)
     }
 
     /// Helper to build a [TransactionRequest] for P2ID-type transactions easily.
@@ -329,7 +395,7 @@ impl<N: NodeRpcClient, R: FeltRng, S: Store> Client<N, R, S> {
         recall_height: Option<u32>,
         note_type: NoteType,
     ) -> Result<TransactionRequest, ClientError> {
-        let random_coin = self.get_random_coin();
+        let random_coin = self.get_random_coin()?;
 
         let created_note = if let Some(recall_height) = recall_height {
             create_p2idr_note(
@@ -391,7 +457,7 @@ impl<N: NodeRpcClient, R: FeltRng, S: Store> Client<N, R, S> {
         target_account_id: AccountId,
         note_type: NoteType,
     ) -> Result<TransactionRequest, ClientError> {
-        let random_coin = self.get_random_coin();
+        let random_coin = self.get_random_coin()?;
         let created_note = create_p2id_note(
             asset.faucet_id(),
             target_account_id,
@@ -435,6 +501,49 @@ impl<N: NodeRpcClient, R: FeltRng, S: Store> Client<N, R, S> {
 // HELPERS
 // ================================================================================================
 
+/// Amount of candidate notes whose IDs are looked up on the node at once when recovering a note.
+const NOTE_RECOVERY_BATCH_SIZE: usize = 100;
+
+/// Creates the note output by a transaction built from `transaction_template`, drawing its
+/// serial number from `random_coin` as [Client::build_transaction_request] does. Returns `None` if
+/// the template does not create notes.
+fn create_template_note(
+    transaction_template: &TransactionTemplate,
+    random_coin: RpoRandomCoin,
+) -> Result<Option<Note>, ClientError> {
+    let note = match transaction_template {
+        TransactionTemplate::ConsumeNotes(..) => return Ok(None),
+        TransactionTemplate::MintFungibleAsset(asset, target_account_id, note_type) => {
+            create_p2id_note(
+                asset.faucet_id(),
+                *target_account_id,
+                vec![(*asset).into()],
+                *note_type,
+                random_coin,
+            )?
+        },
+        TransactionTemplate::PayToId(payment_data, note_type) => create_p2id_note(
+            payment_data.account_id(),
+            payment_data.target_account_id(),
+            vec![payment_data.asset()],
+            *note_type,
+            random_coin,
+        )?,
+        TransactionTemplate::PayToIdWithRecall(payment_data, recall_height, note_type) => {
+            create_p2idr_note(
+                payment_data.account_id(),
+                payment_data.target_account_id(),
+                vec![payment_data.asset()],
+                *note_type,
+                *recall_height,
+                random_coin,
+            )?
+        },
+    };
+
+    Ok(Some(note))
+}
+
 pub(crate) fn prepare_word(word: &Word) -> String {
     word.iter().map(|x| x.as_int().to_string()).collect::<Vec<_>>().join(".")
 }
@@ -1,5 +1,5 @@
 use core::fmt;
-use std::path::PathBuf;
+use std::{collections::BTreeMap, path::PathBuf, time::Duration};
 
 use figment::{
     value::{Dict, Map},
@@ -7,6 +7,11 @@ use figment::{
 };
 use serde::{Deserialize, Serialize};
 
+use crate::client::{
+    rpc::{NodeRpcClientEndpoint, RequestPolicy},
+    sync::SyncConfig,
+};
+
 // CLIENT CONFIG
 // ================================================================================================
 
@@ -17,12 +22,16 @@ pub struct ClientConfig {
     pub rpc: RpcConfig,
     /// Describes settings related to the store.
     pub store: StoreConfig,
+    /// Describes settings related to the privacy of sync requests.
+    #[serde(default)]
+    pub sync: SyncConfig,
 }
 
 impl ClientConfig {
-    /// Returns a new instance of [ClientConfig] with the specified store path and node endpoint.
-    pub const fn new(store: StoreConfig, rpc: RpcConfig) -> Self {
-        Self { store, rpc }
+    /// Returns a new instance of [ClientConfig] with the specified store path and node endpoint,
+    /// and the default sync settings.
+    pub fn new(store: StoreConfig, rpc: RpcConfig) -> Self {
+        Self { store, rpc, sync: SyncConfig::default() }
     }
 }
 
@@ -42,6 +51,116 @@ impl Provider for ClientConfig {
     }
 }
 
+// CLIENT CONFIG FILE
+// ================================================================================================
+
+/// Contents of the client's configuration file.
+///
+/// Besides the top-level `[rpc]` and `[store]` sections, the file can define any number of named
+/// network profiles under `[profiles.<name>]`, each with its own RPC and store settings:
+///
+/// ```toml
+/// default_profile = "local"
+///
+/// [profiles.local]
+/// rpc = { endpoint = { protocol = "http", host = "localhost", port = 57291 } }
+/// store = { database_filepath = "local.sqlite3" }
+///
+/// [profiles.testnet]
+/// rpc = { endpoint = { protocol = "https", host = "rpc.testnet.miden.io", port = 443 } }
+/// store = { database_filepath = "testnet.sqlite3" }
+/// ```
+///
+/// Profiles never share settings with each other or with the top-level sections, so a store
+/// created against one network is never used against another.
+#[derive(Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ClientConfigFile {
+    /// Profile used when none is explicitly selected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_profile: Option<String>,
+    /// RPC settings used when no profile is selected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rpc: Option<RpcConfig>,
+    /// Store settings used when no profile is selected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub store: Option<StoreConfig>,
+    /// Sync settings used when no profile is selected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sync: Option<SyncConfig>,
+    /// Named network profiles.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, ClientConfig>,
+}
+
+impl ClientConfigFile {
+    /// Returns the [ClientConfig] of the specified profile.
+    ///
+    /// If `profile` is `None`, the configured `default_profile` is used, falling back to the
+    /// top-level `[rpc]` and `[store]` sections when no default profile is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the profile does not exist, if no profile was selected and the file
+    /// has no top-level sections, or if two configurations point to the same store.
+    pub fn select(mut self, profile: Option<&str>) -> Result<ClientConfig, String> {
+        self.validate()?;
+
+        match profile.map(str::to_string).or(self.default_profile.take()) {
+            Some(profile) => self.profiles.remove(&profile).ok_or_else(|| {
+                format!(
+                    "profile `{profile}` is not defined, available profiles are: {}",
+                    self.profile_names()
+                )
+            }),
+            None => match (self.rpc, self.store) {
+                (Some(rpc), Some(store)) => Ok(ClientConfig {
+                    rpc,
+                    store,
+                    sync: self.sync.unwrap_or_default(),
+                }),
+                _ if !self.profiles.is_empty() => Err(format!(
+                    "no profile selected and no default profile set, available profiles are: {}",
+                    self.profile_names()
+                )),
+                _ => Err("the configuration is missing the `rpc` or `store` section".to_string()),
+            },
+        }
+    }
+
+    /// Checks that no two configurations in the file share a store.
+    pub fn validate(&self) -> Result<(), String> {
+        let mut store_owners: BTreeMap<&str, &str> = BTreeMap::new();
+        let top_level = self.store.as_ref().map(|store| ("top-level", store));
+        let profiles = self.profiles.iter().map(|(name, config)| (name.as_str(), &config.store));
+
+        for (name, store) in top_level.into_iter().chain(profiles) {
+            if let Some(owner) = store_owners.insert(&store.database_filepath, name) {
+                return Err(format!(
+                    "`{owner}` and `{name}` configurations share the store at {}",
+                    store.database_filepath
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn profile_names(&self) -> String {
+        self.profiles.keys().cloned().collect::<Vec<_>>().join(", ")
+    }
+}
+
+impl From<ClientConfig> for ClientConfigFile {
+    fn from(config: ClientConfig) -> Self {
+        Self {
+            rpc: Some(config.rpc),
+            store: Some(config.store),
+            sync: Some(config.sync),
+            ..Default::default()
+        }
+    }
+}
+
 // ENDPOINT
 // ================================================================================================
 
@@ -143,14 +262,229 @@ impl Default for StoreConfig {
 // RPC CONFIG
 // ================================================================================================
 
-#[derive(Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
 pub struct RpcConfig {
     /// Address of the Miden node to connect to.
     pub endpoint: Endpoint,
+    /// Addresses of the nodes to fail over to, in order, when the previous ones are unavailable.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fallback_endpoints: Vec<Endpoint>,
+    /// Timeout of the requests to endpoints without a specific timeout, in milliseconds.
+    pub timeout_ms: u64,
+    /// Timeouts of specific endpoints, in milliseconds.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub endpoint_timeouts_ms: BTreeMap<NodeRpcClientEndpoint, u64>,
+    /// Maximum number of times a request to an idempotent endpoint is retried.
+    pub max_retries: u32,
+    /// Time waited before the first retry, doubled on every subsequent one, in milliseconds.
+    pub initial_backoff_ms: u64,
+    /// Upper bound of the time waited between retries, in milliseconds.
+    pub max_backoff_ms: u64,
+    /// TLS settings of the connection. TLS is used with the default settings when the endpoint's
+    /// protocol is `https` and no settings are provided. Only allowed for `https` endpoints.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls: Option<TlsConfig>,
+    /// Metadata headers sent with every request, such as an API key or an authorization token.
+    /// Only allowed for `https` endpoints.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl RpcConfig {
+    /// Returns the configured endpoints in the order they are tried.
+    pub fn endpoints(&self) -> impl Iterator<Item = &Endpoint> {
+        core::iter::once(&self.endpoint).chain(&self.fallback_endpoints)
+    }
+}
+
+impl Default for RpcConfig {
+    fn default() -> Self {
+        Self::from(Endpoint::default())
+    }
 }
 
 impl From<Endpoint> for RpcConfig {
     fn from(value: Endpoint) -> Self {
-        Self { endpoint: value }
+        let policy = RequestPolicy::default();
+
+        Self {
+            endpoint: value,
+            fallback_endpoints: Vec::new(),
+            timeout_ms: policy.timeout.as_millis() as u64,
+            endpoint_timeouts_ms: BTreeMap::new(),
+            max_retries: policy.max_retries,
+            initial_backoff_ms: policy.initial_backoff.as_millis() as u64,
+            max_backoff_ms: policy.max_backoff.as_millis() as u64,
+            tls: None,
+            metadata: BTreeMap::new(),
+        }
+    }
+}
+
+impl From<&RpcConfig> for RequestPolicy {
+    fn from(config: &RpcConfig) -> Self {
+        Self {
+            timeout: Duration::from_millis(config.timeout_ms),
+            endpoint_timeouts: config
+                .endpoint_timeouts_ms
+                .iter()
+                .map(|(endpoint, timeout_ms)| (*endpoint, Duration::from_millis(*timeout_ms)))
+                .collect(),
+            max_retries: config.max_retries,
+            initial_backoff: Duration::from_millis(config.initial_backoff_ms),
+            max_backoff: Duration::from_millis(config.max_backoff_ms),
+        }
+    }
+}
+
+// TLS CONFIG
+// ================================================================================================
+
+/// TLS settings of the connection to the node.
+#[derive(Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct TlsConfig {
+    /// PEM file with a certificate authority trusted in addition to the system's roots, such as
+    /// the one of a self-signed node certificate.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ca_certificate: Option<PathBuf>,
+    /// PEM file with the certificate the client authenticates with. Requires `client_key`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_certificate: Option<PathBuf>,
+    /// PEM file with the private key of `client_certificate`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_key: Option<PathBuf>,
+    /// Name the node's certificate is verified against, instead of the endpoint's host.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain_name: Option<String>,
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use figment::{
+        providers::{Format, Toml},
+        Figment,
+    };
+
+    use super::{ClientConfig, ClientConfigFile, Endpoint, RpcConfig, TlsConfig};
+    use crate::client::rpc::{NodeRpcClientEndpoint, RequestPolicy};
+
+    const PROFILES_CONFIG: &str = r#"
+        default_profile = "local"
+
+        [profiles.local]
+        rpc = { endpoint = { protocol = "http", host = "localhost", port = 57291 } }
+        store = { database_filepath = "local.sqlite3" }
+
+        [profiles.testnet]
+        rpc = { endpoint = { protocol = "https", host = "testnet.example.com", port = 443 } }
+        store = { database_filepath = "testnet.sqlite3" }
+    "#;
+
+    fn parse(config: &str) -> ClientConfigFile {
+        Figment::from(Toml::string(config)).extract().unwrap()
+    }
+
+    #[test]
+    fn select_profiles() {
+        let testnet = parse(PROFILES_CONFIG).select(Some("testnet")).unwrap();
+        assert_eq!(
+            testnet.rpc,
+            RpcConfig::from(Endpoint::new(
+                "https".to_string(),
+                "testnet.example.com".to_string(),
+                443
+            ))
+        );
+        assert_eq!(testnet.store.database_filepath, "testnet.sqlite3");
+
+        let local = parse(PROFILES_CONFIG).select(None).unwrap();
+        assert_eq!(local.store.database_filepath, "local.sqlite3");
+
+        assert!(parse(PROFILES_CONFIG).select(Some("devnet")).is_err());
+    }
+
+    #[test]
+    fn select_flat_config() {
+        let config = parse(
+            r#"
+            [rpc]
+            endpoint = { protocol = "http", host = "localhost", port = 57291 }
+
+            [store]
+            database_filepath = "store.sqlite3"
+            "#,
+        );
+
+        assert_eq!(config.select(None).unwrap(), ClientConfig::default());
+    }
+
+    #[test]
+    fn parse_request_policy() {
+        let config = parse(
+            r#"
+            [rpc]
+            endpoint = { protocol = "http", host = "localhost", port = 57291 }
+            timeout_ms = 2000
+            max_retries = 5
+            endpoint_timeouts_ms = { sync_state = 30000 }
+
+            [store]
+            database_filepath = "store.sqlite3"
+            "#,
+        )
+        .select(None)
+        .unwrap();
+
+        let policy = RequestPolicy::from(&config.rpc);
+        assert_eq!(policy.max_retries, 5);
+        assert_eq!(policy.timeout(NodeRpcClientEndpoint::SyncState), Duration::from_millis(30000));
+        assert_eq!(
+            policy.timeout(NodeRpcClientEndpoint::GetNotesById),
+            Duration::from_millis(2000)
+        );
+        assert_eq!(policy.backoff(0), RequestPolicy::default().initial_backoff);
+        assert_eq!(policy.backoff(31), RequestPolicy::default().max_backoff);
+    }
+
+    #[test]
+    fn parse_tls_and_metadata() {
+        let config = parse(
+            r#"
+            [rpc]
+            endpoint = { protocol = "https", host = "localhost", port = 57291 }
+            tls = { ca_certificate = "certs/ca.pem", domain_name = "node.local" }
+            metadata = { authorization = "Bearer token" }
+
+            [store]
+            database_filepath = "store.sqlite3"
+            "#,
+        )
+        .select(None)
+        .unwrap();
+
+        assert_eq!(
+            config.rpc.tls,
+            Some(TlsConfig {
+                ca_certificate: Some("certs/ca.pem".into()),
+                domain_name: Some("node.local".to_string()),
+                ..TlsConfig::default()
+            })
+        );
+        assert_eq!(config.rpc.metadata["authorization"], "Bearer token");
+    }
+
+    #[test]
+    fn profiles_cannot_share_a_store() {
+        let config = parse(&PROFILES_CONFIG.replace("testnet.sqlite3", "local.sqlite3"));
+
+        assert!(config.validate().is_err());
+        assert!(config.select(Some("testnet")).is_err());
     }
 }
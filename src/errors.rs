@@ -1,5 +1,6 @@
 use core::fmt;
 
+#[cfg(feature = "tonic")]
 use miden_node_proto::errors::ConversionError;
 use miden_objects::{
     accounts::AccountId, crypto::merkle::MmrError, notes::NoteId, AccountError, AssetError,
@@ -20,14 +21,19 @@ pub enum ClientError {
     DataDeserializationError(DeserializationError),
     HexParseError(HexParseError),
     ImportNewAccountWithoutSeed,
+    InvalidSyncConfig(String),
+    MasterSeedAlreadySet,
+    MissingClientComponent(&'static str),
     MissingOutputNotes(Vec<NoteId>),
     NoteError(NoteError),
     NoConsumableNoteForAccount(AccountId),
     NodeRpcClientError(NodeRpcClientError),
+    RngSeedAlreadyUsed,
     ScreenerError(ScreenerError),
     StoreError(StoreError),
     TransactionExecutionError(TransactionExecutorError),
     TransactionProvingError(TransactionProverError),
+    VerificationError(VerificationError),
 }
 
 impl fmt::Display for ClientError {
@@ -43,6 +49,13 @@ impl fmt::Display for ClientError {
                 f,
                 "import account error: can't import a new account without its initial seed"
             ),
+            ClientError::InvalidSyncConfig(err) => write!(f, "invalid sync configuration: {err}"),
+            ClientError::MasterSeedAlreadySet => {
+                write!(f, "master seed error: the client already has a master seed")
+            },
+            ClientError::MissingClientComponent(component) => {
+                write!(f, "client builder error: no {component} was provided")
+            },
             ClientError::MissingOutputNotes(note_ids) => {
                 write!(
                     f,
@@ -55,6 +68,10 @@ impl fmt::Display for ClientError {
             },
             ClientError::NoteError(err) => write!(f, "note error: {err}"),
             ClientError::NodeRpcClientError(err) => write!(f, "rpc api error: {err}"),
+            ClientError::RngSeedAlreadyUsed => write!(
+                f,
+                "master seed error: random coins were already derived from the client's current seed"
+            ),
             ClientError::ScreenerError(err) => write!(f, "note screener error: {err}"),
             ClientError::StoreError(err) => write!(f, "store error: {err}"),
             ClientError::TransactionExecutionError(err) => {
@@ -63,6 +80,7 @@ impl fmt::Display for ClientError {
             ClientError::TransactionProvingError(err) => {
                 write!(f, "transaction prover error: {err}")
             },
+            ClientError::VerificationError(err) => write!(f, "verification error: {err}"),
         }
     }
 }
@@ -124,6 +142,13 @@ impl From<ScreenerError> for ClientError {
     }
 }
 
+impl From<VerificationError> for ClientError {
+    fn from(err: VerificationError) -> Self {
+        Self::VerificationError(err)
+    }
+}
+
+#[cfg(feature = "sqlite")]
 impl From<rusqlite::Error> for ClientError {
     fn from(err: rusqlite::Error) -> Self {
         Self::StoreError(StoreError::from(err))
@@ -149,6 +174,7 @@ pub enum StoreError {
     AccountDataNotFound(AccountId),
     AccountError(AccountError),
     AccountHashMismatch(AccountId),
+    AccountIndexAlreadyUsed(u32),
     AccountStorageNotFound(Digest),
     BlockHeaderNotFound(u32),
     ChainMmrNodeNotFound(u64),
@@ -158,11 +184,14 @@ pub enum StoreError {
     InputNoteNotFound(NoteId),
     InputSerializationError(serde_json::Error),
     JsonDataDeserializationError(serde_json::Error),
+    MasterSeedNotFound,
     MmrError(MmrError),
     NoteInclusionProofError(NoteError),
     NoteTagAlreadyTracked(u64),
     ParsingError(String),
     QueryError(String),
+    RngSeedNotFound,
+    #[cfg(feature = "tonic")]
     RpcTypeConversionFailure(ConversionError),
     TransactionScriptError(TransactionScriptError),
     VaultDataNotFound(Digest),
@@ -180,11 +209,13 @@ impl From<AccountError> for StoreError {
     }
 }
 
+#[cfg(feature = "sqlite")]
 impl From<rusqlite_migration::Error> for StoreError {
     fn from(value: rusqlite_migration::Error) -> Self {
         StoreError::DatabaseError(value.to_string())
     }
 }
+#[cfg(feature = "sqlite")]
 impl From<rusqlite::Error> for StoreError {
     fn from(value: rusqlite::Error) -> Self {
         match value {
@@ -252,6 +283,9 @@ impl fmt::Display for StoreError {
             AccountHashMismatch(account_id) => {
                 write!(f, "account hash mismatch for account {account_id}")
             },
+            AccountIndexAlreadyUsed(account_index) => {
+                write!(f, "account index {account_index} was already used to derive an account")
+            },
             AccountStorageNotFound(root) => {
                 write!(f, "account storage data with root {} not found", root)
             },
@@ -277,6 +311,7 @@ impl fmt::Display for StoreError {
             JsonDataDeserializationError(err) => {
                 write!(f, "error deserializing data from JSON from the store: {err}")
             },
+            MasterSeedNotFound => write!(f, "master seed was not found in the store"),
             MmrError(err) => write!(f, "error constructing mmr: {err}"),
             NoteTagAlreadyTracked(tag) => write!(f, "note tag {} is already being tracked", tag),
             NoteInclusionProofError(error) => {
@@ -290,6 +325,8 @@ impl fmt::Display for StoreError {
                 write!(f, "error instantiating transaction script: {err}")
             },
             VaultDataNotFound(root) => write!(f, "account vault data for root {} not found", root),
+            RngSeedNotFound => write!(f, "random coin seed was not found in the store"),
+            #[cfg(feature = "tonic")]
             RpcTypeConversionFailure(err) => write!(f, "failed to convert data: {err}"),
         }
     }
@@ -321,7 +358,11 @@ pub enum NodeRpcClientError {
     DeserializationError(DeserializationError),
     ExpectedFieldMissing(String),
     InvalidAccountReceived(String),
+    InvalidConfig(String),
+    NodeUnavailable(String, String),
     NoteError(NoteError),
+    NotFound(String, String),
+    RecordingError(String),
     RequestError(String, String),
 }
 
@@ -343,9 +384,21 @@ impl fmt::Display for NodeRpcClientError {
             NodeRpcClientError::InvalidAccountReceived(account_error) => {
                 write!(f, "rpc API response contained an invalid account: {account_error}")
             },
+            NodeRpcClientError::InvalidConfig(err) => {
+                write!(f, "invalid rpc configuration: {err}")
+            },
+            NodeRpcClientError::NodeUnavailable(endpoint, err) => {
+                write!(f, "rpc node could not serve the request to {endpoint}: {err}")
+            },
             NodeRpcClientError::NoteError(err) => {
                 write!(f, "rpc API note failed to validate: {err}")
             },
+            NodeRpcClientError::NotFound(endpoint, err) => {
+                write!(f, "rpc request to {endpoint} found no matching data: {err}")
+            },
+            NodeRpcClientError::RecordingError(err) => {
+                write!(f, "failed to record or replay rpc data: {err}")
+            },
             NodeRpcClientError::RequestError(endpoint, err) => {
                 write!(f, "rpc request failed for {endpoint}: {err}")
             },
@@ -371,6 +424,7 @@ impl From<NoteError> for NodeRpcClientError {
     }
 }
 
+#[cfg(feature = "tonic")]
 impl From<ConversionError> for NodeRpcClientError {
     fn from(err: ConversionError) -> Self {
         Self::ConversionFailure(err.to_string())
@@ -472,3 +526,64 @@ impl fmt::Display for InvalidNoteInputsError {
         }
     }
 }
+
+// VERIFICATION ERROR
+// ================================================================================================
+
+/// Error when the data received from the node does not match the commitments the client can
+/// check it against
+#[derive(Debug)]
+pub enum VerificationError {
+    /// The chain MMR peaks obtained after applying the received MMR delta don't hash to the chain
+    /// root of the received block header. Contains the block number, the header's chain root and
+    /// the computed one.
+    ChainRootMismatch(u32, Digest, Digest),
+    /// The inclusion proofs received for the notes with the provided IDs don't open the note
+    /// root of the block they were included in at the notes' ID and metadata.
+    InvalidNoteInclusionProofs(Vec<NoteId>),
+    /// The MMR proof received for the block with the provided number doesn't authenticate its
+    /// header against the client's chain MMR.
+    InvalidBlockMmrProof(u32),
+    /// The proof received from the node for the provided nullifier doesn't open the nullifier
+    /// tree at it.
+    InvalidNullifierProof(Digest),
+    /// The nullifier tree root opened by a proof received from the node doesn't match the
+    /// nullifier root of the block at the client's sync height. Contains the block number, the
+    /// block header's nullifier root and the proof's.
+    NullifierRootMismatch(u32, Digest, Digest),
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerificationError::ChainRootMismatch(block_num, expected, computed) => {
+                write!(
+                    f,
+                    "chain MMR for block {block_num} hashes to {computed} but the block header's chain root is {expected}"
+                )
+            },
+            VerificationError::InvalidNoteInclusionProofs(note_ids) => {
+                write!(
+                    f,
+                    "inclusion proofs of notes {} don't match their block's note root",
+                    note_ids.iter().map(|&id| id.to_hex()).collect::<Vec<_>>().join(", ")
+                )
+            },
+            VerificationError::InvalidBlockMmrProof(block_num) => {
+                write!(f, "MMR proof of block {block_num} doesn't match the client's chain MMR")
+            },
+            VerificationError::InvalidNullifierProof(nullifier) => {
+                write!(f, "proof received for nullifier {nullifier} doesn't open it")
+            },
+            VerificationError::NullifierRootMismatch(block_num, expected, computed) => {
+                write!(
+                    f,
+                    "nullifier proof opens root {computed} but the nullifier root of block {block_num} is {expected}, sync the client to the node's chain tip and try again"
+                )
+            },
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for VerificationError {}
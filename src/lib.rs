@@ -1,6 +1,7 @@
 extern crate alloc;
 
 pub mod client;
+#[cfg(feature = "config")]
 pub mod config;
 pub mod errors;
 pub mod store;
@@ -18,7 +18,7 @@ use miden_objects::{
     assets::{Asset, AssetVault, FungibleAsset, TokenSymbol},
     crypto::{
         dsa::rpo_falcon512::SecretKey,
-        merkle::{Mmr, MmrDelta, NodeIndex, SimpleSmt},
+        merkle::{Mmr, MmrDelta, MmrProof, NodeIndex, SimpleSmt, Smt, SmtProof},
         rand::RpoRandomCoin,
     },
     notes::{
@@ -26,7 +26,7 @@ use miden_objects::{
         NoteScript, NoteTag, NoteType,
     },
     transaction::{InputNote, ProvenTransaction},
-    BlockHeader, Felt, Word, NOTE_TREE_DEPTH,
+    BlockHeader, Digest, Felt, Word, NOTE_TREE_DEPTH, ZERO,
 };
 use rand::Rng;
 use tonic::{Response, Status};
@@ -36,7 +36,7 @@ use crate::{
         rpc::{
             NodeRpcClient, NodeRpcClientEndpoint, NoteDetails, NoteInclusionDetails, StateSyncInfo,
         },
-        sync::FILTER_ID_SHIFT,
+        sync::{note_tree_leaf, FILTER_ID_SHIFT},
         transactions::{
             prepare_word,
             transaction_request::{PaymentTransactionData, TransactionTemplate},
@@ -71,7 +71,12 @@ pub const DEFAULT_ACCOUNT_CODE: &str = "
 pub struct MockRpcApi {
     pub state_sync_requests: BTreeMap<SyncStateRequest, SyncStateResponse>,
     pub genesis_block: BlockHeader,
+    /// Headers of the whole mocked chain, indexed by block number, for the requests that
+    /// authenticate blocks. Only contains the genesis block unless set.
+    pub block_chain: Vec<BlockHeader>,
     pub notes: BTreeMap<NoteId, InputNote>,
+    /// Note tags and nullifier prefixes of the last sync state request received.
+    pub last_sync_state_request: Option<(Vec<NoteTag>, Vec<u16>)>,
 }
 
 impl Default for MockRpcApi {
@@ -80,7 +85,9 @@ impl Default for MockRpcApi {
         Self {
             state_sync_requests,
             genesis_block,
+            block_chain: vec![genesis_block],
             notes,
+            last_sync_state_request: None,
         }
     }
 }
@@ -98,9 +105,11 @@ impl NodeRpcClient for MockRpcApi {
         &mut self,
         block_num: u32,
         _account_ids: &[AccountId],
-        _note_tags: &[NoteTag],
-        _nullifiers_tags: &[u16],
+        note_tags: &[NoteTag],
+        nullifiers_tags: &[u16],
     ) -> Result<StateSyncInfo, NodeRpcClientError> {
+        self.last_sync_state_request = Some((note_tags.to_vec(), nullifiers_tags.to_vec()));
+
         // Match request -> response through block_num
         let response =
             match self.state_sync_requests.iter().find(|(req, _)| req.block_num == block_num) {
@@ -108,9 +117,9 @@ impl NodeRpcClient for MockRpcApi {
                     let response = response.clone();
                     Ok(Response::new(response))
                 },
-                None => Err(NodeRpcClientError::RequestError(
+                None => Err(NodeRpcClientError::NodeUnavailable(
                     NodeRpcClientEndpoint::SyncState.to_string(),
-                    Status::not_found("no response for sync state request").to_string(),
+                    Status::unavailable("no response for sync state request").to_string(),
                 )),
             }?;
 
@@ -118,40 +127,74 @@ impl NodeRpcClient for MockRpcApi {
     }
 
     /// Creates and executes a [GetBlockHeaderByNumberRequest].
-    /// Only used for retrieving genesis block right now so that's the only case we need to cover.
+    /// Only used for retrieving the genesis block and the latest block of the mocked chain, so
+    /// those are the only cases we need to cover.
     async fn get_block_header_by_number(
         &mut self,
         block_num: Option<u32>,
     ) -> Result<BlockHeader, NodeRpcClientError> {
         let request = GetBlockHeaderByNumberRequest { block_num };
 
-        if request.block_num == Some(0) {
-            return Ok(self.genesis_block);
+        match request.block_num {
+            Some(0) => Ok(self.genesis_block),
+            None => Ok(*self.block_chain.last().unwrap_or(&self.genesis_block)),
+            Some(_) => panic!(
+                "get_block_header_by_number is supposed to be only used for the genesis and latest blocks"
+            ),
         }
-        panic!("get_block_header_by_number is supposed to be only used for genesis block")
+    }
+
+    /// Opens the MMR of the mocked block chain with `forest` leaves at `block_num`.
+    async fn get_block_header_with_proof(
+        &mut self,
+        block_num: u32,
+        forest: u32,
+    ) -> Result<(BlockHeader, MmrProof), NodeRpcClientError> {
+        let mut mmr = Mmr::default();
+        for block_header in self.block_chain.iter().take(forest as usize) {
+            mmr.add(block_header.hash());
+        }
+        let proof = mmr.open(block_num as usize, forest as usize).map_err(|err| {
+            NodeRpcClientError::RequestError(
+                NodeRpcClientEndpoint::GetBlockHeaderByNumber.to_string(),
+                err.to_string(),
+            )
+        })?;
+
+        Ok((self.block_chain[block_num as usize], proof))
+    }
+
+    /// Opens a nullifier tree holding the nullifiers of the mocked sync state responses.
+    async fn check_nullifiers(
+        &mut self,
+        nullifiers: &[Digest],
+    ) -> Result<Vec<SmtProof>, NodeRpcClientError> {
+        let nullifier_tree = mock_nullifier_tree(&self.state_sync_requests)?;
+
+        Ok(nullifiers.iter().map(|nullifier| nullifier_tree.open(nullifier)).collect())
     }
 
     async fn get_notes_by_id(
         &mut self,
         note_ids: &[NoteId],
     ) -> Result<Vec<NoteDetails>, NodeRpcClientError> {
-        // assume all off-chain notes for now
         let hit_notes = note_ids.iter().filter_map(|id| self.notes.get(id));
         let mut return_notes = vec![];
         for note in hit_notes {
-            if note.note().metadata().note_type() != NoteType::Public {
-                panic!("this function assumes all notes are offchain for now");
-            }
             let inclusion_details = NoteInclusionDetails::new(
                 note.proof().origin().block_num,
                 note.proof().origin().node_index.value() as u32,
                 note.proof().note_path().clone(),
             );
-            return_notes.push(NoteDetails::OffChain(
-                note.id(),
-                *note.note().metadata(),
-                inclusion_details,
-            ));
+            if note.note().metadata().note_type() == NoteType::Public {
+                return_notes.push(NoteDetails::Public(note.note().clone(), inclusion_details));
+            } else {
+                return_notes.push(NoteDetails::OffChain(
+                    note.id(),
+                    *note.note().metadata(),
+                    inclusion_details,
+                ));
+            }
         }
         Ok(return_notes)
     }
@@ -166,15 +209,40 @@ impl NodeRpcClient for MockRpcApi {
 
     async fn get_account_update(
         &mut self,
-        _account_id: AccountId,
+        account_id: AccountId,
     ) -> Result<Account, NodeRpcClientError> {
-        panic!("shouldn't be used for now")
+        // the mocked chain has no on-chain accounts
+        Err(NodeRpcClientError::NotFound(
+            NodeRpcClientEndpoint::GetAccountDetails.to_string(),
+            format!("account {account_id} not found"),
+        ))
     }
 }
 
 // HELPERS
 // ================================================================================================
 
+/// Returns the nullifier tree of the mocked chain, with the nullifiers of every mocked sync state
+/// response mapped to the number of the block in which they were spent.
+fn mock_nullifier_tree(
+    state_sync_requests: &BTreeMap<SyncStateRequest, SyncStateResponse>,
+) -> Result<Smt, NodeRpcClientError> {
+    let mut nullifier_tree = Smt::new();
+    for response in state_sync_requests.values() {
+        for nullifier_update in response.nullifiers.iter() {
+            let nullifier: Digest = nullifier_update
+                .nullifier
+                .clone()
+                .ok_or(NodeRpcClientError::ExpectedFieldMissing("Nullifier".into()))?
+                .try_into()?;
+            let value = [Felt::from(nullifier_update.block_num), ZERO, ZERO, ZERO];
+            nullifier_tree.insert(nullifier, value);
+        }
+    }
+
+    Ok(nullifier_tree)
+}
+
 /// Generates genesis block header, mock sync state requests and responses
 fn create_mock_sync_state_request_for_account_and_notes(
     account_id: AccountId,
@@ -193,7 +261,8 @@ fn create_mock_sync_state_request_for_account_and_notes(
         .map(|note| (note.note().nullifier().as_elements()[3].as_int() >> FILTER_ID_SHIFT) as u32)
         .collect();
 
-    let account = get_account_with_default_account_code(account_id, Word::default(), None);
+    let consumed_notes_details: Vec<Note> =
+        consumed_notes.iter().map(|note| note.note().clone()).collect();
 
     // This assumes the callee provides either both `tracked_block_headers` and `mmr_delta` are
     // provided or not provided
@@ -201,40 +270,33 @@ fn create_mock_sync_state_request_for_account_and_notes(
         if let Some(tracked_block_headers) = tracked_block_headers {
             (tracked_block_headers, mmr_delta.unwrap())
         } else {
-            let mut mocked_tracked_headers =
-                vec![BlockHeader::mock(8, None, None, &[]), BlockHeader::mock(10, None, None, &[])];
-
-            let all_mocked_block_headers = vec![
-                *genesis_block,
-                BlockHeader::mock(1, None, None, &[]),
-                BlockHeader::mock(2, None, None, &[]),
-                BlockHeader::mock(3, None, None, &[]),
-                BlockHeader::mock(4, None, None, &[]),
-                BlockHeader::mock(5, None, None, &[]),
-                BlockHeader::mock(6, None, None, &[]),
-                BlockHeader::mock(7, None, None, &[]),
-                mocked_tracked_headers[0],
-                BlockHeader::mock(9, None, None, &[]),
-                mocked_tracked_headers[1],
-            ];
-
-            let mut mmr = Mmr::default();
+            let mut mocked_tracked_headers = vec![];
             let mut mocked_mmr_deltas = vec![];
 
-            for (block_num, block_header) in all_mocked_block_headers.iter().enumerate() {
-                if block_num == 8 {
-                    mocked_mmr_deltas.push(mmr.get_delta(1, mmr.forest()).unwrap());
-                }
-                if block_num == 10 {
-                    // Fix mocked block chain root
-                    mocked_tracked_headers[1] = BlockHeader::mock(
-                        10,
-                        Some(mmr.peaks(mmr.forest()).unwrap().hash_peaks()),
-                        None,
-                        &[],
-                    );
-                    mocked_mmr_deltas.push(mmr.get_delta(9, mmr.forest()).unwrap());
-                }
+            let mut mmr = Mmr::default();
+            mmr.add(genesis_block.hash());
+
+            for block_num in 1..=10 {
+                let block_header = match block_num {
+                    8 | 10 => {
+                        // The client will have added the previously tracked block to its MMR
+                        let from_forest = if block_num == 8 { 1 } else { 9 };
+                        mocked_mmr_deltas.push(mmr.get_delta(from_forest, mmr.forest()).unwrap());
+
+                        // Tracked blocks commit to the chain so far and to the note created in them,
+                        // as verified by the client
+                        let chain_root = mmr.peaks(mmr.forest()).unwrap().hash_peaks();
+                        let created_note = output_notes.get(mocked_tracked_headers.len());
+                        let note_root =
+                            mock_block_note_tree(block_num, &consumed_notes_details, created_note)
+                                .root();
+                        let block_header =
+                            BlockHeader::mock(block_num, Some(chain_root), Some(note_root), &[]);
+                        mocked_tracked_headers.push(block_header);
+                        block_header
+                    },
+                    _ => BlockHeader::mock(block_num, None, None, &[]),
+                };
                 mmr.add(block_header.hash());
             }
 
@@ -246,6 +308,16 @@ fn create_mock_sync_state_request_for_account_and_notes(
     let mut created_notes_iter = output_notes.iter();
 
     for (block_order, block_header) in tracked_block_headers.iter().enumerate() {
+        let created_note = created_notes_iter.next().unwrap();
+        let note_tree = mock_block_note_tree(
+            block_header.block_num(),
+            &consumed_notes_details,
+            Some(created_note),
+        );
+        let note_path = note_tree
+            .open(&NodeIndex::new(NOTE_TREE_DEPTH, 0).unwrap().try_into().unwrap())
+            .path;
+
         let request = SyncStateRequest {
             block_num: if block_order == 0 {
                 0
@@ -265,11 +337,11 @@ fn create_mock_sync_state_request_for_account_and_notes(
             accounts: vec![],
             notes: vec![NoteSyncRecord {
                 note_index: 0,
-                note_id: Some(created_notes_iter.next().unwrap().id().into()),
-                sender: Some(account.id().into()),
-                tag: 0u32,
-                note_type: NoteType::OffChain as u32,
-                merkle_path: Some(miden_node_proto::generated::merkle::MerklePath::default()),
+                note_id: Some(created_note.id().into()),
+                sender: Some(created_note.metadata().sender().into()),
+                tag: created_note.metadata().tag().into(),
+                note_type: created_note.metadata().note_type() as u32,
+                merkle_path: Some(note_path.into()),
             }],
             nullifiers: vec![NullifierUpdate {
                 nullifier: Some(consumed_notes.first().unwrap().note().nullifier().inner().into()),
@@ -309,50 +381,48 @@ fn generate_state_sync_mock_requests() -> (
     (genesis_block, state_sync_request_responses, input_notes)
 }
 
+/// Returns the MMR of a mocked chain, the consumed notes recorded in it, the tracked block headers
+/// along with the MMR deltas to reach them, and the chain's genesis block header.
 pub fn mock_full_chain_mmr_and_notes(
     consumed_notes: Vec<Note>,
-) -> (Mmr, Vec<InputNote>, Vec<BlockHeader>, Vec<MmrDelta>) {
-    let mut note_trees = Vec::new();
+) -> (Mmr, Vec<InputNote>, Vec<BlockHeader>, Vec<MmrDelta>, BlockHeader) {
+    let (mmr, recorded_notes, tracked_block_headers, mmr_deltas, block_chain) =
+        mock_full_chain_mmr_and_notes_with_created_notes(consumed_notes, &[]);
+    (mmr, recorded_notes, tracked_block_headers, mmr_deltas, block_chain[0])
+}
 
-    // TODO: Consider how to better represent note authentication data.
-    // we use the index for both the block number and the leaf index in the note tree
-    for (index, note) in consumed_notes.iter().enumerate() {
-        let tree_index = 2 * index;
-        let smt_entries = vec![
-            (tree_index as u64, note.id().into()),
-            ((tree_index + 1) as u64, note.metadata().into()),
-        ];
-        let smt: SimpleSmt<NOTE_TREE_DEPTH> = SimpleSmt::with_leaves(smt_entries).unwrap();
-        note_trees.push(smt);
-    }
+/// Same as [mock_full_chain_mmr_and_notes], but also records `created_notes` in the tracked
+/// blocks, one note per block, in order, and returns the whole chain instead of its genesis block
+/// header.
+fn mock_full_chain_mmr_and_notes_with_created_notes(
+    consumed_notes: Vec<Note>,
+    created_notes: &[Note],
+) -> (Mmr, Vec<InputNote>, Vec<BlockHeader>, Vec<MmrDelta>, Vec<BlockHeader>) {
+    const TRACKED_BLOCKS: [u32; 3] = [2, 4, 6];
 
-    let mut note_tree_iter = note_trees.iter();
     let mut mmr_deltas = Vec::new();
 
-    // create a dummy chain of block headers
-    let block_chain = vec![
-        BlockHeader::mock(0, None, note_tree_iter.next().map(|x| x.root()), &[]),
-        BlockHeader::mock(1, None, note_tree_iter.next().map(|x| x.root()), &[]),
-        BlockHeader::mock(2, None, note_tree_iter.next().map(|x| x.root()), &[]),
-        BlockHeader::mock(3, None, note_tree_iter.next().map(|x| x.root()), &[]),
-        BlockHeader::mock(4, None, note_tree_iter.next().map(|x| x.root()), &[]),
-        BlockHeader::mock(5, None, note_tree_iter.next().map(|x| x.root()), &[]),
-        BlockHeader::mock(6, None, note_tree_iter.next().map(|x| x.root()), &[]),
-    ];
-
-    // instantiate and populate MMR
+    // create a dummy chain of block headers, each committing to the chain before it and to its
+    // notes, and populate the MMR with them
+    let mut block_chain = Vec::new();
+    let mut note_trees = Vec::new();
     let mut mmr = Mmr::default();
-    for (block_num, block_header) in block_chain.iter().enumerate() {
-        if block_num == 2 {
-            mmr_deltas.push(mmr.get_delta(1, mmr.forest()).unwrap());
-        }
-        if block_num == 4 {
-            mmr_deltas.push(mmr.get_delta(3, mmr.forest()).unwrap());
-        }
-        if block_num == 6 {
-            mmr_deltas.push(mmr.get_delta(5, mmr.forest()).unwrap());
+    for block_num in 0..7 {
+        if TRACKED_BLOCKS.contains(&block_num) {
+            mmr_deltas.push(mmr.get_delta(block_num as usize - 1, mmr.forest()).unwrap());
         }
+
+        let created_note = TRACKED_BLOCKS
+            .iter()
+            .position(|tracked_block| *tracked_block == block_num)
+            .and_then(|index| created_notes.get(index));
+        let note_tree = mock_block_note_tree(block_num, &consumed_notes, created_note);
+
+        let chain_root = (block_num != 0).then(|| mmr.peaks(mmr.forest()).unwrap().hash_peaks());
+        let block_header = BlockHeader::mock(block_num, chain_root, Some(note_tree.root()), &[]);
         mmr.add(block_header.hash());
+        block_chain.push(block_header);
+        note_trees.push(note_tree);
     }
 
     // set origin for consumed notes using chain and block data
@@ -376,12 +446,31 @@ pub fn mock_full_chain_mmr_and_notes(
         })
         .collect::<Vec<_>>();
 
-    (
-        mmr,
-        recorded_notes,
-        vec![block_chain[2], block_chain[4], block_chain[6]],
-        mmr_deltas,
-    )
+    let tracked_block_headers: Vec<BlockHeader> = TRACKED_BLOCKS
+        .iter()
+        .map(|block_num| block_chain[*block_num as usize])
+        .collect();
+
+    (mmr, recorded_notes, tracked_block_headers, mmr_deltas, block_chain)
+}
+
+/// Returns the note tree of the mocked block with number `block_num`.
+///
+/// We use the index for both the block number and the leaf index in the note tree of consumed
+/// notes, so the block contains the consumed note with its number (if any) at that leaf. The note
+/// created in the block, if any, is at leaf 0.
+fn mock_block_note_tree(
+    block_num: u32,
+    consumed_notes: &[Note],
+    created_note: Option<&Note>,
+) -> SimpleSmt<NOTE_TREE_DEPTH> {
+    let consumed_note = consumed_notes
+        .get(block_num as usize)
+        .map(|note| (block_num as u64, note_tree_leaf(note.id(), note.metadata()).into()));
+    let created_note =
+        created_note.map(|note| (0, note_tree_leaf(note.id(), note.metadata()).into()));
+
+    SimpleSmt::with_leaves(consumed_note.into_iter().chain(created_note)).unwrap()
 }
 
 /// inserts mock note and account data into the client and returns the last block header of mocked
@@ -406,17 +495,18 @@ pub async fn insert_mock_data(client: &mut MockClient) -> Vec<BlockHeader> {
 
     let assembler = TransactionKernel::assembler();
     let (consumed_notes, created_notes) = mock_notes(&assembler);
-    let (_mmr, consumed_notes, tracked_block_headers, mmr_deltas) =
-        mock_full_chain_mmr_and_notes(consumed_notes);
+    let (_mmr, consumed_notes, mut tracked_block_headers, mmr_deltas, mut block_chain) =
+        mock_full_chain_mmr_and_notes_with_created_notes(consumed_notes, &created_notes);
+    let genesis_block = block_chain[0];
 
     // insert notes into database
     for note in consumed_notes.clone() {
-        client.import_input_note(note.into()).unwrap();
+        client.import_input_note(note.into()).await.unwrap();
     }
 
     // insert notes into database
     for note in created_notes.clone() {
-        client.import_input_note(note.into()).unwrap();
+        client.import_input_note(note.into()).await.unwrap();
     }
 
     // insert account
@@ -425,9 +515,7 @@ pub async fn insert_mock_data(client: &mut MockClient) -> Vec<BlockHeader> {
         .insert_account(&account, Some(account_seed), &AuthInfo::RpoFalcon512(key_pair))
         .unwrap();
 
-    let genesis_block = BlockHeader::mock(0, None, None, &[]);
-
-    client.rpc_api().state_sync_requests = create_mock_sync_state_request_for_account_and_notes(
+    let mut state_sync_requests = create_mock_sync_state_request_for_account_and_notes(
         account.id(),
         &created_notes,
         &consumed_notes,
@@ -436,9 +524,32 @@ pub async fn insert_mock_data(client: &mut MockClient) -> Vec<BlockHeader> {
         Some(tracked_block_headers.clone()),
     );
 
+    // the chain tip commits to the nullifier tree the mocked node proves nullifiers against. It's
+    // not part of the chain MMR of any response, so its hash can change
+    let nullifier_root = mock_nullifier_tree(&state_sync_requests).unwrap().root();
+    let chain_tip = with_nullifier_root(*tracked_block_headers.last().unwrap(), nullifier_root);
+    for response in state_sync_requests.values_mut() {
+        if response.block_header.as_ref().unwrap().block_num == chain_tip.block_num() {
+            response.block_header = Some(NodeBlockHeader::from(chain_tip));
+        }
+    }
+    block_chain[chain_tip.block_num() as usize] = chain_tip;
+    *tracked_block_headers.last_mut().unwrap() = chain_tip;
+
+    client.rpc_api().genesis_block = genesis_block;
+    client.rpc_api().block_chain = block_chain;
+    client.rpc_api().state_sync_requests = state_sync_requests;
+
     tracked_block_headers
 }
 
+/// Returns `block_header` with its nullifier root replaced by `nullifier_root`.
+fn with_nullifier_root(block_header: BlockHeader, nullifier_root: Digest) -> BlockHeader {
+    let mut block_header = NodeBlockHeader::from(block_header);
+    block_header.nullifier_root = Some(nullifier_root.into());
+    block_header.try_into().unwrap()
+}
+
 pub async fn create_mock_transaction(client: &mut MockClient) {
     let key_pair = SecretKey::new();
     let auth_scheme: miden_lib::AuthScheme =
@@ -588,8 +699,10 @@ pub fn mock_notes(assembler: &Assembler) -> (Vec<Note>, Vec<Note>) {
 
     // Created Notes
     const SERIAL_NUM_4: Word = [Felt::new(13), Felt::new(14), Felt::new(15), Felt::new(16)];
+    // the node does not send the `aux` of committed notes, so a non-zero one checks that their
+    // inclusion is verified with the metadata known by the client
     let note_metadata =
-        NoteMetadata::new(sender, NoteType::OffChain, 1u32.into(), Default::default()).unwrap();
+        NoteMetadata::new(sender, NoteType::OffChain, 1u32.into(), Felt::new(1)).unwrap();
     let note_assets = NoteAssets::new(vec![fungible_asset_1]).unwrap();
     let note_recipient =
         NoteRecipient::new(SERIAL_NUM_4, note_script.clone(), NoteInputs::new(vec![]).unwrap());
@@ -1,4 +1,4 @@
-use alloc::collections::BTreeSet;
+use alloc::{collections::BTreeSet, rc::Rc};
 
 use miden_objects::{
     accounts::AccountId,
@@ -17,12 +17,13 @@ use crate::errors::{ClientError, StoreError};
 // ================================================================================================
 
 pub struct ClientDataStore<S: Store> {
-    /// Local database containing information about the accounts managed by this client.
-    pub(crate) store: S,
+    /// Local database containing information about the accounts managed by this client. The
+    /// handle is shared with the [Client](crate::client::Client) that owns the executor.
+    pub(crate) store: Rc<S>,
 }
 
 impl<S: Store> ClientDataStore<S> {
-    pub fn new(store: S) -> Self {
+    pub fn new(store: Rc<S>) -> Self {
         Self { store }
     }
 }
@@ -80,7 +81,8 @@ impl<S: Store> DataStore for ClientDataStore<S> {
             .map(|(header, _has_notes)| *header)
             .collect();
 
-        let partial_mmr = build_partial_mmr_with_paths(&self.store, block_num, &notes_blocks)?;
+        let partial_mmr =
+            build_partial_mmr_with_paths(self.store.as_ref(), block_num, &notes_blocks)?;
         let chain_mmr = ChainMmr::new(partial_mmr, notes_blocks)
             .map_err(|err| DataStoreError::InternalError(err.to_string()))?;
 
@@ -178,7 +180,7 @@ pub fn get_authentication_path_for_blocks<S: Store>(
 /// Calculates the merkle path length for an MMR of a specific forest and a leaf index
 /// `leaf_index` is a 0-indexed leaf number and `forest` is the total amount of leaves
 /// in the MMR at this point.
-fn mmr_merkle_path_len(leaf_index: usize, forest: usize) -> usize {
+pub(crate) fn mmr_merkle_path_len(leaf_index: usize, forest: usize) -> usize {
     let before = forest & leaf_index;
     let after = forest ^ before;
 
@@ -1,13 +1,12 @@
 use alloc::collections::BTreeMap;
 
-use clap::error::Result;
 use miden_objects::{
     accounts::{Account, AccountId, AccountStub},
     crypto::{
         dsa::rpo_falcon512::SecretKey,
         merkle::{InOrderIndex, MmrPeaks},
     },
-    notes::{NoteId, Nullifier},
+    notes::{NoteId, NoteTag, Nullifier},
     transaction::TransactionId,
     BlockHeader, Digest, Felt, Word,
 };
@@ -15,6 +14,7 @@ use miden_tx::utils::{ByteReader, ByteWriter, Deserializable, DeserializationErr
 
 use crate::{
     client::{
+        rpc::NullifierUpdate,
         sync::SyncedNewNotes,
         transactions::{TransactionRecord, TransactionResult},
     },
@@ -22,10 +22,13 @@ use crate::{
 };
 
 pub mod data_store;
+#[cfg(feature = "sqlite")]
 pub mod sqlite_store;
 
 mod note_record;
-pub use note_record::{InputNoteRecord, NoteRecordDetails, NoteStatus, OutputNoteRecord};
+pub use note_record::{
+    ExpectedNoteRecord, InputNoteRecord, NoteRecordDetails, NoteStatus, OutputNoteRecord,
+};
 
 // STORE TRAIT
 // ================================================================================================
@@ -53,7 +56,7 @@ pub trait Store {
     /// - Applying the resulting [AccountDelta](miden_objects::accounts::AccountDelta) and storing the new [Account] state
     /// - Storing new notes as a result of the transaction execution
     /// - Inserting the transaction into the store to track
-    fn apply_transaction(&mut self, tx_result: TransactionResult) -> Result<(), StoreError>;
+    fn apply_transaction(&self, tx_result: TransactionResult) -> Result<(), StoreError>;
 
     // NOTES
     // --------------------------------------------------------------------------------------------
@@ -85,8 +88,26 @@ pub trait Store {
         nullifiers
     }
 
+    /// Returns the nullifiers of the committed output notes whose details are known, which are
+    /// tracked to find out when the notes are consumed
+    ///
+    /// The default implementation of this method uses [Store::get_output_notes].
+    fn get_unspent_output_note_nullifiers(&self) -> Result<Vec<Nullifier>, StoreError> {
+        self.get_output_notes(NoteFilter::Committed)?
+            .iter()
+            .filter_map(|output_note| output_note.details())
+            .map(|details| Ok(Nullifier::from(Digest::try_from(details.nullifier())?)))
+            .collect::<Result<Vec<_>, _>>()
+    }
+
     /// Inserts the provided input note into the database
-    fn insert_input_note(&mut self, note: &InputNoteRecord) -> Result<(), StoreError>;
+    ///
+    /// The expected note with the same ID, if any, is removed.
+    fn insert_input_note(&self, note: &InputNoteRecord) -> Result<(), StoreError>;
+
+    /// Retrieves the private notes that were committed for the client's tags, but whose details
+    /// the client doesn't know yet.
+    fn get_expected_notes(&self) -> Result<Vec<ExpectedNoteRecord>, StoreError>;
 
     // CHAIN DATA
     // --------------------------------------------------------------------------------------------
@@ -146,6 +167,27 @@ pub trait Store {
         has_client_notes: bool,
     ) -> Result<(), StoreError>;
 
+    /// Inserts the header of a block that has relevant notes to the client, alongside peaks
+    /// information at the block's height, and the chain MMR authentication nodes needed to
+    /// authenticate it.
+    ///
+    /// If the block header is already stored, it's only marked as having relevant notes.
+    fn insert_tracked_block_header(
+        &self,
+        block_header: BlockHeader,
+        chain_mmr_peaks: MmrPeaks,
+        authentication_nodes: &[(InOrderIndex, Digest)],
+    ) -> Result<(), StoreError>;
+
+    /// Prunes the block headers and chain MMR nodes that are no longer needed and reclaims the
+    /// space they used.
+    ///
+    /// The genesis block and the block at the sync height, which holds the current chain MMR
+    /// peaks, are always kept. Other blocks are only kept while they contain input or output notes
+    /// that haven't been consumed, or expected notes, along with the MMR nodes needed to
+    /// authenticate them.
+    fn compact(&self) -> Result<CompactionSummary, StoreError>;
+
     // ACCOUNT
     // --------------------------------------------------------------------------------------------
 
@@ -192,7 +234,7 @@ pub trait Store {
 
     /// Inserts an [Account] along with the seed used to create it and its [AuthInfo]
     fn insert_account(
-        &mut self,
+        &self,
         account: &Account,
         account_seed: Option<Word>,
         auth_info: &AuthInfo,
@@ -202,10 +244,17 @@ pub trait Store {
     // --------------------------------------------------------------------------------------------
 
     /// Returns the note tags that the client is interested in.
-    fn get_note_tags(&self) -> Result<Vec<u64>, StoreError>;
+    fn get_note_tags(&self) -> Result<Vec<NoteTag>, StoreError>;
 
     /// Adds a note tag to the list of tags that the client is interested in.
-    fn add_note_tag(&mut self, tag: u64) -> Result<bool, StoreError>;
+    ///
+    /// Returns `false` if the tag was already being tracked.
+    fn add_note_tag(&self, tag: NoteTag) -> Result<bool, StoreError>;
+
+    /// Removes a note tag from the list of tags that the client is interested in.
+    ///
+    /// Returns `false` if the tag was not being tracked.
+    fn remove_note_tag(&self, tag: NoteTag) -> Result<bool, StoreError>;
 
     /// Returns the block number of the last state sync block.
     fn get_sync_height(&self) -> Result<u32, StoreError>;
@@ -214,20 +263,101 @@ pub trait Store {
     ///
     /// - Inserting the new block header to the store alongside new MMR peaks information
     /// - Updating the notes, marking them as `committed` or `consumed` based on incoming
-    ///   inclusion proofs and nullifiers
+    ///   inclusion proofs and nullifiers. Output notes also keep the block they were consumed in
+    /// - Inserting the new public notes, and the expected notes for the private notes whose
+    ///   details are unknown
     /// - Updating transactions in the store, marking as `committed` the ones provided with
     /// `committed_transactions`
+    /// - Marking as `discarded` the transactions provided with `discarded_transactions`, reverting
+    ///   their accounts to the state they had before them and removing the pending notes they
+    ///   created
     /// - Storing new MMR authentication nodes
+    #[allow(clippy::too_many_arguments)]
     fn apply_state_sync(
-        &mut self,
+        &self,
         block_header: BlockHeader,
-        nullifiers: Vec<Digest>,
+        nullifiers: Vec<NullifierUpdate>,
         new_note_details: SyncedNewNotes,
         committed_transactions: &[TransactionId],
+        discarded_transactions: &[TransactionId],
         new_mmr_peaks: MmrPeaks,
         new_authentication_nodes: &[(InOrderIndex, Digest)],
         updated_onchain_accounts: &[Account],
     ) -> Result<(), StoreError>;
+
+    /// Applies the updates found while rescanning blocks that the client already synced past,
+    /// without changing the sync height. An update involves:
+    ///
+    /// - Marking the provided block headers as having relevant notes, inserting them alongside
+    ///   their MMR peaks if they are not stored yet
+    /// - Storing new MMR authentication nodes
+    /// - Updating the notes, marking them as `committed` or `consumed` based on incoming
+    ///   inclusion proofs and nullifiers, and inserting new public and expected notes
+    fn apply_rescan(
+        &self,
+        tracked_block_headers: &[(BlockHeader, MmrPeaks)],
+        nullifiers: Vec<NullifierUpdate>,
+        new_note_details: SyncedNewNotes,
+        new_authentication_nodes: &[(InOrderIndex, Digest)],
+    ) -> Result<(), StoreError>;
+
+    // SEEDS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the seed from which the client's random coins are derived, or `None` if no seed
+    /// has been set yet.
+    fn get_rng_seed(&self) -> Result<Option<Word>, StoreError>;
+
+    /// Returns the amount of random coins derived from the persisted seed so far, which is 0 if
+    /// no seed has been set yet.
+    fn get_rng_counter(&self) -> Result<u64, StoreError>;
+
+    /// Sets the seed from which the client's random coins are derived, resetting the counter of
+    /// derived coins to 0.
+    fn set_rng_seed(&self, seed: Word) -> Result<(), StoreError>;
+
+    /// Returns the random coin seed along with the current value of its counter, and atomically
+    /// increments the persisted counter so that the same value is never returned twice.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [StoreError::RngSeedNotFound] if no seed has been set.
+    fn advance_rng_counter(&self) -> Result<(Word, u64), StoreError>;
+
+    /// Returns the master seed from which account keys and init seeds are derived along with the
+    /// epoch with which the random coin seed was derived from it, or `None` if the client was not
+    /// set up with one.
+    fn get_master_seed(&self) -> Result<Option<(Word, u32)>, StoreError>;
+
+    /// Sets the master seed from which account keys and init seeds are derived, resetting the
+    /// index of the next account to derive to 0, along with the seed of the client's random coins
+    /// derived from it with `rng_epoch`, resetting their counter to 0. Both seeds are written in
+    /// the same transaction.
+    fn set_master_seed(&self, seed: Word, rng_epoch: u32, rng_seed: Word)
+        -> Result<(), StoreError>;
+
+    /// Returns the master seed along with the index of the next account to derive from it.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [StoreError::MasterSeedNotFound] if no master seed has been set.
+    fn get_next_account_index(&self) -> Result<(Word, u32), StoreError>;
+
+    /// Inserts an [Account] derived from the master seed with index `account_index`, along with
+    /// the seed used to create it and its [AuthInfo], and advances the index of the next account
+    /// to derive in the same transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [StoreError::AccountIndexAlreadyUsed] if `account_index` is not the index of the
+    /// next account to derive, in which case nothing is inserted.
+    fn insert_derived_account(
+        &self,
+        account: &Account,
+        account_seed: Option<Word>,
+        auth_info: &AuthInfo,
+        account_index: u32,
+    ) -> Result<(), StoreError>;
 }
 
 // DATABASE AUTH INFO
@@ -299,6 +429,18 @@ pub enum ChainMmrNodeFilter<'a> {
     List(&'a [InOrderIndex]),
 }
 
+// COMPACTION SUMMARY
+// ================================================================================================
+
+/// Contains the number of entries removed from the store by [Store::compact].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CompactionSummary {
+    /// Number of block headers that were pruned.
+    pub pruned_block_headers: usize,
+    /// Number of chain MMR nodes that were pruned.
+    pub pruned_chain_mmr_nodes: usize,
+}
+
 // TRANSACTION FILTERS
 // ================================================================================================
 
@@ -306,7 +448,7 @@ pub enum TransactionFilter {
     /// Return all transactions.
     All,
     /// Filter by transactions that have not yet been committed to the blockchain as per the last
-    /// sync.
+    /// sync, and have not been discarded.
     Uncomitted,
 }
 
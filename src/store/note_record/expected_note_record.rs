@@ -0,0 +1,59 @@
+use miden_objects::{
+    accounts::AccountId,
+    notes::{NoteId, NoteInclusionProof, NoteMetadata, NoteTag},
+};
+
+// EXPECTED NOTE RECORD
+// ================================================================================================
+
+/// Represents a private note that was committed to the chain and matched the client's tags, but
+/// whose details the client doesn't know.
+///
+/// The node only returns the metadata and the inclusion proof of private notes. They are kept so
+/// that, once the note's details are imported as an [InputNoteRecord](super::InputNoteRecord), the
+/// note can be marked as committed right away instead of waiting for another sync.
+///
+/// The node does not send the `aux` field of the metadata of private notes, so it is zero in the
+/// stored metadata and the inclusion proof is only verified once the note's details, along with
+/// its actual metadata, are imported.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExpectedNoteRecord {
+    id: NoteId,
+    metadata: NoteMetadata,
+    inclusion_proof: NoteInclusionProof,
+}
+
+impl ExpectedNoteRecord {
+    pub fn new(
+        id: NoteId,
+        metadata: NoteMetadata,
+        inclusion_proof: NoteInclusionProof,
+    ) -> ExpectedNoteRecord {
+        ExpectedNoteRecord { id, metadata, inclusion_proof }
+    }
+
+    pub fn id(&self) -> NoteId {
+        self.id
+    }
+
+    pub fn metadata(&self) -> &NoteMetadata {
+        &self.metadata
+    }
+
+    pub fn sender(&self) -> AccountId {
+        self.metadata.sender()
+    }
+
+    pub fn tag(&self) -> NoteTag {
+        self.metadata.tag()
+    }
+
+    /// Returns the number of the block the note was included in.
+    pub fn block_num(&self) -> u32 {
+        self.inclusion_proof.origin().block_num
+    }
+
+    pub fn inclusion_proof(&self) -> &NoteInclusionProof {
+        &self.inclusion_proof
+    }
+}
@@ -6,9 +6,11 @@ use miden_objects::{
 };
 use serde::{Deserialize, Serialize};
 
+mod expected_note_record;
 mod input_note_record;
 mod output_note_record;
 
+pub use expected_note_record::ExpectedNoteRecord;
 pub use input_note_record::InputNoteRecord;
 pub use output_note_record::OutputNoteRecord;
 
@@ -26,6 +26,7 @@ pub struct OutputNoteRecord {
     metadata: NoteMetadata,
     recipient: Digest,
     status: NoteStatus,
+    consumed_block_num: Option<u32>,
 }
 
 impl OutputNoteRecord {
@@ -37,6 +38,7 @@ impl OutputNoteRecord {
         metadata: NoteMetadata,
         inclusion_proof: Option<NoteInclusionProof>,
         details: Option<NoteRecordDetails>,
+        consumed_block_num: Option<u32>,
     ) -> OutputNoteRecord {
         OutputNoteRecord {
             id,
@@ -46,6 +48,7 @@ impl OutputNoteRecord {
             metadata,
             inclusion_proof,
             details,
+            consumed_block_num,
         }
     }
 
@@ -76,6 +79,12 @@ impl OutputNoteRecord {
     pub fn details(&self) -> Option<&NoteRecordDetails> {
         self.details.as_ref()
     }
+
+    /// Returns the number of the block in which the note was consumed, if the client saw it
+    /// being consumed.
+    pub fn consumed_block_num(&self) -> Option<u32> {
+        self.consumed_block_num
+    }
 }
 
 impl From<Note> for OutputNoteRecord {
@@ -93,6 +102,7 @@ impl From<Note> for OutputNoteRecord {
                 note.inputs().to_vec(),
                 note.serial_num(),
             )),
+            consumed_block_num: None,
         }
     }
 }
@@ -1,4 +1,3 @@
-use clap::error::Result;
 use miden_lib::transaction::TransactionKernel;
 use miden_objects::{
     accounts::{Account, AccountCode, AccountId, AccountStorage, AccountStub},
@@ -7,9 +6,9 @@ use miden_objects::{
     Digest, Felt, Word,
 };
 use miden_tx::utils::{Deserializable, Serializable};
-use rusqlite::{params, Transaction};
+use rusqlite::{params, Connection, Transaction};
 
-use super::SqliteStore;
+use super::{seeds::advance_account_index_tx, SqliteStore};
 use crate::{errors::StoreError, store::AuthInfo};
 
 // TYPES
@@ -36,7 +35,7 @@ impl SqliteStore {
     pub(super) fn get_account_ids(&self) -> Result<Vec<AccountId>, StoreError> {
         const QUERY: &str = "SELECT DISTINCT id FROM accounts";
 
-        self.db
+        self.db()
             .prepare(QUERY)?
             .query_map([], |row| row.get(0))
             .expect("no binding parameters used in query")
@@ -53,7 +52,7 @@ impl SqliteStore {
             FROM accounts a \
             WHERE a.nonce = (SELECT MAX(b.nonce) FROM accounts b WHERE b.id = a.id)";
 
-        self.db
+        self.db()
             .prepare(QUERY)?
             .query_map([], parse_accounts_columns)
             .expect("no binding parameters used in query")
@@ -70,7 +69,7 @@ impl SqliteStore {
             FROM accounts WHERE id = ? \
             ORDER BY nonce DESC \
             LIMIT 1";
-        self.db
+        self.db()
             .prepare(QUERY)?
             .query_map(params![account_id_int as i64], parse_accounts_columns)?
             .map(|result| Ok(result?).and_then(parse_accounts))
@@ -108,7 +107,7 @@ impl SqliteStore {
     pub(crate) fn get_account_auth(&self, account_id: AccountId) -> Result<AuthInfo, StoreError> {
         let account_id_int: u64 = account_id.into();
         const QUERY: &str = "SELECT account_id, auth_info FROM account_auth WHERE account_id = ?";
-        self.db
+        self.db()
             .prepare(QUERY)?
             .query_map(params![account_id_int as i64], parse_account_auth_columns)?
             .map(|result| Ok(result?).and_then(parse_account_auth))
@@ -124,7 +123,7 @@ impl SqliteStore {
         let root_serialized = root.to_string();
         const QUERY: &str = "SELECT root, procedures, module FROM account_code WHERE root = ?";
 
-        self.db
+        self.db()
             .prepare(QUERY)?
             .query_map(params![root_serialized], parse_account_code_columns)?
             .map(|result| Ok(result?).and_then(parse_account_code))
@@ -137,7 +136,7 @@ impl SqliteStore {
         let root_serialized = &root.to_string();
 
         const QUERY: &str = "SELECT root, slots FROM account_storage WHERE root = ?";
-        self.db
+        self.db()
             .prepare(QUERY)?
             .query_map(params![root_serialized], parse_account_storage_columns)?
             .map(|result| Ok(result?).and_then(parse_account_storage))
@@ -151,7 +150,7 @@ impl SqliteStore {
             serde_json::to_string(&root).map_err(StoreError::InputSerializationError)?;
 
         const QUERY: &str = "SELECT root, assets FROM account_vaults WHERE root = ?";
-        self.db
+        self.db()
             .prepare(QUERY)?
             .query_map(params![vault_root], parse_account_asset_vault_columns)?
             .map(|result| Ok(result?).and_then(parse_account_asset_vault))
@@ -160,18 +159,31 @@ impl SqliteStore {
     }
 
     pub(crate) fn insert_account(
-        &mut self,
+        &self,
+        account: &Account,
+        account_seed: Option<Word>,
+        auth_info: &AuthInfo,
+    ) -> Result<(), StoreError> {
+        let mut db = self.db();
+        let tx = db.transaction()?;
+
+        insert_account_tx(&tx, account, account_seed, auth_info)?;
+
+        Ok(tx.commit()?)
+    }
+
+    pub(crate) fn insert_derived_account(
+        &self,
         account: &Account,
         account_seed: Option<Word>,
         auth_info: &AuthInfo,
+        account_index: u32,
     ) -> Result<(), StoreError> {
-        let tx = self.db.transaction()?;
+        let mut db = self.db();
+        let tx = db.transaction()?;
 
-        insert_account_code(&tx, account.code())?;
-        insert_account_storage(&tx, account.storage())?;
-        insert_account_asset_vault(&tx, account.vault())?;
-        insert_account_record(&tx, account, account_seed)?;
-        insert_account_auth(&tx, account.id(), auth_info)?;
+        advance_account_index_tx(&tx, account_index)?;
+        insert_account_tx(&tx, account, account_seed, auth_info)?;
 
         Ok(tx.commit()?)
     }
@@ -180,6 +192,20 @@ impl SqliteStore {
 // HELPERS
 // ================================================================================================
 
+/// Inserts the account along with its code, storage, vault and auth info.
+fn insert_account_tx(
+    tx: &Transaction<'_>,
+    account: &Account,
+    account_seed: Option<Word>,
+    auth_info: &AuthInfo,
+) -> Result<(), StoreError> {
+    insert_account_code(tx, account.code())?;
+    insert_account_storage(tx, account.storage())?;
+    insert_account_asset_vault(tx, account.vault())?;
+    insert_account_record(tx, account, account_seed)?;
+    insert_account_auth(tx, account.id(), auth_info)
+}
+
 /// Update previously-existing account after a transaction execution
 ///
 /// Because the Client retrieves the account by account ID before applying the delta, we don't
@@ -211,6 +237,34 @@ pub(super) fn insert_account_record(
     Ok(())
 }
 
+/// Returns the nonce of the stored state of the account with ID `account_id` whose hash is
+/// `account_hash`.
+///
+/// # Errors
+///
+/// Returns a [StoreError::AccountDataNotFound] if no stored state of the account has that hash.
+pub(super) fn get_account_nonce_by_hash(
+    conn: &Connection,
+    account_id: AccountId,
+    account_hash: Digest,
+) -> Result<u64, StoreError> {
+    let account_id_int: u64 = account_id.into();
+    const QUERY: &str = "SELECT id, nonce, vault_root, storage_root, code_root, account_seed \
+        FROM accounts WHERE id = ?";
+
+    let account_stubs = conn
+        .prepare(QUERY)?
+        .query_map(params![account_id_int as i64], parse_accounts_columns)?
+        .map(|result| Ok(result?).and_then(parse_accounts))
+        .collect::<Result<Vec<_>, StoreError>>()?;
+
+    account_stubs
+        .into_iter()
+        .find(|(account_stub, _)| account_stub.hash() == account_hash)
+        .map(|(account_stub, _)| account_stub.nonce().as_int())
+        .ok_or(StoreError::AccountDataNotFound(account_id))
+}
+
 /// Inserts an [AccountCode]
 fn insert_account_code(tx: &Transaction<'_>, account_code: &AccountCode) -> Result<(), StoreError> {
     let (code_root, code, module) = serialize_account_code(account_code)?;
@@ -439,11 +493,12 @@ mod tests {
 
     #[test]
     fn test_account_code_insertion_no_duplicates() {
-        let mut store = create_test_store();
+        let store = create_test_store();
         let assembler = miden_lib::transaction::TransactionKernel::assembler();
         let module_ast = ModuleAst::parse(DEFAULT_ACCOUNT_CODE).unwrap();
         let account_code = AccountCode::new(module_ast, &assembler).unwrap();
-        let tx = store.db.transaction().unwrap();
+        let mut db = store.db();
+        let tx = db.transaction().unwrap();
 
         // Table is empty at the beginning
         let mut actual: usize =
@@ -479,11 +534,12 @@ mod tests {
     fn test_auth_info_store() {
         let exp_key_pair = SecretKey::new();
 
-        let mut store = create_test_store();
+        let store = create_test_store();
 
         let account_id = AccountId::try_from(3238098370154045919u64).unwrap();
         {
-            let tx = store.db.transaction().unwrap();
+            let mut db = store.db();
+            let tx = db.transaction().unwrap();
             insert_account_auth(&tx, account_id, &AuthInfo::RpoFalcon512(exp_key_pair.clone()))
                 .unwrap();
             tx.commit().unwrap();
@@ -1,7 +1,6 @@
-use alloc::collections::BTreeMap;
+use alloc::collections::{BTreeMap, BTreeSet};
 use std::num::NonZeroUsize;
 
-use clap::error::Result;
 use miden_objects::{
     crypto::merkle::{InOrderIndex, MmrPeaks},
     BlockHeader, Digest,
@@ -9,7 +8,10 @@ use miden_objects::{
 use rusqlite::{params, OptionalExtension, Transaction};
 
 use super::SqliteStore;
-use crate::{errors::StoreError, store::ChainMmrNodeFilter};
+use crate::{
+    errors::StoreError,
+    store::{data_store::mmr_merkle_path_len, ChainMmrNodeFilter, CompactionSummary},
+};
 
 type SerializedBlockHeaderData = (i64, String, String, bool);
 type SerializedBlockHeaderParts = (u64, String, String, bool);
@@ -52,12 +54,29 @@ impl SqliteStore {
             (block_num, header, chain_mmr_peaks, has_client_notes)
         VALUES (?, ?, ?, ?)";
 
-        self.db
+        self.db()
             .execute(QUERY, params![block_num, header, chain_mmr, has_client_notes])?;
 
         Ok(())
     }
 
+    pub(crate) fn insert_tracked_block_header(
+        &self,
+        block_header: BlockHeader,
+        chain_mmr_peaks: MmrPeaks,
+        authentication_nodes: &[(InOrderIndex, Digest)],
+    ) -> Result<(), StoreError> {
+        let mut db = self.db();
+        let tx = db.transaction()?;
+
+        Self::insert_tracked_block_header_tx(&tx, block_header, chain_mmr_peaks)?;
+        Self::insert_chain_mmr_nodes(&tx, authentication_nodes)?;
+
+        tx.commit()?;
+
+        Ok(())
+    }
+
     pub(crate) fn get_block_headers(
         &self,
         block_numbers: &[u32],
@@ -71,7 +90,7 @@ impl SqliteStore {
             "SELECT block_num, header, chain_mmr_peaks, has_client_notes FROM block_headers WHERE block_num IN ({})",
             formatted_block_numbers_list
         );
-        self.db
+        self.db()
             .prepare(&query)?
             .query_map(params![], parse_block_headers_columns)?
             .map(|result| Ok(result?).and_then(parse_block_header))
@@ -80,7 +99,7 @@ impl SqliteStore {
 
     pub(crate) fn get_tracked_block_headers(&self) -> Result<Vec<BlockHeader>, StoreError> {
         const QUERY: &str = "SELECT block_num, header, chain_mmr_peaks, has_client_notes FROM block_headers WHERE has_client_notes=true";
-        self.db
+        self.db()
             .prepare(QUERY)?
             .query_map(params![], parse_block_headers_columns)?
             .map(|result| Ok(result?).and_then(parse_block_header).map(|(block, _)| block))
@@ -91,7 +110,7 @@ impl SqliteStore {
         &self,
         filter: ChainMmrNodeFilter,
     ) -> Result<BTreeMap<InOrderIndex, Digest>, StoreError> {
-        self.db
+        self.db()
             .prepare(&filter.to_query())?
             .query_map(params![], parse_chain_mmr_nodes_columns)?
             .map(|result| Ok(result?).and_then(parse_chain_mmr_nodes))
@@ -105,7 +124,7 @@ impl SqliteStore {
         const QUERY: &str = "SELECT chain_mmr_peaks FROM block_headers WHERE block_num = ?";
 
         let mmr_peaks = self
-            .db
+            .db()
             .prepare(QUERY)?
             .query_row(params![block_num], |row| {
                 let peaks: String = row.get(0)?;
@@ -120,6 +139,71 @@ impl SqliteStore {
         Ok(MmrPeaks::new(0, vec![])?)
     }
 
+    pub(crate) fn compact(&self) -> Result<CompactionSummary, StoreError> {
+        let forest = self.get_sync_height()?;
+
+        let mut db = self.db();
+        let tx = db.transaction()?;
+
+        // Blocks of the notes that may still be consumed, by the client or by other accounts, and
+        // of the expected notes, which are committed once their details are imported
+        const NOTE_BLOCKS_QUERY: &str = "\
+        SELECT json_extract(inclusion_proof, '$.origin.block_num') FROM input_notes
+        WHERE status != 'Consumed' AND inclusion_proof IS NOT NULL
+        UNION
+        SELECT json_extract(inclusion_proof, '$.origin.block_num') FROM output_notes
+        WHERE status != 'Consumed' AND inclusion_proof IS NOT NULL
+        UNION
+        SELECT json_extract(inclusion_proof, '$.origin.block_num') FROM expected_notes";
+        let mut kept_blocks = tx
+            .prepare(NOTE_BLOCKS_QUERY)?
+            .query_map([], |row| row.get(0))?
+            .map(|result| Ok(result?).map(|block_num: i64| block_num as u32))
+            .collect::<Result<BTreeSet<u32>, StoreError>>()?;
+        kept_blocks.insert(0);
+        kept_blocks.insert(forest);
+
+        // The block at the sync height is not part of the chain MMR yet, so it has no
+        // authentication path
+        let mut kept_nodes = BTreeSet::new();
+        for block_num in kept_blocks.iter().filter(|block_num| **block_num < forest) {
+            let path_depth = mmr_merkle_path_len(*block_num as usize, forest as usize);
+
+            let mut idx = InOrderIndex::from_leaf_pos(*block_num as usize);
+            for _ in 0..path_depth {
+                kept_nodes.insert(u64::from(idx));
+                kept_nodes.insert(u64::from(idx.sibling()));
+                idx = idx.parent();
+            }
+            kept_nodes.insert(u64::from(idx));
+        }
+
+        let pruned_block_headers = tx.execute(
+            &format!(
+                "DELETE FROM block_headers WHERE block_num NOT IN ({})",
+                format_id_list(kept_blocks.iter().map(|block_num| *block_num as u64))
+            ),
+            params![],
+        )?;
+        let pruned_chain_mmr_nodes = tx.execute(
+            &format!(
+                "DELETE FROM chain_mmr_nodes WHERE id NOT IN ({})",
+                format_id_list(kept_nodes.into_iter())
+            ),
+            params![],
+        )?;
+
+        tx.commit()?;
+
+        // Reclaim the space freed by the pruned rows
+        db.execute_batch("VACUUM")?;
+
+        Ok(CompactionSummary {
+            pruned_block_headers,
+            pruned_chain_mmr_nodes,
+        })
+    }
+
     /// Inserts a list of MMR authentication nodes to the Chain MMR nodes table.
     pub(crate) fn insert_chain_mmr_nodes(
         tx: &Transaction<'_>,
@@ -148,6 +232,28 @@ impl SqliteStore {
         tx.execute(QUERY, params![block_num, header, chain_mmr, has_client_notes])?;
         Ok(())
     }
+
+    /// Inserts a block header with relevant notes to the client using a [rusqlite::Transaction],
+    /// or marks it as having relevant notes if it's already stored.
+    pub(crate) fn insert_tracked_block_header_tx(
+        tx: &Transaction<'_>,
+        block_header: BlockHeader,
+        chain_mmr_peaks: MmrPeaks,
+    ) -> Result<(), StoreError> {
+        let chain_mmr_peaks = chain_mmr_peaks.peaks().to_vec();
+        let (block_num, header, chain_mmr, has_client_notes) =
+            serialize_block_header(block_header, chain_mmr_peaks, true)?;
+        const INSERT_IGNORE_QUERY: &str = "\
+        INSERT OR IGNORE INTO block_headers
+            (block_num, header, chain_mmr_peaks, has_client_notes)
+        VALUES (?, ?, ?, ?)";
+        tx.execute(INSERT_IGNORE_QUERY, params![block_num, header, chain_mmr, has_client_notes])?;
+
+        const TRACKED_BLOCK_QUERY: &str =
+            "UPDATE block_headers SET has_client_notes = TRUE WHERE block_num = ?";
+        tx.execute(TRACKED_BLOCK_QUERY, params![block_num])?;
+        Ok(())
+    }
 }
 
 // HELPERS
@@ -160,11 +266,16 @@ fn insert_chain_mmr_node(
     node: Digest,
 ) -> Result<(), StoreError> {
     let (id, node) = serialize_chain_mmr_node(id, node)?;
-    const QUERY: &str = "INSERT INTO chain_mmr_nodes (id, node) VALUES (?, ?)";
+    const QUERY: &str = "INSERT OR IGNORE INTO chain_mmr_nodes (id, node) VALUES (?, ?)";
     tx.execute(QUERY, params![id, node])?;
     Ok(())
 }
 
+/// Formats a list of numeric IDs to be used in an `IN` clause.
+fn format_id_list(ids: impl Iterator<Item = u64>) -> String {
+    ids.map(|id| (id as i64).to_string()).collect::<Vec<String>>().join(",")
+}
+
 fn parse_mmr_peaks(forest: u32, peaks_nodes: String) -> Result<MmrPeaks, StoreError> {
     let mmr_peaks_nodes: Vec<Digest> =
         serde_json::from_str(&peaks_nodes).map_err(StoreError::JsonDataDeserializationError)?;
@@ -238,17 +349,28 @@ fn parse_chain_mmr_nodes(
 
 #[cfg(test)]
 mod test {
-    use miden_objects::{crypto::merkle::MmrPeaks, BlockHeader};
+    use std::num::NonZeroUsize;
+
+    use miden_objects::{
+        crypto::merkle::{InOrderIndex, MmrPeaks},
+        BlockHeader, Digest,
+    };
 
     use crate::store::{
         sqlite_store::{tests::create_test_store, SqliteStore},
-        Store,
+        ChainMmrNodeFilter, CompactionSummary, Store,
     };
 
-    fn insert_dummy_block_headers(store: &mut SqliteStore) -> Vec<BlockHeader> {
+    const NOTE_INCLUSION_PROOF: &str = r#"{
+        "origin": { "block_num": {block_num}, "node_index": 0 },
+        "sub_hash": "", "note_root": "", "note_path": []
+    }"#;
+
+    fn insert_dummy_block_headers(store: &SqliteStore) -> Vec<BlockHeader> {
         let block_headers: Vec<BlockHeader> =
             (0..5).map(|block_num| BlockHeader::mock(block_num, None, None, &[])).collect();
-        let tx = store.db.transaction().unwrap();
+        let mut db = store.db();
+        let tx = db.transaction().unwrap();
         let dummy_peaks = MmrPeaks::new(0, Vec::new()).unwrap();
         (0..5).for_each(|block_num| {
             SqliteStore::insert_block_header_tx(
@@ -266,8 +388,8 @@ mod test {
 
     #[test]
     fn insert_and_get_block_headers_by_number() {
-        let mut store = create_test_store();
-        let block_headers = insert_dummy_block_headers(&mut store);
+        let store = create_test_store();
+        let block_headers = insert_dummy_block_headers(&store);
 
         let block_header = store.get_block_header_by_num(3).unwrap();
         assert_eq!(block_headers[3], block_header.0);
@@ -275,8 +397,8 @@ mod test {
 
     #[test]
     fn insert_and_get_block_headers_by_list() {
-        let mut store = create_test_store();
-        let mock_block_headers = insert_dummy_block_headers(&mut store);
+        let store = create_test_store();
+        let mock_block_headers = insert_dummy_block_headers(&store);
 
         let block_headers: Vec<BlockHeader> = store
             .get_block_headers(&[1, 3])
@@ -286,4 +408,139 @@ mod test {
             .collect();
         assert_eq!(&[mock_block_headers[1], mock_block_headers[3]], &block_headers[..]);
     }
+
+    #[test]
+    fn compact_prunes_unneeded_block_headers_and_nodes() {
+        let store = create_test_store();
+        let block_headers = insert_dummy_block_headers(&store);
+
+        // track all nodes of a chain MMR with 4 leaves and sync to block 4
+        {
+            let mut db = store.db();
+            let tx = db.transaction().unwrap();
+            let nodes: Vec<(InOrderIndex, Digest)> = (1..8)
+                .map(|idx| (InOrderIndex::new(NonZeroUsize::new(idx).unwrap()), Digest::default()))
+                .collect();
+            SqliteStore::insert_chain_mmr_nodes(&tx, &nodes).unwrap();
+            tx.execute("UPDATE state_sync SET block_num = 4", []).unwrap();
+            tx.commit().unwrap();
+        }
+
+        // without unspent notes only the genesis block and its authentication path, and the block
+        // at the sync height are kept
+        let summary = store.compact().unwrap();
+        assert_eq!(
+            summary,
+            CompactionSummary {
+                pruned_block_headers: 3,
+                pruned_chain_mmr_nodes: 2
+            }
+        );
+
+        let remaining_headers: Vec<BlockHeader> = store
+            .get_block_headers(&[0, 1, 2, 3, 4])
+            .unwrap()
+            .into_iter()
+            .map(|(block_header, _has_notes)| block_header)
+            .collect();
+        assert_eq!(&[block_headers[0], block_headers[4]], &remaining_headers[..]);
+
+        let remaining_nodes: Vec<u64> = store
+            .get_chain_mmr_nodes(ChainMmrNodeFilter::All)
+            .unwrap()
+            .into_keys()
+            .map(u64::from)
+            .collect();
+        assert_eq!(remaining_nodes, vec![1, 2, 3, 4, 6]);
+    }
+
+    /// Inserts the block headers of a chain MMR with 4 leaves, tracking all its nodes, and syncs
+    /// to block 4.
+    fn insert_dummy_chain(store: &SqliteStore) {
+        insert_dummy_block_headers(store);
+
+        let mut db = store.db();
+        let tx = db.transaction().unwrap();
+        let nodes: Vec<(InOrderIndex, Digest)> = (1..8)
+            .map(|idx| (InOrderIndex::new(NonZeroUsize::new(idx).unwrap()), Digest::default()))
+            .collect();
+        SqliteStore::insert_chain_mmr_nodes(&tx, &nodes).unwrap();
+        tx.execute("UPDATE state_sync SET block_num = 4", []).unwrap();
+        tx.commit().unwrap();
+    }
+
+    /// Inserts a note with the specified status into `table`, included in block `block_num`.
+    fn insert_dummy_note(
+        store: &SqliteStore,
+        table: &str,
+        note_id: &str,
+        status: &str,
+        block_num: u32,
+    ) {
+        let inclusion_proof = NOTE_INCLUSION_PROOF.replace("{block_num}", &block_num.to_string());
+        let query = match table {
+            "input_notes" => "INSERT INTO input_notes (note_id, recipient, assets, status, inclusion_proof, details) \
+                VALUES (?, '', x'', ?, json(?), '{}')",
+            _ => "INSERT INTO output_notes (note_id, recipient, assets, status, inclusion_proof, metadata) \
+                VALUES (?, '', x'', ?, json(?), '{}')",
+        };
+        store
+            .db()
+            .execute(query, rusqlite::params![note_id, status, inclusion_proof])
+            .unwrap();
+    }
+
+    /// Compacts the store and returns the numbers of the remaining block headers.
+    fn compact_and_get_block_nums(store: &SqliteStore) -> Vec<u32> {
+        store.compact().unwrap();
+
+        store
+            .get_block_headers(&[0, 1, 2, 3, 4])
+            .unwrap()
+            .into_iter()
+            .map(|(block_header, _has_notes)| block_header.block_num())
+            .collect()
+    }
+
+    #[test]
+    fn compact_keeps_blocks_of_unconsumed_input_notes() {
+        let store = create_test_store();
+        insert_dummy_chain(&store);
+
+        // notes stay committed while a transaction consuming them is pending, and can be consumed
+        // again if it is discarded
+        insert_dummy_note(&store, "input_notes", "0x01", "Committed", 1);
+        insert_dummy_note(&store, "input_notes", "0x02", "Pending", 2);
+        insert_dummy_note(&store, "input_notes", "0x03", "Consumed", 3);
+
+        assert_eq!(compact_and_get_block_nums(&store), vec![0, 1, 2, 4]);
+    }
+
+    #[test]
+    fn compact_keeps_blocks_of_unconsumed_output_notes() {
+        let store = create_test_store();
+        insert_dummy_chain(&store);
+
+        insert_dummy_note(&store, "output_notes", "0x01", "Committed", 2);
+        insert_dummy_note(&store, "output_notes", "0x02", "Consumed", 3);
+
+        assert_eq!(compact_and_get_block_nums(&store), vec![0, 2, 4]);
+    }
+
+    #[test]
+    fn compact_keeps_blocks_of_expected_notes() {
+        let store = create_test_store();
+        insert_dummy_chain(&store);
+
+        let inclusion_proof = NOTE_INCLUSION_PROOF.replace("{block_num}", "3");
+        store
+            .db()
+            .execute(
+                "INSERT INTO expected_notes (note_id, metadata, inclusion_proof) VALUES ('0x01', '{}', json(?))",
+                [inclusion_proof],
+            )
+            .unwrap();
+
+        assert_eq!(compact_and_get_block_nums(&store), vec![0, 3, 4]);
+    }
 }
@@ -8,8 +8,16 @@ use crate::errors::StoreError;
 // ================================================================================================
 
 lazy_static! {
-    static ref MIGRATIONS: Migrations<'static> =
-        Migrations::new(vec![M::up(include_str!("store.sql")),]);
+    static ref MIGRATIONS: Migrations<'static> = Migrations::new(vec![
+        M::up(include_str!("store.sql")),
+        M::up(include_str!("migrations/002_add_rng_state.sql")),
+        M::up(include_str!("migrations/003_add_master_seed.sql")),
+        M::up(include_str!("migrations/004_convert_note_tags.sql")),
+        M::up(include_str!("migrations/005_add_discarded_transactions.sql")),
+        M::up(include_str!("migrations/006_add_expected_notes.sql")),
+        M::up(include_str!("migrations/007_add_output_note_consumption.sql")),
+        M::up(include_str!("migrations/008_add_rng_epoch.sql")),
+    ]);
 }
 
 // PUBLIC FUNCTIONS
@@ -18,3 +26,23 @@ lazy_static! {
 pub(crate) fn update_to_latest(conn: &mut Connection) -> Result<(), StoreError> {
     Ok(MIGRATIONS.to_latest(conn)?)
 }
+
+#[cfg(test)]
+mod tests {
+    use rusqlite::Connection;
+
+    use super::{update_to_latest, MIGRATIONS};
+
+    #[test]
+    fn migrate_note_tags_stored_as_u64() {
+        let mut db = Connection::open_in_memory().unwrap();
+        MIGRATIONS.to_version(&mut db, 1).unwrap();
+        db.execute("UPDATE state_sync SET tags = '[3, 4294967296, 1]'", []).unwrap();
+
+        update_to_latest(&mut db).unwrap();
+
+        let tags: String =
+            db.query_row("SELECT tags FROM state_sync", [], |row| row.get(0)).unwrap();
+        assert_eq!(serde_json::from_str::<Vec<u32>>(&tags).unwrap(), vec![3, 1]);
+    }
+}
@@ -0,0 +1,8 @@
+-- Create random coin state table
+CREATE TABLE rng_state (
+    id INTEGER NOT NULL,                    -- always 0, there is a single random coin per store
+    seed TEXT NOT NULL,                     -- hex-encoded seed of the client's random coin
+    counter UNSIGNED BIG INT NOT NULL,      -- amount of random coins derived from the seed so far
+    PRIMARY KEY (id),
+    CONSTRAINT check_single_row CHECK (id = 0)
+);
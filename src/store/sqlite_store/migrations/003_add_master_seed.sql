@@ -0,0 +1,8 @@
+-- Create master seed table
+CREATE TABLE master_seed (
+    id INTEGER NOT NULL,                            -- always 0, there is a single master seed per store
+    seed TEXT NOT NULL,                             -- hex-encoded master seed used to derive account keys
+    next_account_index UNSIGNED BIG INT NOT NULL,   -- index of the next account to derive from the seed
+    PRIMARY KEY (id),
+    CONSTRAINT check_single_row CHECK (id = 0)
+);
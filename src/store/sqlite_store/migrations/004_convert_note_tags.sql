@@ -0,0 +1,9 @@
+-- Note tags used to be stored as a list of u64 values. Keep the ones that can be represented as a
+-- u32 note tag, the others could never be sent to the node
+UPDATE state_sync SET tags = (
+    SELECT json_group_array(value) FROM (
+        SELECT value FROM json_each(state_sync.tags)
+        WHERE value BETWEEN 0 AND 4294967295
+        ORDER BY key
+    )
+);
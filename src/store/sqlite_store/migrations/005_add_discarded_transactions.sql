@@ -0,0 +1,2 @@
+-- Add discarded flag to the transactions table
+ALTER TABLE transactions ADD COLUMN discarded BOOLEAN NOT NULL DEFAULT FALSE; -- True if the transaction can no longer be included in the chain.
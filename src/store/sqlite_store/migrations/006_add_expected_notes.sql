@@ -0,0 +1,7 @@
+-- Create expected notes table
+CREATE TABLE expected_notes (
+    note_id BLOB NOT NULL,          -- the note id
+    metadata JSON NOT NULL,         -- JSON consisting of the note sender, tag, type and aux
+    inclusion_proof JSON NOT NULL,  -- JSON consisting of the note inclusion proof, as in the input_notes table
+    PRIMARY KEY (note_id)
+);
@@ -0,0 +1,2 @@
+-- Add consumption block to the output notes table
+ALTER TABLE output_notes ADD COLUMN consumed_block_num UNSIGNED BIG INT NULL; -- number of the block in which the note was consumed, if known
@@ -0,0 +1,2 @@
+-- Add the epoch of the random coin seed derived from the master seed
+ALTER TABLE master_seed ADD COLUMN rng_epoch UNSIGNED BIG INT NOT NULL DEFAULT 0; -- index with which the random coin seed was derived from the master seed
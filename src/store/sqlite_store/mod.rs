@@ -1,4 +1,5 @@
 use alloc::collections::BTreeMap;
+use core::cell::{RefCell, RefMut};
 
 use miden_objects::{
     accounts::{Account, AccountId, AccountStub},
@@ -10,11 +11,12 @@ use miden_objects::{
 use rusqlite::Connection;
 
 use super::{
-    AuthInfo, ChainMmrNodeFilter, InputNoteRecord, NoteFilter, OutputNoteRecord, Store,
-    TransactionFilter,
+    AuthInfo, ChainMmrNodeFilter, CompactionSummary, ExpectedNoteRecord, InputNoteRecord,
+    NoteFilter, OutputNoteRecord, Store, TransactionFilter,
 };
 use crate::{
     client::{
+        rpc::NullifierUpdate,
         sync::SyncedNewNotes,
         transactions::{TransactionRecord, TransactionResult},
     },
@@ -26,6 +28,7 @@ mod accounts;
 mod chain_data;
 mod migrations;
 mod notes;
+mod seeds;
 mod sync;
 mod transactions;
 
@@ -90,8 +93,12 @@ mod transactions;
 ///     ```
 /// - Thus, if needed you can create a struct representing the json values and use serde_json to
 /// simplify all of the serialization/deserialization logic
+///
+/// The connection is wrapped in a [RefCell] so that a single [SqliteStore] instance can be shared
+/// (through an [Rc](alloc::rc::Rc)) between the [Client](crate::client::Client) and its
+/// [ClientDataStore](crate::store::data_store::ClientDataStore).
 pub struct SqliteStore {
-    pub(crate) db: Connection,
+    pub(crate) db: RefCell<Connection>,
 }
 
 impl SqliteStore {
@@ -103,7 +110,17 @@ impl SqliteStore {
         let mut db = Connection::open(config.database_filepath)?;
         migrations::update_to_latest(&mut db)?;
 
-        Ok(Self { db })
+        Ok(Self { db: RefCell::new(db) })
+    }
+
+    /// Returns a mutable reference to the underlying database connection.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the connection is already borrowed, which would mean a store method is being
+    /// re-entered while another one still holds the connection.
+    pub(crate) fn db(&self) -> RefMut<'_, Connection> {
+        self.db.borrow_mut()
     }
 }
 
@@ -112,24 +129,30 @@ impl SqliteStore {
 // To simplify, all implementations rely on inner SqliteStore functions that map 1:1 by name
 // This way, the actual implementations are grouped by entity types in their own sub-modules
 impl Store for SqliteStore {
-    fn get_note_tags(&self) -> Result<Vec<u64>, StoreError> {
+    fn get_note_tags(&self) -> Result<Vec<NoteTag>, StoreError> {
         self.get_note_tags()
     }
 
-    fn add_note_tag(&mut self, tag: u64) -> Result<bool, StoreError> {
+    fn add_note_tag(&self, tag: NoteTag) -> Result<bool, StoreError> {
         self.add_note_tag(tag)
     }
 
+    fn remove_note_tag(&self, tag: NoteTag) -> Result<bool, StoreError> {
+        self.remove_note_tag(tag)
+    }
+
     fn get_sync_height(&self) -> Result<u32, StoreError> {
         self.get_sync_height()
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn apply_state_sync(
-        &mut self,
+        &self,
         block_header: BlockHeader,
-        nullifiers: Vec<Digest>,
+        nullifiers: Vec<NullifierUpdate>,
         committed_notes: SyncedNewNotes,
         committed_transactions: &[TransactionId],
+        discarded_transactions: &[TransactionId],
         new_mmr_peaks: MmrPeaks,
         new_authentication_nodes: &[(InOrderIndex, Digest)],
         updated_onchain_accounts: &[Account],
@@ -139,12 +162,28 @@ impl Store for SqliteStore {
             nullifiers,
             committed_notes,
             committed_transactions,
+            discarded_transactions,
             new_mmr_peaks,
             new_authentication_nodes,
             updated_onchain_accounts,
         )
     }
 
+    fn apply_rescan(
+        &self,
+        tracked_block_headers: &[(BlockHeader, MmrPeaks)],
+        nullifiers: Vec<NullifierUpdate>,
+        new_note_details: SyncedNewNotes,
+        new_authentication_nodes: &[(InOrderIndex, Digest)],
+    ) -> Result<(), StoreError> {
+        self.apply_rescan(
+            tracked_block_headers,
+            nullifiers,
+            new_note_details,
+            new_authentication_nodes,
+        )
+    }
+
     fn get_transactions(
         &self,
         transaction_filter: TransactionFilter,
@@ -152,7 +191,7 @@ impl Store for SqliteStore {
         self.get_transactions(transaction_filter)
     }
 
-    fn apply_transaction(&mut self, tx_result: TransactionResult) -> Result<(), StoreError> {
+    fn apply_transaction(&self, tx_result: TransactionResult) -> Result<(), StoreError> {
         self.apply_transaction(tx_result)
     }
 
@@ -171,10 +210,14 @@ impl Store for SqliteStore {
         self.get_input_note(note_id)
     }
 
-    fn insert_input_note(&mut self, note: &InputNoteRecord) -> Result<(), StoreError> {
+    fn insert_input_note(&self, note: &InputNoteRecord) -> Result<(), StoreError> {
         self.insert_input_note(note)
     }
 
+    fn get_expected_notes(&self) -> Result<Vec<ExpectedNoteRecord>, StoreError> {
+        self.get_expected_notes()
+    }
+
     fn insert_block_header(
         &self,
         block_header: BlockHeader,
@@ -184,6 +227,19 @@ impl Store for SqliteStore {
         self.insert_block_header(block_header, chain_mmr_peaks, has_client_notes)
     }
 
+    fn insert_tracked_block_header(
+        &self,
+        block_header: BlockHeader,
+        chain_mmr_peaks: MmrPeaks,
+        authentication_nodes: &[(InOrderIndex, Digest)],
+    ) -> Result<(), StoreError> {
+        self.insert_tracked_block_header(block_header, chain_mmr_peaks, authentication_nodes)
+    }
+
+    fn compact(&self) -> Result<CompactionSummary, StoreError> {
+        self.compact()
+    }
+
     fn get_block_headers(
         &self,
         block_numbers: &[u32],
@@ -207,7 +263,7 @@ impl Store for SqliteStore {
     }
 
     fn insert_account(
-        &mut self,
+        &self,
         account: &Account,
         account_seed: Option<Word>,
         auth_info: &AuthInfo,
@@ -237,6 +293,49 @@ impl Store for SqliteStore {
     fn get_account_auth(&self, account_id: AccountId) -> Result<AuthInfo, StoreError> {
         self.get_account_auth(account_id)
     }
+
+    fn get_rng_seed(&self) -> Result<Option<Word>, StoreError> {
+        self.get_rng_seed()
+    }
+
+    fn get_rng_counter(&self) -> Result<u64, StoreError> {
+        self.get_rng_counter()
+    }
+
+    fn set_rng_seed(&self, seed: Word) -> Result<(), StoreError> {
+        self.set_rng_seed(seed)
+    }
+
+    fn advance_rng_counter(&self) -> Result<(Word, u64), StoreError> {
+        self.advance_rng_counter()
+    }
+
+    fn get_master_seed(&self) -> Result<Option<(Word, u32)>, StoreError> {
+        self.get_master_seed()
+    }
+
+    fn set_master_seed(
+        &self,
+        seed: Word,
+        rng_epoch: u32,
+        rng_seed: Word,
+    ) -> Result<(), StoreError> {
+        self.set_master_seed(seed, rng_epoch, rng_seed)
+    }
+
+    fn get_next_account_index(&self) -> Result<(Word, u32), StoreError> {
+        self.get_next_account_index()
+    }
+
+    fn insert_derived_account(
+        &self,
+        account: &Account,
+        account_seed: Option<Word>,
+        auth_info: &AuthInfo,
+        account_index: u32,
+    ) -> Result<(), StoreError> {
+        self.insert_derived_account(account, account_seed, auth_info, account_index)
+    }
 }
 
 // TESTS
@@ -244,14 +343,15 @@ impl Store for SqliteStore {
 
 #[cfg(test)]
 pub mod tests {
-    use std::env::temp_dir;
+    use std::{cell::RefCell, env::temp_dir};
 
+    use miden_objects::{crypto::rand::RpoRandomCoin, Felt};
     use rusqlite::Connection;
     use uuid::Uuid;
 
     use super::{migrations, SqliteStore};
     use crate::{
-        client::get_random_coin,
+        client::{sync::SyncConfig, ClientBuilder},
         config::{ClientConfig, RpcConfig},
         mock::{MockClient, MockRpcApi},
     };
@@ -265,14 +365,18 @@ pub mod tests {
                 .try_into()
                 .unwrap(),
             rpc: RpcConfig::default(),
+            sync: SyncConfig::default(),
         };
 
         let rpc_endpoint = client_config.rpc.endpoint.to_string();
         let store = SqliteStore::new((&client_config).into()).unwrap();
-        let rng = get_random_coin();
-        let executor_store = SqliteStore::new((&client_config).into()).unwrap();
 
-        MockClient::new(MockRpcApi::new(&rpc_endpoint), rng, store, executor_store).unwrap()
+        ClientBuilder::new()
+            .with_rpc_api(MockRpcApi::new(&rpc_endpoint))
+            .with_rng(RpoRandomCoin::new(rand::random::<[u64; 4]>().map(Felt::new)))
+            .with_store(store)
+            .build()
+            .unwrap()
     }
 
     pub(crate) fn create_test_store_path() -> std::path::PathBuf {
@@ -286,6 +390,6 @@ pub mod tests {
         let mut db = Connection::open(temp_file).unwrap();
         migrations::update_to_latest(&mut db).unwrap();
 
-        SqliteStore { db }
+        SqliteStore { db: RefCell::new(db) }
     }
 }
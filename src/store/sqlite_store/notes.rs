@@ -1,6 +1,5 @@
 use std::fmt;
 
-use clap::error::Result;
 use miden_objects::{
     crypto::utils::{Deserializable, Serializable},
     notes::{NoteAssets, NoteId, NoteInclusionProof, NoteMetadata, NoteScript, Nullifier},
@@ -11,7 +10,10 @@ use rusqlite::{named_params, params, Transaction};
 use super::SqliteStore;
 use crate::{
     errors::StoreError,
-    store::{InputNoteRecord, NoteFilter, NoteRecordDetails, NoteStatus, OutputNoteRecord},
+    store::{
+        ExpectedNoteRecord, InputNoteRecord, NoteFilter, NoteRecordDetails, NoteStatus,
+        OutputNoteRecord,
+    },
 };
 
 fn insert_note_query(table_name: NoteTable) -> String {
@@ -47,10 +49,21 @@ type SerializedOutputNoteData = (
     Option<String>,
 );
 
+type SerializedExpectedNoteData = (String, String, String);
+type SerializedExpectedNoteParts = (String, String, String);
+
 type SerializedInputNoteParts =
     (Vec<u8>, String, String, String, Option<String>, Option<String>, Vec<u8>);
-type SerializedOutputNoteParts =
-    (Vec<u8>, Option<String>, String, String, String, Option<String>, Option<Vec<u8>>);
+type SerializedOutputNoteParts = (
+    Vec<u8>,
+    Option<String>,
+    String,
+    String,
+    String,
+    Option<String>,
+    Option<Vec<u8>>,
+    Option<u32>,
+);
 
 // NOTE TABLE
 // ================================================================================================
@@ -76,6 +89,11 @@ impl fmt::Display for NoteTable {
 impl NoteFilter {
     /// Returns a [String] containing the query for this Filter
     fn to_query(&self, notes_table: NoteTable) -> String {
+        // Only output notes keep the block in which they were consumed
+        let consumed_block_num = match notes_table {
+            NoteTable::InputNotes => "",
+            NoteTable::OutputNotes => ",\n                    note.consumed_block_num",
+        };
         let base = format!(
             "SELECT 
                     note.assets, 
@@ -84,7 +102,7 @@ impl NoteFilter {
                     note.status,
                     note.metadata,
                     note.inclusion_proof,
-                    script.serialized_note_script
+                    script.serialized_note_script{consumed_block_num}
                     from {notes_table} AS note 
                     LEFT OUTER JOIN notes_scripts AS script
                         ON note.details IS NOT NULL AND 
@@ -108,7 +126,7 @@ impl SqliteStore {
         &self,
         filter: NoteFilter,
     ) -> Result<Vec<InputNoteRecord>, StoreError> {
-        self.db
+        self.db()
             .prepare(&filter.to_query(NoteTable::InputNotes))?
             .query_map([], parse_input_note_columns)
             .expect("no binding parameters used in query")
@@ -121,7 +139,7 @@ impl SqliteStore {
         &self,
         filter: NoteFilter,
     ) -> Result<Vec<OutputNoteRecord>, StoreError> {
-        self.db
+        self.db()
             .prepare(&filter.to_query(NoteTable::OutputNotes))?
             .query_map([], parse_output_note_columns)
             .expect("no binding parameters used in query")
@@ -146,7 +164,7 @@ impl SqliteStore {
                             json_extract(note.details, '$.script_hash') = script.script_hash
                         WHERE note.note_id = ?";
 
-        self.db
+        self.db()
             .prepare(QUERY)?
             .query_map(params![query_id.to_string()], parse_input_note_columns)?
             .map(|result| Ok(result?).and_then(parse_input_note))
@@ -154,19 +172,31 @@ impl SqliteStore {
             .ok_or(StoreError::InputNoteNotFound(note_id))?
     }
 
-    pub(crate) fn insert_input_note(&mut self, note: &InputNoteRecord) -> Result<(), StoreError> {
-        let tx = self.db.transaction()?;
+    pub(crate) fn insert_input_note(&self, note: &InputNoteRecord) -> Result<(), StoreError> {
+        let mut db = self.db();
+        let tx = db.transaction()?;
 
         insert_input_note_tx(&tx, note)?;
 
         Ok(tx.commit()?)
     }
 
+    pub(crate) fn get_expected_notes(&self) -> Result<Vec<ExpectedNoteRecord>, StoreError> {
+        const QUERY: &str = "SELECT note_id, metadata, inclusion_proof FROM expected_notes";
+
+        self.db()
+            .prepare(QUERY)?
+            .query_map([], parse_expected_note_columns)
+            .expect("no binding parameters used in query")
+            .map(|result| Ok(result?).and_then(parse_expected_note))
+            .collect::<Result<Vec<ExpectedNoteRecord>, _>>()
+    }
+
     /// Returns the nullifiers of all unspent input notes
     pub fn get_unspent_input_note_nullifiers(&self) -> Result<Vec<Nullifier>, StoreError> {
         const QUERY: &str = "SELECT json_extract(details, '$.nullifier') FROM input_notes WHERE status = 'Committed'";
 
-        self.db
+        self.db()
             .prepare(QUERY)?
             .query_map([], |row| row.get(0))
             .expect("no binding parameters used in query")
@@ -219,6 +249,26 @@ pub(super) fn insert_input_note_tx(
     const QUERY: &str =
         "INSERT OR IGNORE INTO notes_scripts (script_hash, serialized_note_script) VALUES (?, ?)";
     tx.execute(QUERY, params![note_script_hash, serialized_note_script,])
+        .map_err(|err| StoreError::QueryError(err.to_string()))?;
+
+    // The note's details are now known, so it's no longer expected
+    const DELETE_EXPECTED_QUERY: &str = "DELETE FROM expected_notes WHERE note_id = ?";
+    tx.execute(DELETE_EXPECTED_QUERY, params![note_id])
+        .map_err(|err| StoreError::QueryError(err.to_string()))
+        .map(|_| ())
+}
+
+/// Inserts the provided expected note into the database, unless it's already there
+pub(super) fn insert_expected_note_tx(
+    tx: &Transaction<'_>,
+    note: &ExpectedNoteRecord,
+) -> Result<(), StoreError> {
+    let (note_id, metadata, inclusion_proof) = serialize_expected_note(note)?;
+
+    const QUERY: &str = "\
+    INSERT OR IGNORE INTO expected_notes (note_id, metadata, inclusion_proof)
+    VALUES (?, json(?), json(?))";
+    tx.execute(QUERY, params![note_id, metadata, inclusion_proof])
         .map_err(|err| StoreError::QueryError(err.to_string()))
         .map(|_| ())
 }
@@ -421,6 +471,7 @@ fn parse_output_note_columns(
     let metadata: String = row.get(4)?;
     let inclusion_proof: Option<String> = row.get(5)?;
     let serialized_note_script: Option<Vec<u8>> = row.get(6)?;
+    let consumed_block_num: Option<u32> = row.get(7)?;
 
     Ok((
         assets,
@@ -430,6 +481,7 @@ fn parse_output_note_columns(
         metadata,
         inclusion_proof,
         serialized_note_script,
+        consumed_block_num,
     ))
 }
 
@@ -445,6 +497,7 @@ fn parse_output_note(
         note_metadata,
         note_inclusion_proof,
         serialized_note_script,
+        consumed_block_num,
     ) = serialized_output_note_parts;
 
     let note_details: Option<NoteRecordDetails> = if let Some(details_as_json_str) = note_details {
@@ -495,6 +548,7 @@ fn parse_output_note(
         note_metadata,
         inclusion_proof,
         note_details,
+        consumed_block_num,
     ))
 }
 
@@ -559,3 +613,42 @@ pub(crate) fn serialize_output_note(
         inclusion_proof,
     ))
 }
+
+/// Serialize the provided expected note into database compatible types.
+fn serialize_expected_note(
+    note: &ExpectedNoteRecord,
+) -> Result<SerializedExpectedNoteData, StoreError> {
+    let note_id = note.id().inner().to_string();
+    let metadata =
+        serde_json::to_string(note.metadata()).map_err(StoreError::InputSerializationError)?;
+    let inclusion_proof = serde_json::to_string(note.inclusion_proof())
+        .map_err(StoreError::InputSerializationError)?;
+
+    Ok((note_id, metadata, inclusion_proof))
+}
+
+/// Parse expected note columns from the provided row into native types.
+fn parse_expected_note_columns(
+    row: &rusqlite::Row<'_>,
+) -> Result<SerializedExpectedNoteParts, rusqlite::Error> {
+    let note_id: String = row.get(0)?;
+    let metadata: String = row.get(1)?;
+    let inclusion_proof: String = row.get(2)?;
+
+    Ok((note_id, metadata, inclusion_proof))
+}
+
+/// Parse an expected note from the provided parts.
+fn parse_expected_note(
+    serialized_expected_note_parts: SerializedExpectedNoteParts,
+) -> Result<ExpectedNoteRecord, StoreError> {
+    let (note_id, metadata, inclusion_proof) = serialized_expected_note_parts;
+
+    let note_id = Digest::try_from(note_id).map_err(StoreError::HexParseError)?.into();
+    let metadata: NoteMetadata =
+        serde_json::from_str(&metadata).map_err(StoreError::JsonDataDeserializationError)?;
+    let inclusion_proof: NoteInclusionProof =
+        serde_json::from_str(&inclusion_proof).map_err(StoreError::JsonDataDeserializationError)?;
+
+    Ok(ExpectedNoteRecord::new(note_id, metadata, inclusion_proof))
+}
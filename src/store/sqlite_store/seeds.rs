@@ -0,0 +1,116 @@
+use miden_objects::{Digest, Word};
+use rusqlite::{named_params, params, OptionalExtension, Transaction};
+
+use super::SqliteStore;
+use crate::errors::StoreError;
+
+impl SqliteStore {
+    pub(crate) fn get_rng_seed(&self) -> Result<Option<Word>, StoreError> {
+        const QUERY: &str = "SELECT seed FROM rng_state WHERE id = 0";
+
+        self.db()
+            .query_row(QUERY, [], |row| row.get::<_, String>(0))
+            .optional()?
+            .map(|seed| Ok(Digest::try_from(seed)?.into()))
+            .transpose()
+    }
+
+    pub(crate) fn get_rng_counter(&self) -> Result<u64, StoreError> {
+        const QUERY: &str = "SELECT counter FROM rng_state WHERE id = 0";
+
+        let counter: Option<i64> = self.db().query_row(QUERY, [], |row| row.get(0)).optional()?;
+
+        Ok(counter.unwrap_or(0) as u64)
+    }
+
+    pub(crate) fn set_rng_seed(&self, seed: Word) -> Result<(), StoreError> {
+        const QUERY: &str =
+            "INSERT OR REPLACE INTO rng_state (id, seed, counter) VALUES (0, :seed, 0)";
+
+        self.db()
+            .execute(QUERY, named_params! { ":seed": Digest::from(seed).to_hex() })?;
+
+        Ok(())
+    }
+
+    pub(crate) fn advance_rng_counter(&self) -> Result<(Word, u64), StoreError> {
+        const SELECT_QUERY: &str = "SELECT seed, counter FROM rng_state WHERE id = 0";
+        const UPDATE_QUERY: &str = "UPDATE rng_state SET counter = counter + 1 WHERE id = 0";
+
+        let mut db = self.db();
+        let tx = db.transaction()?;
+
+        let (seed, counter): (String, i64) = tx
+            .query_row(SELECT_QUERY, [], |row| Ok((row.get(0)?, row.get(1)?)))
+            .optional()?
+            .ok_or(StoreError::RngSeedNotFound)?;
+        tx.execute(UPDATE_QUERY, [])?;
+        tx.commit()?;
+
+        Ok((Digest::try_from(seed)?.into(), counter as u64))
+    }
+
+    pub(crate) fn get_master_seed(&self) -> Result<Option<(Word, u32)>, StoreError> {
+        const QUERY: &str = "SELECT seed, rng_epoch FROM master_seed WHERE id = 0";
+
+        self.db()
+            .query_row(QUERY, [], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)))
+            .optional()?
+            .map(|(seed, rng_epoch)| Ok((Digest::try_from(seed)?.into(), rng_epoch as u32)))
+            .transpose()
+    }
+
+    pub(crate) fn set_master_seed(
+        &self,
+        seed: Word,
+        rng_epoch: u32,
+        rng_seed: Word,
+    ) -> Result<(), StoreError> {
+        const MASTER_SEED_QUERY: &str = "INSERT OR REPLACE INTO master_seed \
+            (id, seed, next_account_index, rng_epoch) VALUES (0, :seed, 0, :rng_epoch)";
+        const RNG_SEED_QUERY: &str =
+            "INSERT OR REPLACE INTO rng_state (id, seed, counter) VALUES (0, :seed, 0)";
+
+        let mut db = self.db();
+        let tx = db.transaction()?;
+
+        tx.execute(
+            MASTER_SEED_QUERY,
+            named_params! { ":seed": Digest::from(seed).to_hex(), ":rng_epoch": rng_epoch },
+        )?;
+        tx.execute(RNG_SEED_QUERY, named_params! { ":seed": Digest::from(rng_seed).to_hex() })?;
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    pub(crate) fn get_next_account_index(&self) -> Result<(Word, u32), StoreError> {
+        const QUERY: &str = "SELECT seed, next_account_index FROM master_seed WHERE id = 0";
+
+        let (seed, index): (String, i64) = self
+            .db()
+            .query_row(QUERY, [], |row| Ok((row.get(0)?, row.get(1)?)))
+            .optional()?
+            .ok_or(StoreError::MasterSeedNotFound)?;
+
+        Ok((Digest::try_from(seed)?.into(), index as u32))
+    }
+}
+
+/// Advances the index of the next account to derive from the master seed past `account_index`.
+///
+/// Returns a [StoreError::AccountIndexAlreadyUsed] if `account_index` is not the index of the
+/// next account to derive.
+pub(super) fn advance_account_index_tx(
+    tx: &Transaction<'_>,
+    account_index: u32,
+) -> Result<(), StoreError> {
+    const QUERY: &str = "UPDATE master_seed SET next_account_index = next_account_index + 1 \
+        WHERE id = 0 AND next_account_index = ?";
+
+    if tx.execute(QUERY, params![account_index])? == 0 {
+        return Err(StoreError::AccountIndexAlreadyUsed(account_index));
+    }
+
+    Ok(())
+}
@@ -1,24 +1,56 @@
 use miden_objects::{
     accounts::Account,
     crypto::merkle::{InOrderIndex, MmrPeaks},
-    notes::NoteInclusionProof,
+    notes::{NoteInclusionProof, NoteTag},
     transaction::TransactionId,
     BlockHeader, Digest,
 };
-use rusqlite::{named_params, params};
+use rusqlite::{named_params, params, Transaction};
 
 use super::SqliteStore;
 use crate::{
-    client::sync::SyncedNewNotes,
+    client::{rpc::NullifierUpdate, sync::SyncedNewNotes},
     errors::StoreError,
-    store::sqlite_store::{accounts::update_account, notes::insert_input_note_tx},
+    store::sqlite_store::{
+        accounts::update_account,
+        notes::{insert_expected_note_tx, insert_input_note_tx},
+    },
 };
 
 impl SqliteStore {
-    pub(crate) fn get_note_tags(&self) -> Result<Vec<u64>, StoreError> {
+    pub(crate) fn get_note_tags(&self) -> Result<Vec<NoteTag>, StoreError> {
+        Ok(self.get_raw_note_tags()?.into_iter().map(NoteTag::from).collect())
+    }
+
+    pub(super) fn add_note_tag(&self, tag: NoteTag) -> Result<bool, StoreError> {
+        let mut tags = self.get_raw_note_tags()?;
+        let tag = u32::from(tag);
+        if tags.contains(&tag) {
+            return Ok(false);
+        }
+        tags.push(tag);
+        self.set_raw_note_tags(&tags)?;
+
+        Ok(true)
+    }
+
+    pub(super) fn remove_note_tag(&self, tag: NoteTag) -> Result<bool, StoreError> {
+        let mut tags = self.get_raw_note_tags()?;
+        let tag = u32::from(tag);
+        let initial_len = tags.len();
+        tags.retain(|tracked_tag| *tracked_tag != tag);
+        if tags.len() == initial_len {
+            return Ok(false);
+        }
+        self.set_raw_note_tags(&tags)?;
+
+        Ok(true)
+    }
+
+    fn get_raw_note_tags(&self) -> Result<Vec<u32>, StoreError> {
         const QUERY: &str = "SELECT tags FROM state_sync";
 
-        self.db
+        self.db()
             .prepare(QUERY)?
             .query_map([], |row| row.get(0))
             .expect("no binding parameters used in query")
@@ -33,24 +65,19 @@ impl SqliteStore {
             .expect("state sync tags exist")
     }
 
-    pub(super) fn add_note_tag(&mut self, tag: u64) -> Result<bool, StoreError> {
-        let mut tags = self.get_note_tags()?;
-        if tags.contains(&tag) {
-            return Ok(false);
-        }
-        tags.push(tag);
-        let tags = serde_json::to_string(&tags).map_err(StoreError::InputSerializationError)?;
+    fn set_raw_note_tags(&self, tags: &[u32]) -> Result<(), StoreError> {
+        let tags = serde_json::to_string(tags).map_err(StoreError::InputSerializationError)?;
 
         const QUERY: &str = "UPDATE state_sync SET tags = ?";
-        self.db.execute(QUERY, params![tags])?;
+        self.db().execute(QUERY, params![tags])?;
 
-        Ok(true)
+        Ok(())
     }
 
     pub(super) fn get_sync_height(&self) -> Result<u32, StoreError> {
         const QUERY: &str = "SELECT block_num FROM state_sync";
 
-        self.db
+        self.db()
             .prepare(QUERY)?
             .query_map([], |row| row.get(0))
             .expect("no binding parameters used in query")
@@ -59,33 +86,27 @@ impl SqliteStore {
             .expect("state sync block number exists")
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub(super) fn apply_state_sync(
-        &mut self,
+        &self,
         block_header: BlockHeader,
-        nullifiers: Vec<Digest>,
+        nullifiers: Vec<NullifierUpdate>,
         committed_notes: SyncedNewNotes,
         committed_transactions: &[TransactionId],
+        discarded_transactions: &[TransactionId],
         new_mmr_peaks: MmrPeaks,
         new_authentication_nodes: &[(InOrderIndex, Digest)],
         updated_onchain_accounts: &[Account],
     ) -> Result<(), StoreError> {
-        let tx = self.db.transaction()?;
+        let mut db = self.db();
+        let tx = db.transaction()?;
 
         // Update state sync block number
         const BLOCK_NUMBER_QUERY: &str = "UPDATE state_sync SET block_num = ?";
         tx.execute(BLOCK_NUMBER_QUERY, params![block_header.block_num()])?;
 
         // Update spent notes
-        for nullifier in nullifiers.iter() {
-            const SPENT_INPUT_NOTE_QUERY: &str =
-                "UPDATE input_notes SET status = 'Consumed' WHERE json_extract(details, '$.nullifier') = ?";
-            let nullifier = nullifier.to_hex();
-            tx.execute(SPENT_INPUT_NOTE_QUERY, params![nullifier])?;
-
-            const SPENT_OUTPUT_NOTE_QUERY: &str =
-                "UPDATE output_notes SET status = 'Consumed' WHERE json_extract(details, '$.nullifier') = ?";
-            tx.execute(SPENT_OUTPUT_NOTE_QUERY, params![nullifier])?;
-        }
+        Self::mark_nullifiers_as_consumed(&tx, &nullifiers)?;
 
         // TODO: Due to the fact that notes are returned based on fuzzy matching of tags,
         // this process of marking if the header has notes needs to be revisited
@@ -95,7 +116,85 @@ impl SqliteStore {
         // Insert new authentication nodes (inner nodes of the PartialMmr)
         Self::insert_chain_mmr_nodes(&tx, new_authentication_nodes)?;
 
-        // Update tracked notes
+        // Update tracked notes and commit new public notes
+        Self::apply_committed_notes(&tx, &committed_notes)?;
+
+        // Mark transactions as committed
+        Self::mark_transactions_as_committed(
+            &tx,
+            block_header.block_num(),
+            committed_transactions,
+        )?;
+
+        // Mark transactions as discarded and roll back their changes. This needs to happen before
+        // updating on-chain accounts, as their new state replaces the discarded one
+        Self::mark_transactions_as_discarded(&tx, discarded_transactions)?;
+
+        // Update onchain accounts on the db that have been updated onchain
+        for account in updated_onchain_accounts {
+            update_account(&tx, account)?;
+        }
+
+        // Commit the updates
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    pub(super) fn apply_rescan(
+        &self,
+        tracked_block_headers: &[(BlockHeader, MmrPeaks)],
+        nullifiers: Vec<NullifierUpdate>,
+        committed_notes: SyncedNewNotes,
+        new_authentication_nodes: &[(InOrderIndex, Digest)],
+    ) -> Result<(), StoreError> {
+        let mut db = self.db();
+        let tx = db.transaction()?;
+
+        // Headers of blocks that were synced past are already stored, so only mark them as
+        // having relevant notes
+        for (block_header, mmr_peaks) in tracked_block_headers {
+            Self::insert_tracked_block_header_tx(&tx, *block_header, mmr_peaks.clone())?;
+        }
+
+        Self::insert_chain_mmr_nodes(&tx, new_authentication_nodes)?;
+
+        // Notes are committed before applying the nullifiers, as notes committed during the
+        // rescan may have been consumed afterwards
+        Self::apply_committed_notes(&tx, &committed_notes)?;
+        Self::mark_nullifiers_as_consumed(&tx, &nullifiers)?;
+
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// Marks the input and output notes with the provided nullifiers as consumed, keeping the
+    /// block in which output notes were consumed.
+    fn mark_nullifiers_as_consumed(
+        tx: &Transaction<'_>,
+        nullifiers: &[NullifierUpdate],
+    ) -> Result<(), StoreError> {
+        for NullifierUpdate { nullifier, block_num } in nullifiers.iter() {
+            const SPENT_INPUT_NOTE_QUERY: &str =
+                "UPDATE input_notes SET status = 'Consumed' WHERE json_extract(details, '$.nullifier') = ?";
+            let nullifier = nullifier.to_hex();
+            tx.execute(SPENT_INPUT_NOTE_QUERY, params![nullifier])?;
+
+            const SPENT_OUTPUT_NOTE_QUERY: &str =
+                "UPDATE output_notes SET status = 'Consumed', consumed_block_num = ? WHERE json_extract(details, '$.nullifier') = ?";
+            tx.execute(SPENT_OUTPUT_NOTE_QUERY, params![block_num, nullifier])?;
+        }
+
+        Ok(())
+    }
+
+    /// Stores the inclusion proofs of the tracked notes that were committed, and inserts the new
+    /// public and expected notes.
+    fn apply_committed_notes(
+        tx: &Transaction<'_>,
+        committed_notes: &SyncedNewNotes,
+    ) -> Result<(), StoreError> {
         for (note_id, inclusion_proof) in committed_notes.new_inclusion_proofs().iter() {
             let block_num = inclusion_proof.origin().block_num;
             let sub_hash = inclusion_proof.sub_hash();
@@ -137,24 +236,14 @@ impl SqliteStore {
 
         // Commit new public notes
         for note in committed_notes.new_public_notes() {
-            insert_input_note_tx(&tx, &note.clone().into())?;
+            insert_input_note_tx(tx, &note.clone().into())?;
         }
 
-        // Mark transactions as committed
-        Self::mark_transactions_as_committed(
-            &tx,
-            block_header.block_num(),
-            committed_transactions,
-        )?;
-
-        // Update onchain accounts on the db that have been updated onchain
-        for account in updated_onchain_accounts {
-            update_account(&tx, account)?;
+        // Keep the metadata and proofs of the private notes whose details are unknown
+        for note in committed_notes.new_expected_notes() {
+            insert_expected_note_tx(tx, note)?;
         }
 
-        // Commit the updates
-        tx.commit()?;
-
         Ok(())
     }
 }
@@ -11,7 +11,7 @@ use rusqlite::{params, Transaction};
 use tracing::info;
 
 use super::{
-    accounts::update_account,
+    accounts::{get_account_nonce_by_hash, update_account},
     notes::{insert_input_note_tx, insert_output_note_tx},
     SqliteStore,
 };
@@ -23,8 +23,8 @@ use crate::{
 
 pub(crate) const INSERT_TRANSACTION_QUERY: &str =
     "INSERT INTO transactions (id, account_id, init_account_state, final_account_state, \
-    input_notes, output_notes, script_hash, script_inputs, block_num, commit_height) \
-    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)";
+    input_notes, output_notes, script_hash, script_inputs, block_num, commit_height, discarded) \
+    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)";
 
 pub(crate) const INSERT_TRANSACTION_SCRIPT_QUERY: &str =
     "INSERT OR IGNORE INTO transaction_scripts (script_hash, program) \
@@ -37,11 +37,13 @@ impl TransactionFilter {
     /// Returns a [String] containing the query for this Filter
     pub fn to_query(&self) -> String {
         const QUERY: &str = "SELECT tx.id, tx.account_id, tx.init_account_state, tx.final_account_state, \
-            tx.input_notes, tx.output_notes, tx.script_hash, script.program, tx.script_inputs, tx.block_num, tx.commit_height \
+            tx.input_notes, tx.output_notes, tx.script_hash, script.program, tx.script_inputs, tx.block_num, tx.commit_height, tx.discarded \
             FROM transactions AS tx LEFT JOIN transaction_scripts AS script ON tx.script_hash = script.script_hash";
         match self {
             TransactionFilter::All => QUERY.to_string(),
-            TransactionFilter::Uncomitted => format!("{QUERY} WHERE tx.commit_height IS NULL"),
+            TransactionFilter::Uncomitted => {
+                format!("{QUERY} WHERE tx.commit_height IS NULL AND NOT tx.discarded")
+            },
         }
     }
 }
@@ -61,6 +63,7 @@ type SerializedTransactionData = (
     Option<String>,
     u32,
     Option<u32>,
+    bool,
 );
 
 impl SqliteStore {
@@ -69,7 +72,7 @@ impl SqliteStore {
         &self,
         filter: TransactionFilter,
     ) -> Result<Vec<TransactionRecord>, StoreError> {
-        self.db
+        self.db()
             .prepare(&filter.to_query())?
             .query_map([], parse_transaction_columns)
             .expect("no binding parameters used in query")
@@ -78,7 +81,7 @@ impl SqliteStore {
     }
 
     /// Inserts a transaction and updates the current state based on the `tx_result` changes
-    pub fn apply_transaction(&mut self, tx_result: TransactionResult) -> Result<(), StoreError> {
+    pub fn apply_transaction(&self, tx_result: TransactionResult) -> Result<(), StoreError> {
         let account_id = tx_result.executed_transaction().account_id();
         let account_delta = tx_result.account_delta();
 
@@ -98,7 +101,8 @@ impl SqliteStore {
             .map(|note| OutputNoteRecord::from(note.clone()))
             .collect::<Vec<_>>();
 
-        let tx = self.db.transaction()?;
+        let mut db = self.db();
+        let tx = db.transaction()?;
 
         // Transaction Data
         insert_proven_transaction_data(&tx, tx_result)?;
@@ -143,6 +147,69 @@ impl SqliteStore {
 
         Ok(rows)
     }
+
+    /// Set the provided transactions as discarded, rolling back the changes they made to the
+    /// client's state:
+    ///
+    /// - The account of each transaction is reverted to the state it had before the transaction.
+    ///   If several transactions of the same account are discarded, the account is reverted to
+    ///   the oldest of their initial states.
+    /// - The notes created by each transaction that are still pending are removed, as they will
+    ///   never be committed. Its input notes are left untouched, so the ones that were not
+    ///   consumed by other transactions can be consumed again.
+    ///
+    /// # Errors
+    ///
+    /// This function can return an error if any of the transactions or their initial account
+    /// states are not found, or if any of the updates within the database transaction fail.
+    pub(crate) fn mark_transactions_as_discarded(
+        tx: &Transaction<'_>,
+        transactions_to_discard: &[TransactionId],
+    ) -> Result<usize, StoreError> {
+        // Nonce of the account state to revert each account to
+        let mut reverted_nonces: BTreeMap<AccountId, u64> = BTreeMap::new();
+        let mut rows = 0;
+        for transaction_id in transactions_to_discard {
+            const TRANSACTION_QUERY: &str =
+                "SELECT account_id, init_account_state, output_notes FROM transactions WHERE id = ?";
+            let (account_id, init_account_state, output_notes): (i64, String, Vec<u8>) = tx
+                .query_row(TRANSACTION_QUERY, params![transaction_id.to_string()], |row| {
+                    Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+                })?;
+
+            let account_id = AccountId::try_from(account_id as u64)?;
+            let init_nonce =
+                get_account_nonce_by_hash(tx, account_id, init_account_state.try_into()?)?;
+            reverted_nonces
+                .entry(account_id)
+                .and_modify(|nonce| *nonce = (*nonce).min(init_nonce))
+                .or_insert(init_nonce);
+
+            for note in OutputNotes::read_from_bytes(&output_notes)?.iter() {
+                let note_id = note.id().inner().to_hex();
+
+                const DISCARDED_OUTPUT_NOTE_QUERY: &str =
+                    "DELETE FROM output_notes WHERE note_id = ? AND status = 'Pending'";
+                tx.execute(DISCARDED_OUTPUT_NOTE_QUERY, params![note_id])?;
+
+                const DISCARDED_INPUT_NOTE_QUERY: &str =
+                    "DELETE FROM input_notes WHERE note_id = ? AND status = 'Pending'";
+                tx.execute(DISCARDED_INPUT_NOTE_QUERY, params![note_id])?;
+            }
+
+            const QUERY: &str = "UPDATE transactions set discarded=TRUE where id=?";
+            rows += tx.execute(QUERY, params![transaction_id.to_string()])?;
+        }
+
+        for (account_id, nonce) in reverted_nonces {
+            const QUERY: &str = "DELETE FROM accounts WHERE id = ? AND nonce > ?";
+            let account_id: u64 = account_id.into();
+            tx.execute(QUERY, params![account_id as i64, nonce as i64])?;
+        }
+        info!("Marked {} transactions as discarded", rows);
+
+        Ok(rows)
+    }
 }
 
 pub(super) fn insert_proven_transaction_data(
@@ -161,6 +228,7 @@ pub(super) fn insert_proven_transaction_data(
         script_inputs,
         block_num,
         committed,
+        discarded,
     ) = serialize_transaction_data(transaction_result)?;
 
     if let Some(hash) = script_hash.clone() {
@@ -180,6 +248,7 @@ pub(super) fn insert_proven_transaction_data(
             script_inputs,
             block_num,
             committed,
+            discarded,
         ],
     )?;
 
@@ -238,6 +307,7 @@ pub(super) fn serialize_transaction_data(
         script_inputs,
         transaction_result.block_num(),
         None,
+        false,
     ))
 }
 
@@ -255,6 +325,7 @@ fn parse_transaction_columns(
     let script_inputs: Option<String> = row.get(8)?;
     let block_num: u32 = row.get(9)?;
     let commit_height: Option<u32> = row.get(10)?;
+    let discarded: bool = row.get(11)?;
 
     Ok((
         id,
@@ -268,6 +339,7 @@ fn parse_transaction_columns(
         script_inputs,
         block_num,
         commit_height,
+        discarded,
     ))
 }
 
@@ -287,6 +359,7 @@ fn parse_transaction(
         script_inputs,
         block_num,
         commit_height,
+        discarded,
     ) = serialized_transaction;
     let account_id = AccountId::try_from(account_id as u64)?;
     let id: Digest = id.try_into()?;
@@ -327,8 +400,11 @@ fn parse_transaction(
         None
     };
 
-    let transaction_status =
-        commit_height.map_or(TransactionStatus::Pending, TransactionStatus::Committed);
+    let transaction_status = if discarded {
+        TransactionStatus::Discarded
+    } else {
+        commit_height.map_or(TransactionStatus::Pending, TransactionStatus::Committed)
+    };
 
     Ok(TransactionRecord {
         id: id.into(),
@@ -1,24 +1,48 @@
 // TESTS
 // ================================================================================================
+use std::{cell::RefCell, rc::Rc};
+
 use miden_lib::transaction::TransactionKernel;
 use miden_objects::{
     accounts::{AccountId, AccountStub, ACCOUNT_ID_FUNGIBLE_FAUCET_OFF_CHAIN},
     assembly::{AstSerdeOptions, ModuleAst},
     assets::{FungibleAsset, TokenSymbol},
-    crypto::dsa::rpo_falcon512::SecretKey,
-    Word,
+    crypto::{
+        dsa::rpo_falcon512::SecretKey,
+        merkle::{MerklePath, MmrPeaks},
+        rand::FeltRng,
+    },
+    notes::{NoteExecutionMode, NoteInclusionProof, NoteTag},
+    transaction::InputNote,
+    BlockHeader, Digest, Felt, Word,
 };
+use tonic::transport::ClientTlsConfig;
 
 use crate::{
     client::{
         accounts::{AccountStorageMode, AccountTemplate},
-        transactions::transaction_request::TransactionTemplate,
+        derive_random_coin,
+        events::ClientEvent,
+        get_random_coin,
+        rpc::{
+            FailoverRpcClient, NodeRpcClient, NullifierUpdate, RecordingRpcClient, ReplayRpcClient,
+            TonicRpcClient,
+        },
+        sync::{SyncConfig, SyncStatus, SyncedNewNotes, FILTER_ID_SHIFT},
+        sync_service::{SyncService, SyncServiceConfig},
+        transactions::{transaction_request::TransactionTemplate, TransactionStatus},
     },
+    config::{Endpoint, RpcConfig, TlsConfig},
+    errors::{ClientError, NodeRpcClientError, VerificationError},
     mock::{
         get_account_with_default_account_code, mock_full_chain_mmr_and_notes,
-        mock_fungible_faucet_account, mock_notes, ACCOUNT_ID_REGULAR,
+        mock_fungible_faucet_account, mock_notes, MockRpcApi, ACCOUNT_ID_REGULAR,
+    },
+    store::{
+        data_store::get_authentication_path_for_blocks,
+        sqlite_store::tests::{create_test_client, create_test_store, create_test_store_path},
+        AuthInfo, InputNoteRecord, NoteFilter, NoteStatus, Store, TransactionFilter,
     },
-    store::{sqlite_store::tests::create_test_client, AuthInfo, InputNoteRecord, NoteFilter},
 };
 
 #[tokio::test]
@@ -34,7 +58,7 @@ async fn test_input_notes_round_trip() {
 
     // insert notes into database
     for note in consumed_notes.iter().cloned() {
-        client.import_input_note(note.into()).unwrap();
+        client.import_input_note(note.into()).await.unwrap();
     }
 
     // retrieve notes from database
@@ -58,7 +82,10 @@ async fn test_get_input_note() {
     let (_consumed_notes, created_notes) = mock_notes(&assembler);
 
     // insert Note into database
-    client.import_input_note(created_notes.first().unwrap().clone().into()).unwrap();
+    client
+        .import_input_note(created_notes.first().unwrap().clone().into())
+        .await
+        .unwrap();
 
     // retrieve note from database
     let retrieved_note =
@@ -233,16 +260,17 @@ async fn test_sync_state() {
     let pending_notes = client.get_input_notes(NoteFilter::Pending).unwrap();
 
     // sync state
-    let block_num: u32 = client.sync_state().await.unwrap();
+    let sync_summary = client.sync_state().await.unwrap();
 
     // verify that the client is synced to the latest block
     assert_eq!(
-        block_num,
+        sync_summary.block_num,
         client.rpc_api().state_sync_requests.first_key_value().unwrap().1.chain_tip
     );
 
     // verify that we now have one consumed note after syncing state
     assert_eq!(client.get_input_notes(NoteFilter::Consumed).unwrap().len(), 1);
+    assert_eq!(sync_summary.consumed_nullifiers.len(), 1);
 
     // verify that the pending note we had is now committed
     assert_ne!(client.get_input_notes(NoteFilter::Committed).unwrap(), pending_notes);
@@ -254,6 +282,412 @@ async fn test_sync_state() {
     );
 }
 
+#[tokio::test]
+async fn test_check_note_spent() {
+    // generate test client with a random store name
+    let mut client = create_test_client();
+
+    // generate test data
+    crate::mock::insert_mock_data(&mut client).await;
+    client.sync_state().await.unwrap();
+    let (chain_tip, _) = client
+        .store()
+        .get_block_header_by_num(client.get_sync_height().unwrap())
+        .unwrap();
+
+    // the node proves which notes were spent against the nullifier root of the chain tip
+    let mut spent_notes = Vec::new();
+    for note in client.get_input_notes(NoteFilter::All).unwrap() {
+        let status = client.check_note_spent(note.id()).await.unwrap();
+        assert_eq!(status.nullifier(), Digest::try_from(note.nullifier()).unwrap());
+        assert_eq!(status.nullifier_root(), chain_tip.nullifier_root());
+        if status.is_spent() {
+            spent_notes.push(note.id());
+        }
+    }
+
+    // and they are the ones the sync marked as consumed
+    let consumed_notes: Vec<_> = client
+        .get_input_notes(NoteFilter::Consumed)
+        .unwrap()
+        .iter()
+        .map(|note| note.id())
+        .collect();
+    assert_eq!(spent_notes, consumed_notes);
+}
+
+#[tokio::test]
+async fn test_check_note_spent_rejects_unverified_nullifier_root() {
+    let mut client = create_test_client();
+    crate::mock::insert_mock_data(&mut client).await;
+    client.sync_state().await.unwrap();
+
+    // the node's nullifier tree moves past the one committed to by the client's chain tip
+    let response = client.rpc_api().state_sync_requests.values_mut().next().unwrap();
+    response
+        .nullifiers
+        .push(miden_node_proto::generated::responses::NullifierUpdate {
+            nullifier: Some(
+                Digest::new([Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)]).into(),
+            ),
+            block_num: 8,
+        });
+
+    let note = client.get_input_notes(NoteFilter::All).unwrap().remove(0);
+    assert!(matches!(
+        client.check_note_spent(note.id()).await,
+        Err(ClientError::VerificationError(VerificationError::NullifierRootMismatch(..)))
+    ));
+}
+
+#[tokio::test]
+async fn test_sync_state_emits_events() {
+    // generate test client with a random store name
+    let mut client = create_test_client();
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let listener_events = events.clone();
+    client.subscribe(move |event: &ClientEvent| listener_events.borrow_mut().push(event.clone()));
+
+    // generate test data
+    crate::mock::insert_mock_data(&mut client).await;
+    let pending_notes = client.get_input_notes(NoteFilter::Pending).unwrap();
+
+    client.sync_state().await.unwrap();
+
+    let events = events.borrow();
+    let consumed_note = client.get_input_notes(NoteFilter::Consumed).unwrap().pop().unwrap();
+    assert!(events.iter().any(|event| matches!(
+        event,
+        ClientEvent::NoteConsumed { note_id, .. } if *note_id == consumed_note.id()
+    )));
+
+    for pending_note in pending_notes {
+        assert!(events.iter().any(|event| matches!(
+            event,
+            ClientEvent::NoteCommitted { note_id, .. } if *note_id == pending_note.id()
+        )));
+    }
+}
+
+#[tokio::test]
+async fn test_sync_service() {
+    // generate test client with a random store name
+    let mut client = create_test_client();
+
+    // generate test data
+    crate::mock::insert_mock_data(&mut client).await;
+    let chain_tip = client.rpc_api().state_sync_requests.first_key_value().unwrap().1.chain_tip;
+
+    let (service, mut handle) = SyncService::new(client, SyncServiceConfig::default());
+    assert!(handle.latest_status().is_none());
+
+    let controller = async move {
+        assert!(handle.changed().await);
+        let state = handle.state();
+        handle.stop();
+        state
+    };
+    let (client, state) = tokio::join!(service.run(), controller);
+
+    assert_eq!(state.latest_status, Some(SyncStatus::SyncedToLastBlock(chain_tip)));
+    assert!(state.last_error.is_none());
+    assert_eq!(client.get_sync_height().unwrap(), chain_tip);
+}
+
+#[tokio::test]
+async fn test_sync_state_rejects_unverified_block_header() {
+    // generate test client with a random store name
+    let mut client = create_test_client();
+
+    // generate test data
+    let tracked_block_headers = crate::mock::insert_mock_data(&mut client).await;
+
+    // replace the first block header returned by the node with one that does not commit to the
+    // chain tracked by the client
+    let forged_block_header =
+        BlockHeader::mock(tracked_block_headers[0].block_num(), None, None, &[]);
+    let response = client.rpc_api().state_sync_requests.values_mut().next().unwrap();
+    response.block_header = Some(forged_block_header.into());
+
+    // the sync step is rejected and nothing from it is persisted
+    assert!(matches!(
+        client.sync_state().await,
+        Err(ClientError::VerificationError(VerificationError::ChainRootMismatch(..)))
+    ));
+    assert_eq!(client.get_sync_height().unwrap(), 0);
+    assert!(client.get_block_headers(&[forged_block_header.block_num()]).unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_sync_state_rejects_invalid_note_inclusion_proof() {
+    // generate test client with a random store name
+    let mut client = create_test_client();
+
+    // generate test data
+    crate::mock::insert_mock_data(&mut client).await;
+
+    // replace the merkle path of the first note returned by the node with one that does not open
+    // the block's note root
+    let response = client.rpc_api().state_sync_requests.values_mut().next().unwrap();
+    response.notes[0].merkle_path = Some(Default::default());
+
+    // the sync step is rejected and the note is not marked as committed
+    assert!(matches!(
+        client.sync_state().await,
+        Err(ClientError::VerificationError(VerificationError::InvalidNoteInclusionProofs(
+            ..
+        )))
+    ));
+    assert_eq!(client.get_sync_height().unwrap(), 0);
+    assert!(client
+        .get_input_notes(NoteFilter::Committed)
+        .unwrap()
+        .iter()
+        .all(|note| { note.inclusion_proof().is_some() }));
+}
+
+#[tokio::test]
+async fn test_pipelined_sync_request_tracks_notes_committed_in_previous_chunk() {
+    // generate test client with a random store name
+    let mut client = create_test_client();
+
+    // generate test data
+    let tracked_block_headers = crate::mock::insert_mock_data(&mut client).await;
+    let first_block = tracked_block_headers[0].block_num();
+    let second_block = tracked_block_headers[1].block_num();
+
+    // the request for the second chunk is sent while the first one is being applied
+    let sync_summary = client.sync_state_to(second_block).await.unwrap();
+    assert_eq!(sync_summary.blocks_processed, vec![first_block, second_block]);
+    assert_eq!(client.get_sync_height().unwrap(), second_block);
+
+    // and it already includes the nullifier of the note committed in the first chunk
+    let committed_note = client
+        .get_input_notes(NoteFilter::Committed)
+        .unwrap()
+        .into_iter()
+        .find(|note| note.inclusion_proof().unwrap().origin().block_num == first_block)
+        .unwrap();
+    let nullifier = Digest::try_from(committed_note.nullifier()).unwrap();
+    let (_, nullifiers_tags) = client.rpc_api().last_sync_state_request.clone().unwrap();
+    assert!(nullifiers_tags.contains(&((nullifier[3].as_int() >> FILTER_ID_SHIFT) as u16)));
+}
+
+#[tokio::test]
+async fn test_sync_state_to_and_rescan_from() {
+    // generate test client with a random store name
+    let mut client = create_test_client();
+
+    // generate test data
+    crate::mock::insert_mock_data(&mut client).await;
+    let pending_notes = client.get_input_notes(NoteFilter::Pending).unwrap().len();
+
+    // hide the notes and nullifiers of the first sync step
+    let (_, first_response) = client
+        .rpc_api()
+        .state_sync_requests
+        .iter_mut()
+        .find(|(request, _)| request.block_num == 0)
+        .unwrap();
+    let first_block = first_response.block_header.as_ref().unwrap().block_num;
+    let chain_tip = first_response.chain_tip;
+    let notes = std::mem::take(&mut first_response.notes);
+    let nullifiers = std::mem::take(&mut first_response.nullifiers);
+
+    // syncing to a block before the chain tip stops after the step that reaches it
+    let sync_summary = client.sync_state_to(first_block - 1).await.unwrap();
+    assert_eq!(sync_summary.block_num, first_block);
+    assert!(first_block < chain_tip);
+    assert_eq!(client.get_sync_height().unwrap(), first_block);
+    assert_eq!(client.get_input_notes(NoteFilter::Pending).unwrap().len(), pending_notes);
+    assert_eq!(client.get_input_notes(NoteFilter::Consumed).unwrap().len(), 0);
+
+    // rescanning the synced blocks finds the hidden note and nullifier
+    let (_, first_response) = client
+        .rpc_api()
+        .state_sync_requests
+        .iter_mut()
+        .find(|(request, _)| request.block_num == 0)
+        .unwrap();
+    first_response.notes = notes;
+    first_response.nullifiers = nullifiers;
+
+    let rescan_summary = client.rescan_from(0, &[]).await.unwrap();
+    assert_eq!(rescan_summary.block_num, first_block);
+    assert_eq!(rescan_summary.blocks_processed, vec![first_block]);
+    assert_eq!(rescan_summary.committed_notes.len(), 1);
+    assert_eq!(rescan_summary.consumed_nullifiers.len(), 1);
+    assert_eq!(client.get_sync_height().unwrap(), first_block);
+    assert_eq!(client.get_input_notes(NoteFilter::Pending).unwrap().len(), pending_notes - 1);
+    assert_eq!(client.get_input_notes(NoteFilter::Consumed).unwrap().len(), 1);
+    assert!(client.store().get_block_header_by_num(first_block).unwrap().1);
+
+    // the rest of the chain is synced as usual
+    let sync_summary = client.sync_state().await.unwrap();
+    assert_eq!(sync_summary.block_num, chain_tip);
+}
+
+#[tokio::test]
+async fn test_rescan_keeps_nullifiers_of_a_response_past_the_sync_height() {
+    // generate test client with a random store name
+    let mut client = create_test_client();
+
+    // generate test data
+    crate::mock::insert_mock_data(&mut client).await;
+
+    // sync the whole chain without learning of any consumed note
+    let mut nullifiers = Vec::new();
+    for response in client.rpc_api().state_sync_requests.values_mut() {
+        nullifiers = std::mem::take(&mut response.nullifiers);
+    }
+    client.sync_state().await.unwrap();
+    assert_eq!(client.get_input_notes(NoteFilter::Consumed).unwrap().len(), 0);
+
+    // the second response goes past the sync height, but one of its nullifiers was spent before
+    let mut response_blocks: Vec<u32> = client
+        .rpc_api()
+        .state_sync_requests
+        .values()
+        .map(|response| response.block_header.as_ref().unwrap().block_num)
+        .collect();
+    response_blocks.sort();
+    let second_block = response_blocks[1];
+    let sync_height = second_block - 1;
+    for nullifier in nullifiers.iter_mut() {
+        nullifier.block_num = sync_height;
+    }
+    client
+        .rpc_api()
+        .state_sync_requests
+        .values_mut()
+        .find(|response| response.block_header.as_ref().unwrap().block_num == second_block)
+        .unwrap()
+        .nullifiers = nullifiers;
+    client
+        .store()
+        .db()
+        .execute("UPDATE state_sync SET block_num = ?", [sync_height])
+        .unwrap();
+
+    let rescan_summary = client.rescan_from(0, &[]).await.unwrap();
+    assert!(rescan_summary.committed_notes.is_empty());
+    assert_eq!(rescan_summary.consumed_nullifiers.len(), 1);
+    assert_eq!(client.get_input_notes(NoteFilter::Consumed).unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_sync_state_stores_expected_private_notes() {
+    // sync a first client to learn the inclusion proof of a private note created in the chain
+    let mut client = create_test_client();
+    crate::mock::insert_mock_data(&mut client).await;
+    let note_id = client.get_input_notes(NoteFilter::Pending).unwrap()[0].id();
+    client.sync_state().await.unwrap();
+    let committed_note: InputNote = client.get_input_note(note_id).unwrap().try_into().unwrap();
+
+    // a second client doesn't know the note's details, so the node only returns its metadata
+    let mut client = create_test_client();
+    crate::mock::insert_mock_data(&mut client).await;
+    let pending_note = client.get_input_note(note_id).unwrap();
+    client
+        .store()
+        .db()
+        .execute("DELETE FROM input_notes WHERE note_id = ?", [note_id.inner().to_string()])
+        .unwrap();
+    client.rpc_api().notes.insert(note_id, committed_note.clone());
+
+    let sync_summary = client.sync_state().await.unwrap();
+    assert_eq!(sync_summary.new_expected_notes, vec![note_id]);
+
+    let expected_notes = client.get_expected_notes().unwrap();
+    assert_eq!(expected_notes.len(), 1);
+    assert_eq!(expected_notes[0].id(), note_id);
+    assert_eq!(expected_notes[0].metadata(), committed_note.note().metadata());
+    assert_eq!(expected_notes[0].inclusion_proof(), committed_note.proof());
+
+    // importing the note's details commits it with the stored proof, without syncing
+    client.import_input_note(pending_note).await.unwrap();
+    let imported_note = client.get_input_note(note_id).unwrap();
+    assert_eq!(imported_note.status(), NoteStatus::Committed);
+    assert_eq!(imported_note.inclusion_proof(), Some(committed_note.proof()));
+    assert!(client.get_expected_notes().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_sync_state_with_widened_prefixes_and_decoys() {
+    // generate test client with a random store name
+    let mut client = create_test_client();
+
+    // generate test data
+    crate::mock::insert_mock_data(&mut client).await;
+
+    let sync_config = SyncConfig {
+        nullifier_prefix_bits: 12,
+        account_tag_prefix_bits: 10,
+        decoy_nullifier_prefixes: 3,
+        decoy_note_tags: 2,
+        ..SyncConfig::default()
+    };
+    client.set_sync_config(sync_config).unwrap();
+
+    let account_tags: Vec<NoteTag> = client
+        .get_accounts()
+        .unwrap()
+        .iter()
+        .map(|(stub, _)| NoteTag::from_account_id(stub.id(), NoteExecutionMode::Local).unwrap())
+        .collect();
+
+    // the results received for the extra prefixes and tags are filtered out
+    let sync_summary = client.sync_state().await.unwrap();
+    assert_eq!(sync_summary.block_num, client.get_sync_height().unwrap());
+    assert!(sync_summary.new_public_notes.is_empty());
+
+    // every real prefix is sent along with all the prefixes that share its 12 high bits. The
+    // nullifiers that are still unspent were also unspent when the last request was sent
+    let (note_tags, nullifiers_tags) = client.rpc_api().last_sync_state_request.clone().unwrap();
+    let nullifier_prefixes: Vec<u16> = client
+        .store()
+        .get_unspent_input_note_nullifiers()
+        .unwrap()
+        .iter()
+        .map(|nullifier| (nullifier.inner()[3].as_int() >> FILTER_ID_SHIFT) as u16)
+        .collect();
+    for prefix in nullifier_prefixes {
+        let base = prefix & 0xfff0;
+        assert!((base..=base | 0xf).all(|prefix| nullifiers_tags.contains(&prefix)));
+    }
+
+    // every account tag is sent along with the tags of the accounts that share its prefix
+    let note_tags: Vec<u32> = note_tags.into_iter().map(u32::from).collect();
+    for tag in account_tags.into_iter().map(u32::from) {
+        let base = tag & 0xfff0_ffff;
+        assert!((0..16).all(|low_bits| note_tags.contains(&(base | (low_bits << 16)))));
+    }
+
+    // prefix widths beyond the ones supported by the node are rejected, as well as the ones and
+    // the decoys that would make requests too large
+    let invalid_configs = [
+        SyncConfig { nullifier_prefix_bits: 17, ..sync_config },
+        SyncConfig { nullifier_prefix_bits: 0, ..sync_config },
+        SyncConfig {
+            account_tag_prefix_bits: 5,
+            ..sync_config
+        },
+        SyncConfig {
+            decoy_nullifier_prefixes: 257,
+            ..sync_config
+        },
+        SyncConfig { decoy_note_tags: 257, ..sync_config },
+    ];
+    for invalid_config in invalid_configs {
+        assert!(matches!(
+            client.set_sync_config(invalid_config),
+            Err(ClientError::InvalidSyncConfig(_))
+        ));
+    }
+    assert_eq!(client.sync_config(), &sync_config);
+}
+
 #[tokio::test]
 async fn test_sync_state_mmr_state() {
     // generate test client with a random store name
@@ -263,7 +697,7 @@ async fn test_sync_state_mmr_state() {
     let tracked_block_headers = crate::mock::insert_mock_data(&mut client).await;
 
     // sync state
-    let block_num: u32 = client.sync_state().await.unwrap();
+    let block_num = client.sync_state().await.unwrap().block_num;
 
     // verify that the client is synced to the latest block
     assert_eq!(
@@ -317,19 +751,38 @@ async fn test_add_tag() {
     assert_eq!(client.get_note_tags().unwrap().len(), 0);
 
     // add a tag
-    const TAG_VALUE_1: u64 = 1;
-    const TAG_VALUE_2: u64 = 2;
-    client.add_note_tag(TAG_VALUE_1).unwrap();
-    client.add_note_tag(TAG_VALUE_2).unwrap();
+    let tag_1 = NoteTag::from(1);
+    let tag_2 = NoteTag::from(2);
+    client.add_note_tag(tag_1).unwrap();
+    client.add_note_tag(tag_2).unwrap();
 
     // verify that the tag is being tracked
-    assert_eq!(client.get_note_tags().unwrap(), vec![TAG_VALUE_1, TAG_VALUE_2]);
+    assert_eq!(client.get_note_tags().unwrap(), vec![tag_1, tag_2]);
 
     // attempt to add the same tag again
-    client.add_note_tag(TAG_VALUE_1).unwrap();
+    client.add_note_tag(tag_1).unwrap();
 
     // verify that the tag is still being tracked only once
-    assert_eq!(client.get_note_tags().unwrap(), vec![TAG_VALUE_1, TAG_VALUE_2]);
+    assert_eq!(client.get_note_tags().unwrap(), vec![tag_1, tag_2]);
+}
+
+#[tokio::test]
+async fn test_remove_tag() {
+    // generate test client with a random store name
+    let mut client = create_test_client();
+
+    let tag_1 = NoteTag::from(1);
+    let tag_2 = NoteTag::from(2);
+    client.add_note_tag(tag_1).unwrap();
+    client.add_note_tag(tag_2).unwrap();
+
+    // remove a tracked tag
+    client.remove_note_tag(tag_1).unwrap();
+    assert_eq!(client.get_note_tags().unwrap(), vec![tag_2]);
+
+    // removing a tag that is not tracked leaves the tracked tags unchanged
+    client.remove_note_tag(tag_1).unwrap();
+    assert_eq!(client.get_note_tags().unwrap(), vec![tag_2]);
 }
 
 #[tokio::test]
@@ -368,3 +821,413 @@ async fn test_mint_transaction() {
     let transaction = client.new_transaction(transaction_request).unwrap();
     assert!(transaction.executed_transaction().account_delta().nonce().is_some());
 }
+
+#[tokio::test]
+async fn test_discard_transaction() {
+    const FAUCET_ID: u64 = ACCOUNT_ID_FUNGIBLE_FAUCET_OFF_CHAIN;
+    const INITIAL_BALANCE: u64 = 1000;
+
+    // generate test client with a random store name
+    let mut client = create_test_client();
+
+    let key_pair = SecretKey::new();
+    let faucet = mock_fungible_faucet_account(
+        AccountId::try_from(FAUCET_ID).unwrap(),
+        INITIAL_BALANCE,
+        key_pair.clone(),
+    );
+    client
+        .store()
+        .insert_account(&faucet, None, &AuthInfo::RpoFalcon512(key_pair))
+        .unwrap();
+
+    client.sync_state().await.unwrap();
+
+    // execute a mint transaction and apply it locally, as if it had been submitted
+    let transaction_template = TransactionTemplate::MintFungibleAsset(
+        FungibleAsset::new(faucet.id(), 5u64).unwrap(),
+        AccountId::from_hex("0x168187d729b31a84").unwrap(),
+        miden_objects::notes::NoteType::OffChain,
+    );
+    let transaction_request = client.build_transaction_request(transaction_template).unwrap();
+    let transaction = client.new_transaction(transaction_request).unwrap();
+    let transaction_id = transaction.executed_transaction().id();
+    client.store().apply_transaction(transaction).unwrap();
+
+    assert_ne!(client.get_account(faucet.id()).unwrap().0.hash(), faucet.hash());
+    assert_eq!(client.store().get_output_notes(NoteFilter::Pending).unwrap().len(), 1);
+
+    // discard the transaction as part of a sync update
+    let block_num = client.get_sync_height().unwrap() + 1;
+    client
+        .store()
+        .apply_state_sync(
+            BlockHeader::mock(block_num, None, None, &[]),
+            vec![],
+            SyncedNewNotes::new(vec![], vec![], vec![]),
+            &[],
+            &[transaction_id],
+            MmrPeaks::new(0, vec![]).unwrap(),
+            &[],
+            &[],
+        )
+        .unwrap();
+
+    // the account is back to its state before the transaction and the created note is dropped
+    assert_eq!(client.get_account(faucet.id()).unwrap().0.hash(), faucet.hash());
+    assert!(client.store().get_output_notes(NoteFilter::Pending).unwrap().is_empty());
+
+    let transactions = client.get_transactions(TransactionFilter::All).unwrap();
+    assert!(matches!(transactions[0].transaction_status, TransactionStatus::Discarded));
+    assert!(client.get_transactions(TransactionFilter::Uncomitted).unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_sync_discards_transactions_of_diverged_off_chain_account() {
+    const FAUCET_ID: u64 = ACCOUNT_ID_FUNGIBLE_FAUCET_OFF_CHAIN;
+    const INITIAL_BALANCE: u64 = 1000;
+
+    let mut client = create_test_client();
+
+    let key_pair = SecretKey::new();
+    let faucet = mock_fungible_faucet_account(
+        AccountId::try_from(FAUCET_ID).unwrap(),
+        INITIAL_BALANCE,
+        key_pair.clone(),
+    );
+    client
+        .store()
+        .insert_account(&faucet, None, &AuthInfo::RpoFalcon512(key_pair))
+        .unwrap();
+
+    // sync the first chunk of the chain only
+    client.sync_state_to(1).await.unwrap();
+    let sync_height = client.get_sync_height().unwrap();
+
+    // execute a mint transaction and apply it locally, as if it had been submitted
+    let transaction_template = TransactionTemplate::MintFungibleAsset(
+        FungibleAsset::new(faucet.id(), 5u64).unwrap(),
+        AccountId::from_hex("0x168187d729b31a84").unwrap(),
+        miden_objects::notes::NoteType::OffChain,
+    );
+    let transaction_request = client.build_transaction_request(transaction_template).unwrap();
+    let transaction = client.new_transaction(transaction_request).unwrap();
+    let transaction_id = transaction.executed_transaction().id();
+    client.store().apply_transaction(transaction).unwrap();
+
+    // the next chunk updates the off-chain account to a state the transaction doesn't lead to
+    let (_, response) = client
+        .rpc_api()
+        .state_sync_requests
+        .iter_mut()
+        .find(|(request, _)| request.block_num == sync_height)
+        .unwrap();
+    response
+        .accounts
+        .push(miden_node_proto::generated::responses::AccountHashUpdate {
+            account_id: Some(faucet.id().into()),
+            account_hash: Some(
+                Digest::new([Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)]).into(),
+            ),
+            block_num: response.block_header.as_ref().unwrap().block_num,
+        });
+
+    // the sync succeeds, discarding the stale transaction and rolling back its changes
+    let summary = client.sync_state().await.unwrap();
+    assert_eq!(summary.discarded_transactions, vec![transaction_id]);
+    assert_eq!(client.get_account(faucet.id()).unwrap().0.hash(), faucet.hash());
+    assert!(client.store().get_output_notes(NoteFilter::Pending).unwrap().is_empty());
+
+    let transactions = client.get_transactions(TransactionFilter::All).unwrap();
+    assert!(matches!(transactions[0].transaction_status, TransactionStatus::Discarded));
+}
+
+#[tokio::test]
+async fn test_output_note_consumption_is_tracked() {
+    const FAUCET_ID: u64 = ACCOUNT_ID_FUNGIBLE_FAUCET_OFF_CHAIN;
+    const INITIAL_BALANCE: u64 = 1000;
+
+    // generate test client with a random store name
+    let mut client = create_test_client();
+
+    let key_pair = SecretKey::new();
+    let faucet = mock_fungible_faucet_account(
+        AccountId::try_from(FAUCET_ID).unwrap(),
+        INITIAL_BALANCE,
+        key_pair.clone(),
+    );
+    client
+        .store()
+        .insert_account(&faucet, None, &AuthInfo::RpoFalcon512(key_pair))
+        .unwrap();
+
+    client.sync_state().await.unwrap();
+
+    // execute a mint transaction so that the client tracks the created note as an output note
+    let transaction_template = TransactionTemplate::MintFungibleAsset(
+        FungibleAsset::new(faucet.id(), 5u64).unwrap(),
+        AccountId::from_hex("0x168187d729b31a84").unwrap(),
+        miden_objects::notes::NoteType::OffChain,
+    );
+    let transaction_request = client.build_transaction_request(transaction_template).unwrap();
+    let transaction = client.new_transaction(transaction_request).unwrap();
+    client.store().apply_transaction(transaction).unwrap();
+
+    let output_notes = client.get_output_notes(NoteFilter::All).unwrap();
+    assert_eq!(output_notes.len(), 1);
+    assert_eq!(output_notes[0].consumed_block_num(), None);
+    let nullifier = Digest::try_from(output_notes[0].details().unwrap().nullifier()).unwrap();
+
+    // the note's nullifier is received as part of a sync update
+    let block_num = client.get_sync_height().unwrap() + 1;
+    client
+        .store()
+        .apply_state_sync(
+            BlockHeader::mock(block_num, None, None, &[]),
+            vec![NullifierUpdate { nullifier, block_num }],
+            SyncedNewNotes::new(vec![], vec![], vec![]),
+            &[],
+            &[],
+            MmrPeaks::new(0, vec![]).unwrap(),
+            &[],
+            &[],
+        )
+        .unwrap();
+
+    let consumed_notes = client.get_output_notes(NoteFilter::Consumed).unwrap();
+    assert_eq!(consumed_notes.len(), 1);
+    assert_eq!(consumed_notes[0].id(), output_notes[0].id());
+    assert_eq!(consumed_notes[0].consumed_block_num(), Some(block_num));
+}
+
+#[tokio::test]
+async fn test_import_note_committed_in_untracked_block() {
+    let mut client = create_test_client();
+    crate::mock::insert_mock_data(&mut client).await;
+
+    // the second mocked consumed note was committed in block 1, which no client tracks
+    let note = client
+        .get_input_notes(NoteFilter::Committed)
+        .unwrap()
+        .into_iter()
+        .find(|note| note.inclusion_proof().unwrap().origin().block_num == 1)
+        .unwrap();
+
+    // another client that synced past the note's block only receives it afterwards
+    let mut importing_client = create_test_client();
+    importing_client.rpc_api().genesis_block = client.rpc_api().genesis_block;
+    importing_client.rpc_api().block_chain = client.rpc_api().block_chain.clone();
+    importing_client.rpc_api().state_sync_requests = client.rpc_api().state_sync_requests.clone();
+    importing_client.sync_state().await.unwrap();
+    assert!(importing_client.get_block_headers(&[1]).unwrap().is_empty());
+
+    importing_client.import_input_note(note.clone()).await.unwrap();
+
+    // the block header is stored as tracked, along with the chain MMR peaks at its height
+    let (block_header, has_client_notes) = importing_client.get_block_headers(&[1]).unwrap()[0];
+    assert!(has_client_notes);
+    assert_eq!(block_header.note_root(), note.inclusion_proof().unwrap().note_root());
+    let block_peaks = importing_client.store().get_chain_mmr_peaks_by_block_num(1).unwrap();
+    assert_eq!(block_peaks.hash_peaks(), block_header.chain_root());
+
+    // and the stored chain MMR nodes authenticate it against the current peaks
+    let sync_height = importing_client.get_sync_height().unwrap();
+    let path =
+        get_authentication_path_for_blocks(importing_client.store(), &[1], sync_height as usize)
+            .unwrap()
+            .remove(0);
+    let current_peaks =
+        importing_client.store().get_chain_mmr_peaks_by_block_num(sync_height).unwrap();
+    assert_eq!(path.compute_root(1, block_header.hash()).unwrap(), current_peaks.peaks()[0]);
+}
+
+#[tokio::test]
+async fn test_import_note_rejects_invalid_note_path() {
+    let mut client = create_test_client();
+    crate::mock::insert_mock_data(&mut client).await;
+
+    let note = client
+        .get_input_notes(NoteFilter::Committed)
+        .unwrap()
+        .into_iter()
+        .find(|note| note.inclusion_proof().unwrap().origin().block_num == 1)
+        .unwrap();
+
+    // keep the block's note root, but replace the note path with one that does not open it
+    let proof = note.inclusion_proof().unwrap();
+    let forged_proof = NoteInclusionProof::new(
+        proof.origin().block_num,
+        proof.sub_hash(),
+        proof.note_root(),
+        proof.origin().node_index.value(),
+        MerklePath::new(vec![Digest::default(); proof.note_path().depth() as usize]),
+    )
+    .unwrap();
+    let forged_note = InputNoteRecord::new(
+        note.id(),
+        note.recipient(),
+        note.assets().clone(),
+        NoteStatus::Committed,
+        note.metadata().copied(),
+        Some(forged_proof),
+        note.details().clone(),
+    );
+
+    let mut importing_client = create_test_client();
+    importing_client.rpc_api().genesis_block = client.rpc_api().genesis_block;
+    importing_client.rpc_api().block_chain = client.rpc_api().block_chain.clone();
+    importing_client.rpc_api().state_sync_requests = client.rpc_api().state_sync_requests.clone();
+    importing_client.sync_state().await.unwrap();
+
+    assert!(matches!(
+        importing_client.import_input_note(forged_note).await,
+        Err(ClientError::VerificationError(VerificationError::InvalidNoteInclusionProofs(
+            ..
+        )))
+    ));
+    assert!(importing_client.get_input_note(note.id()).is_err());
+    assert!(importing_client.get_block_headers(&[1]).unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_random_coin_is_persisted() {
+    let store = create_test_store();
+    assert!(store.get_rng_seed().unwrap().is_none());
+
+    let mut first_coin = get_random_coin(&store).unwrap();
+    let mut second_coin = get_random_coin(&store).unwrap();
+
+    // every coin is derived from the same persisted seed, but from a different counter value
+    let seed = store.get_rng_seed().unwrap().unwrap();
+    assert_eq!(store.advance_rng_counter().unwrap(), (seed, 2));
+
+    let first_word = first_coin.draw_word();
+    assert_ne!(first_word, second_coin.draw_word());
+    assert_eq!(first_word, derive_random_coin(seed, 0).draw_word());
+}
+
+#[tokio::test]
+async fn test_failover_rejects_nodes_with_a_different_genesis() {
+    let node = MockRpcApi::default();
+    let genesis = node.genesis_block.hash();
+
+    let forked_genesis = BlockHeader::mock(0, Some(Digest::new([Felt::new(1); 4])), None, &[]);
+    assert_ne!(forked_genesis.hash(), genesis);
+    let mut forked_node = MockRpcApi::default();
+    forked_node.genesis_block = forked_genesis;
+    forked_node.block_chain = vec![forked_genesis];
+
+    // the forked node is preferred, but its genesis block differs from the local one
+    let mut rpc_api = FailoverRpcClient::new(vec![forked_node, node]).with_genesis(genesis);
+    assert_eq!(rpc_api.get_block_header_by_number(Some(0)).await.unwrap().hash(), genesis);
+
+    // without a local genesis block, the one of the first healthy node is required from the rest
+    let mut failing_node = MockRpcApi::default();
+    failing_node.state_sync_requests.clear();
+    let mut forked_node = MockRpcApi::default();
+    forked_node.genesis_block = forked_genesis;
+    forked_node.block_chain = vec![forked_genesis];
+    let mut node = MockRpcApi::default();
+    node.genesis_block = failing_node.genesis_block;
+    node.block_chain = vec![failing_node.genesis_block];
+    let genesis = failing_node.genesis_block.hash();
+
+    // the failed sync request is sent to the last node, skipping the forked one
+    let mut rpc_api = FailoverRpcClient::new(vec![failing_node, forked_node, node]);
+    rpc_api.sync_state(0, &[], &[], &[]).await.unwrap();
+    assert_eq!(rpc_api.get_block_header_by_number(None).await.unwrap().hash(), genesis);
+
+    // requests that a node served but rejected are not sent to another node, even if it could
+    // have answered them
+    let node = MockRpcApi::default();
+    let mut longer_node = MockRpcApi::default();
+    longer_node.genesis_block = node.genesis_block;
+    longer_node.block_chain = vec![node.genesis_block, BlockHeader::mock(1, None, None, &[])];
+
+    let mut rpc_api = FailoverRpcClient::new(vec![node, longer_node]);
+    assert!(matches!(
+        rpc_api.get_block_header_with_proof(1, 2).await,
+        Err(NodeRpcClientError::RequestError(..))
+    ));
+}
+
+#[tokio::test]
+async fn test_tls_settings_require_an_https_endpoint() {
+    let mut rpc_client =
+        TonicRpcClient::new("http://localhost:57291").with_tls_config(ClientTlsConfig::new());
+    assert!(matches!(
+        rpc_client.get_block_header_by_number(None).await,
+        Err(NodeRpcClientError::InvalidConfig(_))
+    ));
+
+    let mut rpc_client = TonicRpcClient::new("http://localhost:57291")
+        .with_metadata("authorization", "Bearer token")
+        .unwrap();
+    assert!(matches!(
+        rpc_client.get_block_header_by_number(None).await,
+        Err(NodeRpcClientError::InvalidConfig(_))
+    ));
+
+    let mut config = RpcConfig::default();
+    config.metadata.insert("authorization".to_string(), "Bearer token".to_string());
+    assert!(matches!(
+        TonicRpcClient::try_from(&config),
+        Err(NodeRpcClientError::InvalidConfig(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_custom_certificate_authority_is_loaded() {
+    // nothing listens on the port, so the request can only fail after the TLS settings, including
+    // the self-signed certificate authority, were accepted
+    let mut config =
+        RpcConfig::from(Endpoint::new("https".to_string(), "localhost".to_string(), 1));
+    config.max_retries = 0;
+    config.tls = Some(TlsConfig {
+        ca_certificate: Some(
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/certs/ca.pem"),
+        ),
+        ..TlsConfig::default()
+    });
+
+    let mut rpc_client = TonicRpcClient::try_from(&config).unwrap();
+    assert!(matches!(
+        rpc_client.get_block_header_by_number(None).await,
+        Err(NodeRpcClientError::ConnectionError(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_replay_recorded_requests() {
+    let recording_path = create_test_store_path().with_extension("jsonl");
+    let nullifiers = [Digest::new([Felt::new(1); 4])];
+
+    // record a session against the mocked node
+    let mut rpc_api = RecordingRpcClient::new(MockRpcApi::default(), &recording_path).unwrap();
+    let sync_info = rpc_api.sync_state(0, &[], &[], &[]).await.unwrap();
+    let genesis = rpc_api.get_block_header_by_number(Some(0)).await.unwrap();
+    let proofs = rpc_api.check_nullifiers(&nullifiers).await.unwrap();
+    drop(rpc_api);
+
+    // and serve the same responses back without it
+    let mut rpc_api = ReplayRpcClient::from_file(&recording_path).unwrap();
+    assert_eq!(rpc_api.remaining_interactions(), 3);
+
+    assert_eq!(rpc_api.check_nullifiers(&nullifiers).await.unwrap(), proofs);
+    assert_eq!(rpc_api.get_block_header_by_number(Some(0)).await.unwrap(), genesis);
+
+    let replayed_sync_info = rpc_api.sync_state(0, &[], &[], &[]).await.unwrap();
+    assert_eq!(replayed_sync_info.chain_tip, sync_info.chain_tip);
+    assert_eq!(replayed_sync_info.block_header, sync_info.block_header);
+    assert_eq!(replayed_sync_info.mmr_delta, sync_info.mmr_delta);
+    assert_eq!(replayed_sync_info.account_hash_updates, sync_info.account_hash_updates);
+    assert_eq!(replayed_sync_info.nullifiers, sync_info.nullifiers);
+    assert_eq!(replayed_sync_info.note_inclusions.len(), sync_info.note_inclusions.len());
+    assert_eq!(rpc_api.remaining_interactions(), 0);
+
+    // each recorded response is served only once
+    assert!(matches!(
+        rpc_api.get_block_header_by_number(Some(0)).await,
+        Err(NodeRpcClientError::RecordingError(_))
+    ));
+}
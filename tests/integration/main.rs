@@ -3,12 +3,12 @@ use std::{collections::BTreeMap, env::temp_dir, time::Duration};
 use miden_client::{
     client::{
         accounts::{AccountStorageMode, AccountTemplate},
-        get_random_coin,
-        rpc::TonicRpcClient,
+        rpc::{FailoverRpcClient, TonicRpcClient},
+        sync::SyncConfig,
         transactions::transaction_request::{
             PaymentTransactionData, TransactionRequest, TransactionTemplate,
         },
-        Client,
+        Client, ClientBuilder,
     },
     config::{ClientConfig, RpcConfig},
     errors::{ClientError, NodeRpcClientError},
@@ -32,7 +32,7 @@ use uuid::Uuid;
 
 pub const ACCOUNT_ID_REGULAR: u64 = ACCOUNT_ID_REGULAR_ACCOUNT_UPDATABLE_CODE_OFF_CHAIN;
 
-type TestClient = Client<TonicRpcClient, RpoRandomCoin, SqliteStore>;
+type TestClient = Client<FailoverRpcClient<TonicRpcClient>, RpoRandomCoin, SqliteStore>;
 
 fn create_test_client() -> TestClient {
     let client_config = ClientConfig {
@@ -43,13 +43,10 @@ fn create_test_client() -> TestClient {
             .try_into()
             .unwrap(),
         rpc: RpcConfig::default(),
+        sync: SyncConfig::default(),
     };
 
-    let rpc_endpoint = client_config.rpc.endpoint.to_string();
-    let store = SqliteStore::new((&client_config).into()).unwrap();
-    let executor_store = SqliteStore::new((&client_config).into()).unwrap();
-    let rng = get_random_coin();
-    TestClient::new(TonicRpcClient::new(&rpc_endpoint), rng, store, executor_store).unwrap()
+    ClientBuilder::from_config(&client_config).unwrap().build().unwrap()
 }
 
 fn create_test_store_path() -> std::path::PathBuf {